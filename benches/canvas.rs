@@ -56,7 +56,7 @@ mod tests {
     }
 
     impl EventListener for CanvasBencher {
-        fn on_event(&mut self, event: &Event) {
+        fn on_event(&mut self, event: &Event) -> EventResult {
             if let Event::WindowResized { size, ty } = event {
                 self.msg = format!(
                     "Window resized to {}x{}, type: {:?}",
@@ -64,6 +64,7 @@ mod tests {
                 );
                 self.this.update();
             }
+            EventResult::Ignored
         }
     }
 
@@ -1,3 +1,4 @@
+#![allow(clippy::bool_assert_comparison)]
 
 
 #[cfg(test)]
@@ -0,0 +1,297 @@
+//! This file contains the ordering of bidirectional text shared by all the backends.
+//! The lines of a text are split into runs with the Unicode bidirectional algorithm,
+//! and the right-to-left runs are reversed, so that the surfaces draw every line from left
+//! to right. The `CaretMap` tells where the positions of a line are shown, for the carets.
+
+use unicode_bidi::ParagraphBidiInfo;
+
+use crate::*;
+
+/// A run of a line that goes in one direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VisualRun {
+    /// The byte range of the run in the text.
+    pub start: usize,
+    pub end: usize,
+    pub rtl: bool,
+}
+
+/// Split the line `start..end` of `text` into runs that go in one direction, in the order
+/// they are shown from left to right. A line that holds several paragraphs shows them one
+/// after the other.
+/// Also tell if the (first) paragraph of the line goes from right to left.
+pub(crate) fn visual_runs(text: &str, start: usize, end: usize) -> (Vec<VisualRun>, bool) {
+    let mut runs = Vec::new();
+    let mut rtl = None;
+    let mut para_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    loop {
+        // the paragraph holds its newline
+        let para_end = text[para_start..]
+            .find('\n')
+            .map_or(text.len(), |i| para_start + i + 1);
+        let para = &text[para_start..para_end];
+        let line = start.max(para_start)..end.min(para_end);
+        // most texts have no right-to-left char, so they are not analysed
+        let info = (!para.is_ascii()).then(|| ParagraphBidiInfo::new(para, None));
+        rtl.get_or_insert(
+            info.as_ref()
+                .is_some_and(|info| info.paragraph_level.is_rtl()),
+        );
+        match info {
+            Some(info) if info.has_rtl() && !line.is_empty() => {
+                let line = line.start - para_start..line.end - para_start;
+                let (levels, para_runs) = info.visual_runs(line);
+                runs.extend(para_runs.into_iter().map(|run| VisualRun {
+                    start: para_start + run.start,
+                    end: para_start + run.end,
+                    rtl: levels[run.start].is_rtl(),
+                }));
+            }
+            _ => runs.push(VisualRun {
+                start: line.start,
+                end: line.end,
+                rtl: false,
+            }),
+        }
+        if para_end >= end {
+            break;
+        }
+        para_start = para_end;
+    }
+    (runs, rtl.unwrap_or(false))
+}
+
+/// Get the text of `start..end` as it is shown from left to right: shaped, and reversed if
+/// it goes from right to left.
+pub(crate) fn run_text(text: &str, start: usize, end: usize, rtl: bool) -> String {
+    let shaped = shape(text, start, end);
+    if !rtl {
+        return shaped.into_owned();
+    }
+    // the marks stay after the char they are drawn over
+    let mut clusters = Vec::new();
+    let mut pos = 0;
+    while pos < shaped.len() {
+        let end = cluster_end(&shaped, pos, shaped.len());
+        clusters.push(&shaped[pos..end]);
+        pos = end;
+    }
+    clusters
+        .into_iter()
+        .rev()
+        .flat_map(|cluster| cluster.chars().map(mirror))
+        .collect()
+}
+
+/// Get the line `start..end` of `text` as it is shown from left to right.
+/// Also tell if the paragraph of the line goes from right to left.
+pub(crate) fn visual_text(text: &str, start: usize, end: usize) -> (String, bool) {
+    let (runs, rtl) = visual_runs(text, start, end);
+    let shown = runs
+        .iter()
+        .map(|run| run_text(text, run.start, run.end, run.rtl))
+        .collect();
+    (shown, rtl)
+}
+
+/// Get the char shown for `ch` in a right-to-left run, where the brackets face the other way.
+fn mirror(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        ch => ch,
+    }
+}
+
+/// A cluster of chars of a line, as it is shown.
+struct Cell {
+    start: usize,
+    end: usize,
+    rtl: bool,
+    left: i32,
+    right: i32,
+}
+
+/// Where the positions of a single line of text are shown, from left to right.
+/// Right-to-left runs are shown reversed, so moving the caret one step to the left or to the
+/// right does not always move it to the previous or the next char of the text.
+pub(crate) struct CaretMap {
+    cells: Vec<Cell>,
+    /// The caret stops from left to right, with their x and the position of the text.
+    stops: Vec<(i32, usize)>,
+    len: usize,
+    rtl: bool,
+}
+
+impl CaretMap {
+    /// Lay out the line `text`, with the width of the shown text given by `measure`.
+    /// If `bidi` is false, the text is shown as it is, from left to right.
+    pub fn new(text: &str, bidi: bool, measure: impl Fn(&str) -> i32) -> Self {
+        let (runs, rtl) = match bidi {
+            true => visual_runs(text, 0, text.len()),
+            false => (
+                vec![VisualRun {
+                    start: 0,
+                    end: text.len(),
+                    rtl: false,
+                }],
+                false,
+            ),
+        };
+        let shown = |start, end| match bidi {
+            true => shape(text, start, end),
+            false => text[start..end].into(),
+        };
+        let mut cells = Vec::new();
+        let mut x = 0;
+        for run in runs {
+            let first = cells.len();
+            let mut pos = run.start;
+            // the widths of the clusters add up, so that every cluster is measured once
+            let mut right = x;
+            while pos < run.end {
+                let end = match bidi {
+                    true => cluster_end(text, pos, run.end),
+                    false => next_char_pos(text, pos),
+                };
+                let left = right;
+                right += measure(&shown(pos, end));
+                cells.push(Cell {
+                    start: pos,
+                    end,
+                    rtl: run.rtl,
+                    left,
+                    right,
+                });
+                pos = end;
+            }
+            if run.rtl {
+                // the clusters are shown from the right of the run
+                for cell in &mut cells[first..] {
+                    (cell.left, cell.right) = (x + right - cell.right, x + right - cell.left);
+                }
+                cells[first..].reverse();
+            }
+            x = right;
+        }
+        // the position after a cell, on its right, and the one before it, on its left
+        let after = |cell: &Cell| match cell.rtl {
+            true => cell.start,
+            false => cell.end,
+        };
+        let before = |cell: &Cell| match cell.rtl {
+            true => cell.end,
+            false => cell.start,
+        };
+        let (first, last) = match rtl {
+            true => (text.len(), 0),
+            false => (0, text.len()),
+        };
+        let mut stops = Vec::with_capacity(cells.len() + 1);
+        for i in 0..=cells.len() {
+            let left = i.checked_sub(1).map(|i| &cells[i]);
+            let pos = match (left, cells.get(i)) {
+                // the ends of the line are the start and the end of the text
+                (None, _) => first,
+                (_, None) => last,
+                // between two runs, the stop belongs to the one that goes in the line direction
+                (Some(left), Some(right)) if left.rtl != right.rtl => match left.rtl == rtl {
+                    true => after(left),
+                    false => before(right),
+                },
+                (Some(left), Some(_)) => after(left),
+            };
+            stops.push((left.map_or(0, |cell| cell.right), pos));
+        }
+        Self {
+            cells,
+            stops,
+            len: text.len(),
+            rtl,
+        }
+    }
+
+    /// Get the width of the line.
+    pub fn width(&self) -> i32 {
+        self.stops.last().map_or(0, |(x, _)| *x)
+    }
+
+    /// Get the x of the caret before the char at `pos`, or at the end of the line.
+    pub fn x_of(&self, pos: usize) -> i32 {
+        if let Some((x, _)) = self.stops.iter().find(|(_, stop)| *stop == pos) {
+            return *x;
+        }
+        let cell = self.cells.iter().find(|cell| match cell.rtl {
+            true => cell.end == pos,
+            false => cell.start == pos,
+        });
+        match cell {
+            Some(cell) if cell.rtl => cell.right,
+            Some(cell) => cell.left,
+            None if pos >= self.len && self.rtl => 0,
+            None => self.width(),
+        }
+    }
+
+    /// Get the position of the caret stop nearest to `x`.
+    pub fn pos_at(&self, x: i32) -> usize {
+        let mut nearest = (i32::MAX, 0);
+        for (stop_x, pos) in &self.stops {
+            if (stop_x - x).abs() <= nearest.0 {
+                nearest = ((stop_x - x).abs(), *pos);
+            }
+        }
+        nearest.1
+    }
+
+    /// Get the position one step to the left of `pos`, or to the right if `right` is true.
+    pub fn step(&self, pos: usize, right: bool) -> usize {
+        let x = self.x_of(pos);
+        let index = self
+            .stops
+            .iter()
+            .position(|(_, stop)| *stop == pos)
+            .or_else(|| self.stops.iter().position(|(stop_x, _)| *stop_x == x))
+            .unwrap_or(0);
+        let index = match right {
+            true => (index + 1).min(self.stops.len() - 1),
+            false => index.saturating_sub(1),
+        };
+        self.stops[index].1
+    }
+
+    /// Get the x of every caret stop, from left to right.
+    pub fn edges(&self) -> impl Iterator<Item = i32> + '_ {
+        self.stops.iter().map(|(x, _)| *x)
+    }
+
+    /// Get the left and the right of the parts of the line that show the text from `start`
+    /// to `end`, from left to right.
+    pub fn spans(&self, start: usize, end: usize) -> Vec<(i32, i32)> {
+        let mut spans: Vec<(i32, i32)> = Vec::new();
+        for cell in &self.cells {
+            if cell.start < start || cell.end > end {
+                continue;
+            }
+            match spans.last_mut() {
+                Some(last) if last.1 == cell.left => last.1 = cell.right,
+                _ => spans.push((cell.left, cell.right)),
+            }
+        }
+        spans
+    }
+}
+
+fn next_char_pos(text: &str, pos: usize) -> usize {
+    pos + text[pos..].chars().next().map_or(0, char::len_utf8)
+}
@@ -1,8 +1,6 @@
 //! This file contains the implementation of the Canvas struct and its dependent structs.
 //! The `Canvas` struct is used to draw shapes and text on the screen.
 
-use std::os::raw::c_void;
-
 use crate::*;
 
 /// The `LineStyle` is used to specify the style of the line.
@@ -57,45 +55,64 @@ impl Default for PenStyle {
 /// The `Pen` is used to specify the style of the pen.
 #[derive(Clone)]
 pub struct Pen {
-    hpen: *mut c_void,
+    pub(crate) hpen: Handle,
+    pub(crate) style: PenStyle,
 }
 impl Drop for Pen {
     fn drop(&mut self) {
-        delete_object(self.hpen);
+        if !self.hpen.is_null() {
+            Platform::delete_object(self.hpen);
+        }
     }
 }
 impl Pen {
     /// Create a new `Pen` with `style`.
     pub fn new(style: PenStyle) -> Self {
         Self {
-            hpen: new_pen_object(style),
+            hpen: Platform::new_pen(&style),
+            style,
         }
     }
+
+    /// Get the style of the pen.
+    pub fn style(&self) -> PenStyle {
+        self.style
+    }
 }
 
 /// The `Brush` is used to specify the color of the brush.
 #[derive(Clone)]
 pub struct Brush {
-    hbrush: *mut c_void,
+    pub(crate) hbrush: Handle,
+    pub(crate) color: Color,
 }
 impl Drop for Brush {
     fn drop(&mut self) {
-        delete_object(self.hbrush);
+        if !self.hbrush.is_null() {
+            Platform::delete_object(self.hbrush);
+        }
     }
 }
 impl Brush {
     /// Create a new `Brush` with `color`.
     pub fn new(color: Color) -> Self {
         Self {
-            hbrush: new_brush_object(BrushParam::Solid(color)),
+            hbrush: Platform::new_brush(color),
+            color,
         }
     }
+
+    /// Get the color of the brush.
+    pub fn color(&self) -> Color {
+        self.color
+    }
 }
 
 /// The `Font` is used to specify the font.
 #[derive(Clone)]
 pub struct Font {
-    pub(crate) hfont: *mut c_void,
+    pub(crate) hfont: Handle,
+    pub(crate) style: FontStyle,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -113,7 +130,7 @@ pub enum FontWeight {
 }
 
 /// The `FontStyle` is used to specify the font style.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FontStyle {
     pub size: i32,          // Font size
     pub weight: FontWeight, // Font weight
@@ -125,7 +142,9 @@ pub struct FontStyle {
 
 impl Drop for Font {
     fn drop(&mut self) {
-        delete_object(self.hfont);
+        if !self.hfont.is_null() {
+            Platform::delete_object(self.hfont);
+        }
     }
 }
 
@@ -142,11 +161,50 @@ impl Default for FontStyle {
     }
 }
 
+/// The vertical measures of a font, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FontMetrics {
+    /// The height of the font above the baseline.
+    pub ascent: i32,
+    /// The depth of the font below the baseline.
+    pub descent: i32,
+    /// The distance between the baselines of two lines of text.
+    pub line_height: i32,
+}
+
 impl Font {
     /// Create a new `Font` with `style`.
     pub fn new(style: FontStyle) -> Self {
-        let hfont = new_font_object(style);
-        Self { hfont }
+        let hfont = Platform::new_font(&style);
+        Self { hfont, style }
+    }
+
+    /// Get the style of the font.
+    pub fn style(&self) -> &FontStyle {
+        &self.style
+    }
+
+    /// Get the size of the box taken by the single-line `text` drawn with the font.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let font = Font::new(FontStyle::default());
+    /// let size = font.measure("Hello");
+    /// assert!(size.width > font.measure("Hi").width);
+    /// assert_eq!(size.height, font.metrics().line_height);
+    /// ```
+    pub fn measure(&self, text: &str) -> Size {
+        Platform::text_extent(&self.style, text)
+    }
+
+    /// Get how far the pen moves to the right after drawing `ch`.
+    pub fn advance(&self, ch: char) -> i32 {
+        self.measure(ch.encode_utf8(&mut [0; 4])).width
+    }
+
+    pub fn metrics(&self) -> FontMetrics {
+        Platform::font_metrics(&self.style)
     }
 }
 
@@ -164,6 +222,7 @@ pub enum TextAlign {
     RightBottom,
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for TextAlign {
     fn default() -> Self {
         Self::LeftTop
@@ -171,12 +230,39 @@ impl Default for TextAlign {
 }
 
 /// The `Canvas` is used to draw shapes and text on the screen.
+/// It can also draw into an offscreen image, see `Canvas::offscreen`.
 pub struct Canvas {
-    pub(crate) hdc: *mut c_void,
+    pub(crate) surface: Box<dyn Surface>,
     pub(crate) rect: Rect,
 }
 
 impl Canvas {
+    /// Create a canvas that draws into a transparent image of `size` with the software renderer.
+    /// It works on every platform and does not need a window.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let canvas = Canvas::offscreen(size!(100, 100));
+    /// canvas.clear(Color::WHITE);
+    /// canvas.set_brush(&Brush::new(Color::RED));
+    /// canvas.fill_rect(rect!(10, 10, 80, 80));
+    /// let image = canvas.into_image().unwrap();
+    /// assert_eq!(image.pixel(pos!(50, 50)), Some(Color::RED));
+    /// ```
+    pub fn offscreen(size: Size) -> Self {
+        Self {
+            surface: Box::new(Raster::new(size, Color::TRANSPARENT)),
+            rect: rect!(0, 0, size.width, size.height),
+        }
+    }
+
+    /// Get the image drawn by an offscreen canvas.
+    /// Return None if the canvas draws on the screen.
+    pub fn into_image(self) -> Option<Image> {
+        self.surface.into_image()
+    }
+
     /// Clear current widget content with `color`.
     /// # Example
     /// ```
@@ -196,7 +282,7 @@ impl Canvas {
     /// }
     /// ```
     pub fn clear(&self, color: Color) {
-        clear_device(self.hdc, self.rect, color);
+        self.surface.clear(self.rect, color);
     }
 
     /// Set current pen to `pen`.
@@ -225,9 +311,7 @@ impl Canvas {
     /// }
     /// ```
     pub fn set_pen(&self, pen: &Pen) -> Pen {
-        Pen {
-            hpen: select_object(self.hdc, pen.hpen) as *mut c_void,
-        }
+        self.surface.set_pen(pen)
     }
 
     /// Set current brush to `brush`.
@@ -250,9 +334,7 @@ impl Canvas {
     /// }
     /// ```
     pub fn set_brush(&self, brush: &Brush) -> Brush {
-        Brush {
-            hbrush: select_object(self.hdc, brush.hbrush) as *mut c_void,
-        }
+        self.surface.set_brush(brush)
     }
 
     /// Set current font to `font`.
@@ -280,9 +362,7 @@ impl Canvas {
     /// }
     /// ```
     pub fn set_font(&self, font: &Font) -> Font {
-        Font {
-            hfont: select_object(self.hdc, font.hfont) as *mut c_void,
-        }
+        self.surface.set_font(font)
     }
     
     /// Set the text color to `color`.
@@ -304,108 +384,312 @@ impl Canvas {
     /// }
     /// ```
     pub fn set_text_color(&self, color: Color) -> Color {
-        set_current_text_color(self.hdc, color)
+        self.surface.set_text_color(color)
     }
 
     /// Draw a line from `(x1, y1)` to `(x2, y2)`.
     /// It uses the current pen.
     pub fn line(&self, x1: i32, y1: i32, x2: i32, y2: i32) {
-        draw_line(self.hdc, x1, y1, x2, y2);
+        self.surface.line(x1, y1, x2, y2);
     }
 
     /// Draw a rectangle with `rect`.
     /// It uses the current pen.
     pub fn rect(&self, rect: Rect) {
-        draw_rect(self.hdc, rect);
+        self.surface.rect(rect);
     }
 
     /// Draw a rounded rectangle with `rect` and `rx` and `ry`.
     /// It uses the current pen.
     pub fn round_rect(&self, rect: Rect, rx: i32, ry: i32) {
-        draw_round_rect(self.hdc, rect, rx, ry);
+        self.surface.round_rect(rect, rx, ry);
     }
 
     /// Draw a polygon with `points`.
     /// It uses the current pen.
     pub fn polyline(&self, points: &[Point]) {
-        draw_polyline(self.hdc, points);
+        self.surface.polyline(points);
     }
 
     /// Draw a polygon with `points`.
     /// It uses the current pen.
     pub fn polygon(&self, points: &[Point]) {
-        draw_polygon(self.hdc, points);
+        self.surface.polygon(points);
     }
 
     /// Draw an arc with `rect`, `start` and `sweep`.
     /// It uses the current pen.
     pub fn arc(&self, rect: Rect, start: f32, sweep: f32) {
-        draw_arc(self.hdc, rect, start, sweep);
+        self.surface.arc(rect, start, sweep);
     }
 
     /// Draw a pie with `rect`, `start` and `sweep`.
     /// It uses the current pen.
     pub fn pie(&self, rect: Rect, start: f32, sweep: f32) {
-        draw_pie(self.hdc, rect, start, sweep);
+        self.surface.pie(rect, start, sweep);
     }
 
     /// Draw an ellipse with `rect`.
     /// It uses the current pen.
     pub fn ellipse(&self, rect: Rect) {
-        draw_ellipse(self.hdc, rect);
+        self.surface.ellipse(rect);
     }
 
     /// Draw a circle with `pos` and `radius`.
     /// It uses the current pen.
     pub fn circle(&self, pos: Point, radius: i32) {
-        draw_circle(self.hdc, pos, radius);
+        self.surface.circle(pos, radius);
     }
 
     /// Draw a fill rectangle with `rect`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_rect(&self, rect: Rect) {
-        draw_fill_rect(self.hdc, rect);
+        self.surface.fill_rect(rect);
     }
 
     /// Draw a fill rounded rectangle with `rect` and `rx` and `ry`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_round_rect(&self, rect: Rect, rx: i32, ry: i32) {
-        draw_fill_round_rect(self.hdc, rect, rx, ry);
+        self.surface.fill_round_rect(rect, rx, ry);
     }
 
     /// Draw a fill polygon with `points`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_polygon(&self, points: &[Point]) {
-        draw_fill_polygon(self.hdc, points);
+        self.surface.fill_polygon(points);
     }
 
     /// Draw a fill pie with `rect`, `start` and `sweep`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_pie(&self, rect: Rect, start: f32, sweep: f32) {
-        draw_fill_pie(self.hdc, rect, start, sweep);
+        self.surface.fill_pie(rect, start, sweep);
     }
 
     /// Draw a fill ellipse with `rect`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_ellipse(&self, rect: Rect) {
-        draw_fill_ellipse(self.hdc, rect);
+        self.surface.fill_ellipse(rect);
     }
 
     /// Draw a fill circle with `pos` and `radius`.
     /// It uses the current pen for outline and brush for fill.
     pub fn fill_circle(&self, pos: Point, radius: i32) {
-        draw_fill_circle(self.hdc, pos, radius);
+        self.surface.fill_circle(pos, radius);
+    }
+
+    /// Get the size of the box taken by the single-line `text` drawn with the current font.
+    /// The text is measured as it is shown, with its Arabic letters joined.
+    pub fn text_extent(&self, text: &str) -> Size {
+        self.surface.text_extent(&shape(text, 0, text.len()))
+    }
+
+    /// Get the size of the box taken by `text` laid out with `format`.
+    /// The lines are wrapped and truncated at `width` if there is one.
+    pub fn formatted_text_extent(
+        &self,
+        text: &str,
+        width: Option<i32>,
+        format: &TextFormat,
+    ) -> Size {
+        self.layout(text, format, (width, None)).size()
     }
 
     /// Draw a text with `pos` and `text`.
+    /// The text can hold several lines, `align` places the block of lines around `pos`.
     /// It uses the current text color, and font.
     pub fn xy_text(&self, pos: Point, text: &str, align: TextAlign) {
-        draw_xy_text(self.hdc, pos, text, align);
+        let layout = self.layout(text, &align.into(), (None, None));
+        let rect = rect!(pos.x, pos.y, 0, 0);
+        for (line, pos) in layout.lines.iter().zip(layout.place(rect, align)) {
+            self.surface.text_line(pos, &Self::shown(text, line), None);
+        }
     }
 
     /// Draw a text with `rect` and `text`.
+    /// The text can hold several lines, it is clipped to `rect`.
     /// It uses the current text color, and font.
     pub fn rect_text(&self, rect: Rect, text: &str, align: TextAlign) {
-        draw_rect_text(self.hdc, rect, text, align);
+        self.formatted_text(rect, text, &align.into());
+    }
+
+    /// Draw a text in `rect`, wrapped, spaced and truncated as `format` says.
+    /// It is clipped to `rect`, and uses the current text color, and font.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let canvas = Canvas::offscreen(size!(200, 100));
+    /// let format = TextFormat {
+    ///     align: TextAlign::Center,
+    ///     wrap: true,
+    ///     line_spacing: 4,
+    ///     ellipsis: true,
+    /// };
+    /// canvas.formatted_text(rect!(0, 0, 200, 100), "A long label\non two lines", &format);
+    /// ```
+    pub fn formatted_text(&self, rect: Rect, text: &str, format: &TextFormat) {
+        let bounds = (Some(rect.size.width), Some(rect.size.height));
+        let layout = self.layout(text, format, bounds);
+        for (line, pos) in layout.lines.iter().zip(layout.place(rect, format.align)) {
+            self.surface.text_line(pos, &Self::shown(text, line), Some(rect));
+        }
+    }
+
+    /// Draw `text` in `rect`, each run with its own style.
+    /// The runs are laid out across lines as `format` says, which can be just a `TextAlign`,
+    /// and the lines are clipped to `rect`. The runs of a line sit on the same baseline.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let error = TextStyle {
+    ///     color: Color::RED,
+    ///     background: Some(Color::YELLOW),
+    ///     ..Default::default()
+    /// };
+    /// let mut log = RichText::new();
+    /// log.push("[info] started\n", &TextStyle::default());
+    /// log.push("[error] failed", &error);
+    ///
+    /// let canvas = Canvas::offscreen(size!(200, 100));
+    /// canvas.rich_text(rect!(0, 0, 200, 100), &log, TextAlign::LeftTop);
+    /// ```
+    pub fn rich_text(&self, rect: Rect, text: &RichText, format: impl Into<TextFormat>) {
+        let format = format.into();
+        let measure = RichMeasure::new(text);
+        let bounds = (Some(rect.size.width), Some(rect.size.height));
+        let layout = TextLayout::new(text.text(), &format, bounds, &measure);
+        let Some((font, _)) = measure.fonts.first() else {
+            return;
+        };
+        // the pen and the brush of each background, made once for all the runs
+        let mut fills: Vec<(Color, Pen, Brush)> = Vec::new();
+        for run in 0..measure.fonts.len() {
+            if let Some(background) = text.run_style(run).background {
+                if !fills.iter().any(|(color, ..)| *color == background) {
+                    // the outline is drawn with the color of the background too
+                    let pen = Pen::new(PenStyle {
+                        color: background,
+                        ..PenStyle::default()
+                    });
+                    fills.push((background, pen, Brush::new(background)));
+                }
+            }
+        }
+        // The objects handed back by the selections below are owned by `fills`, by `measure`
+        // or by whoever selected them before, so they are forgotten instead of deleted.
+        let mut old_fill = None;
+        let (old_font, old_color) = (self.set_font(font), self.set_text_color(Color::BLACK));
+        for (line, pos) in layout.lines.iter().zip(layout.place(rect, format.align)) {
+            // the pieces of the line from left to right, with the direction of their run
+            let (runs, rtl) = visual_runs(text.text(), line.start, line.end);
+            let mut pieces = Vec::new();
+            for run in runs {
+                let mut run_pieces = text.pieces(run.start, run.end);
+                if run.rtl {
+                    run_pieces.reverse();
+                }
+                pieces.extend(run_pieces.into_iter().map(|piece| (piece, run.rtl)));
+            }
+            if line.ellipsis {
+                let ellipsis = ((measure.run_before(line.end), 0..0), false);
+                match rtl {
+                    true => pieces.insert(0, ellipsis),
+                    false => pieces.push(ellipsis),
+                }
+            }
+            let mut x = pos.x;
+            for ((run, piece), rtl) in pieces {
+                let shown = match piece.is_empty() {
+                    true => String::from(ELLIPSIS),
+                    false => run_text(text.text(), piece.start, piece.end, rtl),
+                };
+                let style = text.run_style(run);
+                let (font, metrics) = &measure.fonts[run];
+                let width = font.measure(&shown).width;
+                let fill = style
+                    .background
+                    .and_then(|background| fills.iter().find(|(color, ..)| *color == background));
+                let back = rect!(x, pos.y, width, line.height) & rect;
+                if let (Some((_, pen, brush)), Some(back)) = (fill, back) {
+                    let old = (self.set_pen(pen), self.set_brush(brush));
+                    match old_fill {
+                        None => old_fill = Some(old),
+                        Some(_) => std::mem::forget(old),
+                    }
+                    self.fill_rect(back);
+                }
+                std::mem::forget(self.set_font(font));
+                self.set_text_color(style.color);
+                let y = pos.y + line.ascent - metrics.ascent;
+                self.surface.text_line(pos!(x, y), &shown, Some(rect));
+                x += width;
+            }
+        }
+        if let Some((pen, brush)) = old_fill {
+            std::mem::forget(self.set_pen(&pen));
+            std::mem::forget(self.set_brush(&brush));
+            std::mem::forget((pen, brush));
+        }
+        std::mem::forget(self.set_font(&old_font));
+        std::mem::forget(old_font);
+        self.set_text_color(old_color);
+    }
+
+    /// Draw the single line `text` in the middle of `rect`, scrolled `scroll` pixels to the
+    /// left and clipped to `rect`, as editing widgets show their content.
+    pub(crate) fn scrolled_text(&self, rect: Rect, scroll: i32, text: &str) {
+        let layout = self.layout(text, &TextAlign::LeftMiddle.into(), (None, None));
+        let (x, y, w, h) = rect.into();
+        let shifted = rect!(x - scroll, y, w, h);
+        for (line, pos) in layout.lines.iter().zip(layout.place(shifted, TextAlign::LeftMiddle)) {
+            self.surface.text_line(pos, &Self::shown(text, line), Some(rect));
+        }
+    }
+
+    fn layout(
+        &self,
+        text: &str,
+        format: &TextFormat,
+        bounds: (Option<i32>, Option<i32>),
+    ) -> TextLayout {
+        let measure = PlainMeasure {
+            surface: self.surface.as_ref(),
+            text,
+            metrics: self.surface.font_metrics(),
+        };
+        TextLayout::new(text, format, bounds, &measure)
+    }
+
+    /// Get the text shown on `line` from left to right, with its ellipsis at the end of it
+    /// in the direction of the paragraph.
+    fn shown(text: &str, line: &TextLine) -> String {
+        let (shown, rtl) = visual_text(text, line.start, line.end);
+        match (line.ellipsis, rtl) {
+            (false, _) => shown,
+            (true, false) => format!("{}{}", shown, ELLIPSIS),
+            (true, true) => format!("{}{}", ELLIPSIS, shown),
+        }
+    }
+}
+
+/// Measures a text with the font selected in a surface.
+struct PlainMeasure<'a> {
+    surface: &'a dyn Surface,
+    text: &'a str,
+    metrics: FontMetrics,
+}
+
+impl TextMeasure for PlainMeasure<'_> {
+    fn width(&self, start: usize, end: usize) -> i32 {
+        self.surface.text_extent(&shape(self.text, start, end)).width
+    }
+
+    fn ellipsis_width(&self, _end: usize) -> i32 {
+        self.surface.text_extent(ELLIPSIS).width
+    }
+
+    fn metrics(&self, _start: usize, _end: usize) -> FontMetrics {
+        self.metrics
     }
 }
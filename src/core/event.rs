@@ -1,43 +1,48 @@
 //! This file is used to define the Event enum and its variants.
 
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+
 use crate::HotKeyFlags;
 use crate::Point;
 use crate::Size;
 
 /// The Event enum represents all possible events that can occur in a GUI application.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Event {
     KeyPressed {
         key: KeyCode,
         sys: bool,
+        mods: Modifiers,
     },
     KeyReleased {
         key: KeyCode,
         sys: bool,
+        mods: Modifiers,
     },
     MouseMoved {
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
     MouseButtonPressed {
         button: MouseButton,
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
     MouseButtonReleased {
         button: MouseButton,
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
     MouseWheelScrolled {
-        wheel: MouseWheel,
+        delta: ScrollDelta,
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
     MouseDoubleClicked {
         button: MouseButton,
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
 
     WindowCreated,
@@ -67,9 +72,73 @@ pub enum Event {
 
     Hover {
         pos: Point,
-        mk: ModifierKey,
+        mods: Modifiers,
     },
     Leave,
+
+    /// The window got the keyboard focus.
+    FocusIn,
+    /// The window lost the keyboard focus.
+    FocusOut,
+}
+
+impl Event {
+    /// Check if the event comes from the keyboard or the mouse.
+    /// Input events go through the capture and bubble phases: the ancestors of the target see
+    /// them first with `EventListener::on_capture`, and they get them with `on_event` when the
+    /// target ignores them.
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            Event::KeyPressed { .. }
+                | Event::KeyReleased { .. }
+                | Event::Input { .. }
+                | Event::MouseMoved { .. }
+                | Event::MouseButtonPressed { .. }
+                | Event::MouseButtonReleased { .. }
+                | Event::MouseWheelScrolled { .. }
+                | Event::MouseDoubleClicked { .. }
+        )
+    }
+
+    /// Get the modifier keys and the mouse buttons held down when the event occurred,
+    /// if the event tells them.
+    pub fn modifiers(&self) -> Option<Modifiers> {
+        match self {
+            Event::KeyPressed { mods, .. }
+            | Event::KeyReleased { mods, .. }
+            | Event::MouseMoved { mods, .. }
+            | Event::MouseButtonPressed { mods, .. }
+            | Event::MouseButtonReleased { mods, .. }
+            | Event::MouseWheelScrolled { mods, .. }
+            | Event::MouseDoubleClicked { mods, .. }
+            | Event::Hover { mods, .. } => Some(*mods),
+            _ => None,
+        }
+    }
+
+    /// Get the event with its position moved by `offset`, as a parent window sees it.
+    pub(crate) fn translated(&self, offset: Point) -> Event {
+        let mut event = *self;
+        match &mut event {
+            Event::MouseMoved { pos, .. }
+            | Event::MouseButtonPressed { pos, .. }
+            | Event::MouseButtonReleased { pos, .. }
+            | Event::MouseWheelScrolled { pos, .. }
+            | Event::MouseDoubleClicked { pos, .. }
+            | Event::Hover { pos, .. } => *pos += offset,
+            _ => {}
+        }
+        event
+    }
+}
+
+/// Tells if an element handled an event.
+/// An input event that is handled stops there, one that is ignored goes on to the parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventResult {
+    Handled,
+    Ignored,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -105,6 +174,8 @@ pub enum KeyCode {
     RightCtrl,  // Right Ctrl
     RightShift, // Right Shift
     RightAlt,   // Right Alt
+    LeftWin,    // Left Windows key
+    RightWin,   // Right Windows key
     ScrollLock, // ScrollLock
     NumLock,    // NumLock
     Delete,     // Delete(Del)
@@ -137,20 +208,172 @@ pub enum MouseButton {
     Other(u16),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum MouseWheel {
-    Up,
-    Down,
+/// How far a mouse wheel or a touchpad scrolled, in both axes.
+/// Positive values scroll towards the end of the content: down, and to the right.
+/// The distance is given both in lines and in pixels, and precise devices such as touchpads
+/// scroll by fractions of them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScrollDelta {
+    pub lines_x: f32,
+    pub lines_y: f32,
+    pub pixels_x: f32,
+    pub pixels_y: f32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ModifierKey {
-    Shift,
-    Ctrl,
-    Alt,
-    Win,
-    Mouse(MouseButton),
-    None,
+impl ScrollDelta {
+    /// The number of pixels in a line, to convert between the two units.
+    pub const PIXELS_PER_LINE: f32 = 20.0;
+
+    /// Create a delta from a distance in lines, as a mouse wheel scrolls.
+    pub fn lines(x: f32, y: f32) -> Self {
+        Self {
+            lines_x: x,
+            lines_y: y,
+            pixels_x: x * Self::PIXELS_PER_LINE,
+            pixels_y: y * Self::PIXELS_PER_LINE,
+        }
+    }
+
+    /// Create a delta from a distance in pixels, as a touchpad scrolls.
+    pub fn pixels(x: f32, y: f32) -> Self {
+        Self {
+            lines_x: x / Self::PIXELS_PER_LINE,
+            lines_y: y / Self::PIXELS_PER_LINE,
+            pixels_x: x,
+            pixels_y: y,
+        }
+    }
+}
+
+/// The modifier keys and the mouse buttons held down when an input event occurs.
+/// The flags are combined with `|`, e.g. `Modifiers::CTRL | Modifiers::SHIFT`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(u16);
+
+impl Modifiers {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(1 << 0);
+    pub const CTRL: Self = Self(1 << 1);
+    pub const ALT: Self = Self(1 << 2);
+    /// The Windows key, or the Command key on a Mac keyboard.
+    pub const META: Self = Self(1 << 3);
+    pub const LEFT_BUTTON: Self = Self(1 << 4);
+    pub const RIGHT_BUTTON: Self = Self(1 << 5);
+    pub const MIDDLE_BUTTON: Self = Self(1 << 6);
+    pub const X1_BUTTON: Self = Self(1 << 7);
+    pub const X2_BUTTON: Self = Self(1 << 8);
+
+    const KEYS: Self = Self(0b1111);
+    const NAMES: [&'static str; 9] = [
+        "SHIFT",
+        "CTRL",
+        "ALT",
+        "META",
+        "LEFT_BUTTON",
+        "RIGHT_BUTTON",
+        "MIDDLE_BUTTON",
+        "X1_BUTTON",
+        "X2_BUTTON",
+    ];
+
+    /// Get the flag of a mouse button. The extension buttons are `MouseButton::Other(1)` and
+    /// `MouseButton::Other(2)`, and other buttons have no flag.
+    pub const fn from_button(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::LEFT_BUTTON,
+            MouseButton::Right => Self::RIGHT_BUTTON,
+            MouseButton::Middle => Self::MIDDLE_BUTTON,
+            MouseButton::Other(1) => Self::X1_BUTTON,
+            MouseButton::Other(2) => Self::X2_BUTTON,
+            MouseButton::Other(_) => Self::NONE,
+        }
+    }
+
+    /// Get the flag of a modifier key, or `Modifiers::NONE` if `key` is not one.
+    pub const fn from_key(key: KeyCode) -> Self {
+        match key {
+            KeyCode::Shift | KeyCode::LeftShift | KeyCode::RightShift => Self::SHIFT,
+            KeyCode::Ctrl | KeyCode::LeftCtrl | KeyCode::RightCtrl => Self::CTRL,
+            KeyCode::Alt | KeyCode::LeftAlt | KeyCode::RightAlt => Self::ALT,
+            KeyCode::LeftWin | KeyCode::RightWin => Self::META,
+            _ => Self::NONE,
+        }
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Check if all the flags of `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Set the flags of `other` if `value` is true, or clear them otherwise.
+    pub fn set(&mut self, other: Self, value: bool) {
+        match value {
+            true => self.insert(other),
+            false => self.remove(other),
+        }
+    }
+
+    /// Get the modifier keys only, without the mouse buttons.
+    pub const fn keys(self) -> Self {
+        Self(self.0 & Self::KEYS.0)
+    }
+
+    pub const fn shift(self) -> bool {
+        self.contains(Self::SHIFT)
+    }
+
+    pub const fn ctrl(self) -> bool {
+        self.contains(Self::CTRL)
+    }
+
+    pub const fn alt(self) -> bool {
+        self.contains(Self::ALT)
+    }
+
+    pub const fn meta(self) -> bool {
+        self.contains(Self::META)
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Debug for Modifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("NONE");
+        }
+        let mut names = Self::NAMES
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.0 & (1 << i) != 0)
+            .map(|(_, name)| *name);
+        f.write_str(names.next().unwrap_or_default())?;
+        names.try_for_each(|name| write!(f, " | {}", name))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,92 @@
+//! This file contains the keyboard focus policy of the windows and their tab order.
+//! Tab and Shift+Tab move the focus through the windows of a top-level window that take it
+//! with Tab, when the window that has the focus does not use the key.
+
+use std::cell::Cell;
+
+use crate::*;
+
+/// How a window takes the keyboard focus.
+/// `Window::set_focus` gives the focus to a window whatever its policy is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusPolicy {
+    /// The window does not take the focus by itself.
+    #[default]
+    None,
+    /// The window takes the focus when it is clicked.
+    Click,
+    /// The window takes the focus with Tab and Shift+Tab.
+    Tab,
+    /// The window takes the focus when it is clicked, and with Tab and Shift+Tab.
+    Strong,
+}
+
+impl FocusPolicy {
+    /// Check if the window takes the focus when it is clicked.
+    pub fn accepts_click(self) -> bool {
+        matches!(self, FocusPolicy::Click | FocusPolicy::Strong)
+    }
+
+    /// Check if the window takes the focus with Tab and Shift+Tab.
+    pub fn accepts_tab(self) -> bool {
+        matches!(self, FocusPolicy::Tab | FocusPolicy::Strong)
+    }
+}
+
+thread_local! {
+    /// The number of windows created so far, which gives the default tab order.
+    static CREATED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Get the place of a new window in the order of creation.
+pub(crate) fn creation_order() -> u64 {
+    CREATED.with(|created| {
+        created.set(created.get() + 1);
+        created.get()
+    })
+}
+
+/// Get the windows that take the focus with Tab in the top-level window of `window`,
+/// in the tab order. Hidden and disabled windows, and the windows in them, are left out.
+fn tab_chain(window: Handle) -> Vec<Handle> {
+    let mut top = window;
+    while let Some(parent) = Platform::parent_window(top) {
+        top = parent;
+    }
+    let mut chain = Vec::new();
+    let mut pending = Platform::child_windows(top);
+    while let Some(window) = pending.pop() {
+        if !Platform::is_window_visible(window) || !Platform::is_window_enabled(window) {
+            continue;
+        }
+        Platform::visit_window(window, &mut |ele| {
+            let this = ele.as_window();
+            if this.focus_policy().accepts_tab() {
+                // the windows with a tab index come first, then the others as they were created
+                let key = (this.tab_index().is_none(), this.tab_index(), this.order);
+                chain.push((key, window));
+            }
+        });
+        pending.extend(Platform::child_windows(window));
+    }
+    chain.sort_by_key(|(key, _)| *key);
+    chain.into_iter().map(|(_, window)| window).collect()
+}
+
+/// Move the focus from `window` to the next window in the tab order, or to the previous one
+/// if `backwards` is true. The order wraps around at its ends.
+/// Return false if no window takes the focus with Tab.
+pub(crate) fn move_focus(window: Handle, backwards: bool) -> bool {
+    let chain = tab_chain(window);
+    if chain.is_empty() {
+        return false;
+    }
+    let next = match (chain.iter().position(|w| *w == window), backwards) {
+        (Some(i), false) => (i + 1) % chain.len(),
+        (Some(i), true) => (i + chain.len() - 1) % chain.len(),
+        (None, false) => 0,
+        (None, true) => chain.len() - 1,
+    };
+    Platform::set_focus(chain[next]);
+    true
+}
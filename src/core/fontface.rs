@@ -0,0 +1,150 @@
+//! This file contains the implementation of the `FontFace` struct.
+//! A `FontFace` is a TrueType or OpenType font loaded from a file or from memory.
+//! Once loaded, it draws every `FontStyle` that names its family, with the software renderer
+//! on every backend, so the text looks the same everywhere.
+
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ttf_parser::{name_id, Face};
+
+use crate::*;
+
+/// The faces that were loaded, which are looked up by family.
+static FACES: Mutex<Vec<FontFace>> = Mutex::new(Vec::new());
+/// The id of the next face that is loaded. Ids are never reused, so cached glyphs stay valid.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+struct FaceData {
+    id: usize,
+    family: String,
+    bold: bool,
+    italic: bool,
+    data: Vec<u8>,
+}
+
+/// A TrueType or OpenType font.
+/// # Example
+/// ```no_run
+/// use rusty_gui::*;
+///
+/// let face = FontFace::load("fonts/NotoSans-Regular.ttf").unwrap();
+/// // every font of the "Noto Sans" family is now drawn with the face
+/// let font = Font::new(face.style(20));
+/// assert_eq!(font.style().font, "Noto Sans");
+/// ```
+#[derive(Clone)]
+pub struct FontFace {
+    data: Arc<FaceData>,
+}
+
+impl fmt::Debug for FontFace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FontFace")
+            .field("family", &self.data.family)
+            .field("bold", &self.data.bold)
+            .field("italic", &self.data.italic)
+            .finish()
+    }
+}
+
+impl FontFace {
+    /// Load the font file at `path`. See `FontFace::from_bytes`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Load a font from the content of a TrueType or OpenType file.
+    /// The face replaces the one of the same family and style that was loaded before.
+    pub fn from_bytes(data: &[u8]) -> io::Result<Self> {
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let face = Face::parse(data, 0).map_err(|err| invalid(err.to_string()))?;
+        let names = |id| {
+            face.names()
+                .into_iter()
+                .filter(|name| name.name_id == id)
+                .find_map(|name| name.to_string())
+        };
+        let family = names(name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| names(name_id::FAMILY))
+            .ok_or_else(|| invalid("the font has no family name".to_string()))?;
+        let face = Self {
+            data: Arc::new(FaceData {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                family,
+                bold: face.is_bold(),
+                italic: face.is_italic(),
+                data: data.to_vec(),
+            }),
+        };
+        let mut faces = FACES.lock().unwrap();
+        faces.retain(|other| {
+            !other.family().eq_ignore_ascii_case(face.family())
+                || other.is_bold() != face.is_bold()
+                || other.is_italic() != face.is_italic()
+        });
+        faces.push(face.clone());
+        Ok(face)
+    }
+
+    /// Get the family name of the face, which `FontStyle::font` uses to select it.
+    pub fn family(&self) -> &str {
+        &self.data.family
+    }
+
+    pub fn is_bold(&self) -> bool {
+        self.data.bold
+    }
+
+    pub fn is_italic(&self) -> bool {
+        self.data.italic
+    }
+
+    /// Check if the face has a glyph for `ch`.
+    pub fn has_char(&self, ch: char) -> bool {
+        self.face().glyph_index(ch).is_some()
+    }
+
+    /// Get a `FontStyle` of `size` that selects the face.
+    pub fn style(&self, size: i32) -> FontStyle {
+        FontStyle {
+            size,
+            weight: match self.is_bold() {
+                true => FontWeight::Bold,
+                false => FontWeight::Normal,
+            },
+            italic: self.is_italic(),
+            font: self.family().to_string(),
+            ..FontStyle::default()
+        }
+    }
+
+    /// Find the loaded face that fits `style` best, if its family was loaded.
+    pub(crate) fn find(style: &FontStyle) -> Option<FontFace> {
+        let bold = style.weight as i32 >= FontWeight::SemiBold as i32;
+        let faces = FACES.lock().unwrap();
+        faces
+            .iter()
+            .filter(|face| face.family().eq_ignore_ascii_case(&style.font))
+            .min_by_key(|face| {
+                (face.is_bold() != bold) as u8 + (face.is_italic() != style.italic) as u8
+            })
+            .cloned()
+    }
+
+    /// Get the number that tells the face apart from the other loaded faces.
+    pub(crate) fn id(&self) -> usize {
+        self.data.id
+    }
+
+    pub(crate) fn face(&self) -> Face<'_> {
+        // the data was parsed when the face was loaded
+        Face::parse(&self.data.data, 0).unwrap()
+    }
+}
@@ -1,5 +1,5 @@
 use std::any::Any;
-use crate::{Canvas, Event, Window};
+use crate::{Canvas, Event, EventResult, Size, Window};
 
 /// A trait for elements that can be used as a `Window`.
 pub trait AsWindow {
@@ -51,11 +51,37 @@ macro_rules! default_as_window {
 /// A trait for elements that can be drawn on a `Canvas`.
 pub trait Drawable: AsWindow {
     fn draw(&mut self, canvas: &mut Canvas);
+
+    /// Get the size the element would like to have to show its content.
+    /// Layouts use it together with the size policy of the window.
+    /// `None` means there is no preference, so layouts start from the current size of the window.
+    fn size_hint(&self) -> Option<Size> {
+        None
+    }
+
+    /// Get the smallest size at which the element can still show its content.
+    /// `None` means it can be shrunk to nothing.
+    fn minimum_size_hint(&self) -> Option<Size> {
+        None
+    }
 }
 
 /// A trait for elements that can listen to events.
 pub trait EventListener: AsWindow {
-    fn on_event(&mut self, event: &Event);
+    /// Handle an event sent to the element.
+    /// An input event that returns `EventResult::Ignored` bubbles up to the parent window,
+    /// with its position moved into the parent, so a container gets the events its children
+    /// do not use.
+    fn on_event(&mut self, event: &Event) -> EventResult;
+
+    /// See an input event before it reaches a descendant of the element, from the top-level
+    /// window down. Returning `EventResult::Handled` keeps it from the descendant, which lets
+    /// a container catch its shortcuts before the children do.
+    fn on_capture(&mut self, event: &Event) -> EventResult {
+        let _ = event;
+        EventResult::Ignored
+    }
+
     fn on_message(&mut self, msg: Box<dyn Any>){
         let _ = msg;
     }
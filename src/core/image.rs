@@ -0,0 +1,149 @@
+//! This file contains the implementation of the `Image` struct.
+//! It is the pixel buffer that the software renderer draws on.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::*;
+
+/// An image stored as RGBA pixels, row by row from the top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    size: Size,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Create a new `Image` with `size`, filled with `color`.
+    /// # Panics
+    /// If the width or the height is negative.
+    pub fn new(size: Size, color: Color) -> Self {
+        if size.width < 0 || size.height < 0 {
+            panic!("Invalid image size: {:?}", size);
+        }
+        let pixel = [color.red, color.green, color.blue, color.alpha];
+        Self {
+            size,
+            data: pixel.repeat(size.area() as usize),
+        }
+    }
+
+    /// Create a new `Image` from RGBA bytes.
+    /// Return None if the length of `data` does not match `size`.
+    pub fn from_rgba(size: Size, data: Vec<u8>) -> Option<Self> {
+        if size.width < 0 || size.height < 0 || data.len() != size.area() as usize * 4 {
+            return None;
+        }
+        Some(Self { size, data })
+    }
+
+    /// Get the size of the image.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Get the RGBA bytes of the image.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn index(&self, pos: Point) -> Option<usize> {
+        if pos.within(&rect!(0, 0, self.size.width, self.size.height)) {
+            Some((pos.y * self.size.width + pos.x) as usize * 4)
+        } else {
+            None
+        }
+    }
+
+    /// Get the color of the pixel at `pos`.
+    /// Return None if `pos` is outside of the image.
+    pub fn pixel(&self, pos: Point) -> Option<Color> {
+        let i = self.index(pos)?;
+        let p = &self.data[i..i + 4];
+        Some(rgb!(p[0], p[1], p[2], p[3]))
+    }
+
+    /// Set the color of the pixel at `pos`.
+    /// It does nothing if `pos` is outside of the image.
+    pub fn set_pixel(&mut self, pos: Point, color: Color) {
+        if let Some(i) = self.index(pos) {
+            self.data[i..i + 4].copy_from_slice(&[color.red, color.green, color.blue, color.alpha]);
+        }
+    }
+
+    /// Decode a PNG image from `reader`.
+    /// Images of every color type are converted to RGBA.
+    pub fn read_png<R: Read>(reader: R) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(io::Error::other)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(io::Error::other)?;
+        let buf = &buf[..info.buffer_size()];
+        let data = match info.color_type {
+            png::ColorType::Rgba => buf.to_vec(),
+            png::ColorType::Rgb => buf.chunks(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => buf.chunks(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => buf.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "unexpanded palette"));
+            }
+        };
+        let size = size!(info.width as i32, info.height as i32);
+        Ok(Self { size, data })
+    }
+
+    /// Load a PNG file at `path`.
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_png(BufReader::new(File::open(path)?))
+    }
+
+    /// Encode the image as PNG and write it to `writer`.
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.size.width as u32, self.size.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&self.data).map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    /// Save the image as a PNG file at `path`.
+    /// # Example
+    /// ```no_run
+    /// use rusty_gui::*;
+    ///
+    /// let image = Image::new(size!(16, 16), Color::RED);
+    /// image.save_png("red.png").unwrap();
+    /// ```
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    /// Draw `color` over the pixel at `pos` with alpha blending.
+    /// `coverage` is the part of the pixel covered by the shape, between 0 and 255.
+    pub(crate) fn blend_pixel(&mut self, pos: Point, color: Color, coverage: u8) {
+        let Some(i) = self.index(pos) else {
+            return;
+        };
+        let sa = color.alpha as u32 * coverage as u32 / 255;
+        if sa == 0 {
+            return;
+        }
+        if sa == 255 {
+            self.data[i..i + 4].copy_from_slice(&[color.red, color.green, color.blue, 255]);
+            return;
+        }
+        let dst = &mut self.data[i..i + 4];
+        let da = dst[3] as u32 * (255 - sa) / 255;
+        let out_a = sa + da;
+        let mix = |s: u8, d: u8| ((s as u32 * sa + d as u32 * da) / out_a) as u8;
+        dst[0] = mix(color.red, dst[0]);
+        dst[1] = mix(color.green, dst[1]);
+        dst[2] = mix(color.blue, dst[2]);
+        dst[3] = out_a as u8;
+    }
+}
@@ -1,11 +1,33 @@
+mod bidi;
 mod canvas;
 mod event;
+mod focus;
+mod fontface;
 mod gui;
+mod image;
+mod richtext;
+mod shaping;
+mod shortcut;
+mod text;
 mod widget;
 mod window;
 
 pub use canvas::*;
 pub use event::*;
+pub use focus::FocusPolicy;
+pub use fontface::FontFace;
 pub use gui::*;
+pub use image::*;
+pub use richtext::{RichText, TextStyle};
+pub use shortcut::{KeyChord, KeySequence, Shortcut, ShortcutContext, ShortcutError};
+pub use text::TextFormat;
+
 pub use widget::Widget;
-pub use window::Window;
+pub use window::{Window, WindowID};
+
+pub(crate) use bidi::{run_text, visual_runs, visual_text, CaretMap};
+pub(crate) use focus::{creation_order, move_focus};
+pub(crate) use richtext::RichMeasure;
+pub(crate) use shaping::{cluster_end, shape};
+pub(crate) use shortcut::match_shortcut;
+pub(crate) use text::{wrap_lines, TextLayout, TextLine, TextMeasure, ELLIPSIS};
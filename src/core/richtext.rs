@@ -0,0 +1,237 @@
+//! This file contains the implementation of the `RichText` struct and its `TextStyle`.
+//! A `RichText` is drawn with `Canvas::rich_text`, which lays out its runs across lines.
+
+use std::ops::Range;
+
+use crate::*;
+
+/// The `TextStyle` is used to specify the look of a run of a `RichText`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextStyle {
+    pub font: FontStyle,           // Font, with its underline and strikeout
+    pub color: Color,              // Text color
+    pub background: Option<Color>, // Highlight behind the text
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font: FontStyle::default(),
+            color: Color::BLACK,
+            background: None,
+        }
+    }
+}
+
+/// A text made of runs that each have their own `TextStyle`.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let keyword = TextStyle {
+///     color: Color::BLUE,
+///     ..Default::default()
+/// };
+/// let mut text = RichText::new();
+/// text.push("fn", &keyword).push(" main() {}", &TextStyle::default());
+/// // highlight the name of the function
+/// let name = TextStyle {
+///     background: Some(Color::YELLOW),
+///     ..Default::default()
+/// };
+/// text.set_style(3..7, &name);
+/// assert_eq!(text.text(), "fn main() {}");
+/// assert_eq!(text.runs().count(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RichText {
+    text: String,
+    /// The start of every run with its style, in order. A run ends where the next one starts.
+    runs: Vec<(usize, TextStyle)>,
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        let mut rich = Self::new();
+        rich.push(text, &TextStyle::default());
+        rich
+    }
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.runs.clear();
+    }
+
+    /// Append `text` drawn with `style`.
+    pub fn push(&mut self, text: &str, style: &TextStyle) -> &mut Self {
+        if text.is_empty() {
+            return self;
+        }
+        if self.runs.last().map(|(_, last)| last) != Some(style) {
+            self.runs.push((self.text.len(), style.clone()));
+        }
+        self.text.push_str(text);
+        self
+    }
+
+    /// Give `style` to the text in the byte `range`.
+    /// # Panics
+    /// Panics if the range is out of the text or does not fall on char boundaries.
+    pub fn set_style(&mut self, range: Range<usize>, style: &TextStyle) -> &mut Self {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.text.len(),
+            "Range out of the text"
+        );
+        assert!(self.text.is_char_boundary(start) && self.text.is_char_boundary(end));
+        if start == end {
+            return self;
+        }
+        let after = self.runs[self.run_at(end)].1.clone();
+        self.runs.retain(|(run, _)| *run < start || *run > end);
+        let index = self.runs.partition_point(|(run, _)| *run < start);
+        self.runs.insert(index, (start, style.clone()));
+        if end < self.text.len() {
+            self.runs.insert(index + 1, (end, after));
+        }
+        // merge the runs that have the same style
+        self.runs.dedup_by(|(_, next), (_, run)| next == run);
+        self
+    }
+
+    /// Get the style of the char at byte `pos`, or None if `pos` is out of the text.
+    pub fn style_at(&self, pos: usize) -> Option<&TextStyle> {
+        (pos < self.text.len()).then(|| &self.runs[self.run_at(pos)].1)
+    }
+
+    /// Get the byte range, the text and the style of every run, in order.
+    pub fn runs(&self) -> impl Iterator<Item = (Range<usize>, &str, &TextStyle)> {
+        self.runs.iter().enumerate().map(|(i, (start, style))| {
+            let end = self
+                .runs
+                .get(i + 1)
+                .map_or(self.text.len(), |(next, _)| *next);
+            (*start..end, &self.text[*start..end], style)
+        })
+    }
+
+    /// Get the size of the box taken by the text laid out with `format`.
+    /// The lines are wrapped and truncated at `width` if there is one.
+    pub fn measure(&self, width: Option<i32>, format: impl Into<TextFormat>) -> Size {
+        let measure = RichMeasure::new(self);
+        TextLayout::new(&self.text, &format.into(), (width, None), &measure).size()
+    }
+
+    /// Get the index of the run holding the char at byte `pos`.
+    /// The last run holds the end of the text.
+    pub(crate) fn run_at(&self, pos: usize) -> usize {
+        self.runs.partition_point(|(start, _)| *start <= pos).max(1) - 1
+    }
+
+    pub(crate) fn run_style(&self, run: usize) -> &TextStyle {
+        &self.runs[run].1
+    }
+
+    /// Split the byte range `start..end` at the runs it crosses.
+    pub(crate) fn pieces(&self, start: usize, end: usize) -> Vec<(usize, Range<usize>)> {
+        self.runs()
+            .enumerate()
+            .filter_map(|(i, (run, _, _))| {
+                let piece = run.start.max(start)..run.end.min(end);
+                (piece.start < piece.end).then_some((i, piece))
+            })
+            .collect()
+    }
+}
+
+/// Measures a `RichText` with the fonts of its runs.
+pub(crate) struct RichMeasure<'a> {
+    text: &'a RichText,
+    /// The font of every run, and its metrics.
+    pub fonts: Vec<(Font, FontMetrics)>,
+}
+
+impl<'a> RichMeasure<'a> {
+    pub fn new(text: &'a RichText) -> Self {
+        let fonts = text
+            .runs
+            .iter()
+            .map(|(_, style)| {
+                let font = Font::new(style.font.clone());
+                let metrics = font.metrics();
+                (font, metrics)
+            })
+            .collect();
+        Self { text, fonts }
+    }
+
+    /// Get the run whose style ends the text before `end`.
+    pub fn run_before(&self, end: usize) -> usize {
+        self.text.run_at(end.saturating_sub(1))
+    }
+}
+
+impl TextMeasure for RichMeasure<'_> {
+    fn width(&self, start: usize, end: usize) -> i32 {
+        self.text
+            .pieces(start, end)
+            .into_iter()
+            .map(|(i, piece)| {
+                let shaped = shape(&self.text.text, piece.start, piece.end);
+                self.fonts[i].0.measure(&shaped).width
+            })
+            .sum()
+    }
+
+    fn ellipsis_width(&self, end: usize) -> i32 {
+        match self.fonts.get(self.run_before(end)) {
+            Some((font, _)) => font.measure(ELLIPSIS).width,
+            None => 0,
+        }
+    }
+
+    fn metrics(&self, start: usize, end: usize) -> FontMetrics {
+        let runs = match start == end {
+            true => vec![self.text.run_at(start)],
+            false => self
+                .text
+                .pieces(start, end)
+                .into_iter()
+                .map(|(i, _)| i)
+                .collect(),
+        };
+        let metrics = runs
+            .iter()
+            .filter_map(|i| self.fonts.get(*i))
+            .map(|(_, m)| m);
+        let (ascent, descent, line_height) = metrics.fold((0, 0, 0), |(a, d, h), m| {
+            (a.max(m.ascent), d.max(m.descent), h.max(m.line_height))
+        });
+        match line_height {
+            0 => Font::new(FontStyle::default()).metrics(),
+            _ => FontMetrics {
+                ascent,
+                descent,
+                line_height: line_height.max(ascent + descent),
+            },
+        }
+    }
+}
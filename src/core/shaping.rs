@@ -0,0 +1,191 @@
+//! This file contains the shaping of the text shared by all the backends.
+//! Arabic letters take the form that joins them to their neighbours, and a lam followed by
+//! an alef becomes a ligature, using the presentation forms of Unicode.
+//! The shaped text is then drawn by the surfaces char by char, like any other text.
+
+use std::borrow::Cow;
+
+use unicode_bidi::{bidi_class, BidiClass};
+
+/// How an Arabic letter joins its neighbours.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Joining {
+    /// It does not join, like the hamza.
+    None,
+    /// It only joins the letter before it, like the alef.
+    Right,
+    /// It joins the letters on both sides, like the beh.
+    Dual,
+    /// It joins the letters on both sides but has no forms, like the tatweel.
+    Causing,
+}
+
+/// The isolated form of the letters from U+0621 to U+064A, followed by their final, initial
+/// and medial forms if they join, and how they join.
+const FORMS: [(u32, Joining); 42] = [
+    (0xFE80, Joining::None),  // hamza
+    (0xFE81, Joining::Right), // alef with madda above
+    (0xFE83, Joining::Right), // alef with hamza above
+    (0xFE85, Joining::Right), // waw with hamza above
+    (0xFE87, Joining::Right), // alef with hamza below
+    (0xFE89, Joining::Dual),  // yeh with hamza above
+    (0xFE8D, Joining::Right), // alef
+    (0xFE8F, Joining::Dual),  // beh
+    (0xFE93, Joining::Right), // teh marbuta
+    (0xFE95, Joining::Dual),  // teh
+    (0xFE99, Joining::Dual),  // theh
+    (0xFE9D, Joining::Dual),  // jeem
+    (0xFEA1, Joining::Dual),  // hah
+    (0xFEA5, Joining::Dual),  // khah
+    (0xFEA9, Joining::Right), // dal
+    (0xFEAB, Joining::Right), // thal
+    (0xFEAD, Joining::Right), // reh
+    (0xFEAF, Joining::Right), // zain
+    (0xFEB1, Joining::Dual),  // seen
+    (0xFEB5, Joining::Dual),  // sheen
+    (0xFEB9, Joining::Dual),  // sad
+    (0xFEBD, Joining::Dual),  // dad
+    (0xFEC1, Joining::Dual),  // tah
+    (0xFEC5, Joining::Dual),  // zah
+    (0xFEC9, Joining::Dual),  // ain
+    (0xFECD, Joining::Dual),  // ghain
+    (0, Joining::None),
+    (0, Joining::None),
+    (0, Joining::None),
+    (0, Joining::None),
+    (0, Joining::None),
+    (0, Joining::Causing),    // tatweel
+    (0xFED1, Joining::Dual),  // feh
+    (0xFED5, Joining::Dual),  // qaf
+    (0xFED9, Joining::Dual),  // kaf
+    (0xFEDD, Joining::Dual),  // lam
+    (0xFEE1, Joining::Dual),  // meem
+    (0xFEE5, Joining::Dual),  // noon
+    (0xFEE9, Joining::Dual),  // heh
+    (0xFEED, Joining::Right), // waw
+    (0xFEEF, Joining::Right), // alef maksura
+    (0xFEF1, Joining::Dual),  // yeh
+];
+
+const LAM: char = '\u{0644}';
+
+/// Get the isolated form of the ligature of a lam with the alef `ch`, or None if it is not
+/// an alef. The final form follows it.
+fn lam_alef(ch: char) -> Option<u32> {
+    match ch {
+        '\u{0622}' => Some(0xFEF5),
+        '\u{0623}' => Some(0xFEF7),
+        '\u{0625}' => Some(0xFEF9),
+        '\u{0627}' => Some(0xFEFB),
+        _ => None,
+    }
+}
+
+fn joining(ch: char) -> Joining {
+    match ch {
+        '\u{0621}'..='\u{064A}' => FORMS[ch as usize - 0x0621].1,
+        _ => Joining::None,
+    }
+}
+
+/// Check if `ch` is a mark drawn over the char before it, which is left out when the letters
+/// are joined.
+fn is_mark(ch: char) -> bool {
+    bidi_class(ch) == BidiClass::NSM
+}
+
+/// Check if `ch` only steers the bidirectional algorithm, like the left-to-right mark,
+/// and is not drawn.
+fn is_bidi_control(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    )
+}
+
+/// Check if a lam at `pos` of `text` makes a ligature with the alef after it, before `end`.
+fn is_ligature(text: &str, pos: usize, end: usize) -> bool {
+    let mut chars = text[pos..end].chars();
+    chars.next() == Some(LAM) && chars.next().and_then(lam_alef).is_some()
+}
+
+/// Get the end of the cluster of chars that starts at `pos` of `text` and is drawn as a whole:
+/// a char with the marks over it, or a lam-alef ligature. The cluster ends before `end`.
+pub(crate) fn cluster_end(text: &str, pos: usize, end: usize) -> usize {
+    let mut chars = text[pos..end].char_indices().skip(1);
+    if is_ligature(text, pos, end) {
+        chars.next();
+    }
+    chars
+        .find(|(_, ch)| !is_mark(*ch))
+        .map_or(end, |(i, _)| pos + i)
+}
+
+/// Shape the text of `text` from `start` to `end`.
+/// The letters join the ones around them in the whole text, even out of the range.
+/// The bidirectional controls are left out.
+/// Return the shaped text in logical order, which is borrowed if nothing was shaped.
+pub(crate) fn shape(text: &str, start: usize, end: usize) -> Cow<'_, str> {
+    let part = &text[start..end];
+    let shaped = |ch: char| joining(ch) != Joining::None || is_bidi_control(ch);
+    if part.is_ascii() || !part.chars().any(shaped) {
+        return Cow::Borrowed(part);
+    }
+    // if the letters before and after the range join the range
+    let joins_next = |ch: char| matches!(joining(ch), Joining::Dual | Joining::Causing);
+    let joins_prev = |ch: char| joining(ch) != Joining::None;
+    let mut prev = text[..start]
+        .chars()
+        .rev()
+        .find(|ch| !is_mark(*ch))
+        .is_some_and(joins_next);
+    let after = text[end..]
+        .chars()
+        .find(|ch| !is_mark(*ch))
+        .is_some_and(joins_prev);
+
+    let mut shaped = String::with_capacity(part.len());
+    let mut pos = start;
+    while pos < end {
+        let ch = text[pos..].chars().next().unwrap();
+        let mut next_pos = pos + ch.len_utf8();
+        if is_mark(ch) || is_bidi_control(ch) {
+            if !is_bidi_control(ch) {
+                shaped.push(ch);
+            }
+            pos = next_pos;
+            continue;
+        }
+        let next = text[next_pos..end].chars().find(|ch| !is_mark(*ch));
+        let next_joins = match next {
+            Some(next) => joins_prev(next),
+            None => after,
+        };
+        let (form, joins) = if is_ligature(text, pos, end) {
+            let alef = text[next_pos..].chars().next().unwrap();
+            next_pos += alef.len_utf8();
+            let ligature = lam_alef(alef).unwrap();
+            (ligature + prev as u32, false)
+        } else {
+            match joining(ch) {
+                Joining::Right => (FORMS[ch as usize - 0x0621].0 + prev as u32, false),
+                Joining::Dual => {
+                    let isolated = FORMS[ch as usize - 0x0621].0;
+                    let form = match (prev, next_joins) {
+                        (false, false) => isolated,
+                        (true, false) => isolated + 1,
+                        (false, true) => isolated + 2,
+                        (true, true) => isolated + 3,
+                    };
+                    (form, true)
+                }
+                Joining::Causing => (ch as u32, true),
+                Joining::None => (ch as u32, false),
+            }
+        };
+        shaped.push(char::from_u32(form).unwrap_or(ch));
+        prev = joins;
+        pos = next_pos;
+    }
+    Cow::Owned(shaped)
+}
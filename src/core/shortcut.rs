@@ -0,0 +1,494 @@
+//! This file contains the keyboard shortcuts of the application.
+//! Unlike the hotkeys of `Window::register_hotkey`, which the system takes from every
+//! application, a shortcut is only matched against the key events this application gets.
+//! The key events are matched before the capture phase, so a shortcut wins over the keys of
+//! the window that has the focus.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    str::FromStr,
+};
+
+use crate::*;
+
+/// A key pressed with some modifier keys, such as Ctrl+S.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    /// The modifier keys, without the mouse buttons.
+    pub mods: Modifiers,
+    pub key: KeyCode,
+}
+
+impl KeyChord {
+    pub fn new(mods: Modifiers, key: KeyCode) -> Self {
+        Self {
+            mods: mods.keys(),
+            key,
+        }
+    }
+}
+
+/// A sequence of key chords pressed one after the other, such as Ctrl+K then Ctrl+C.
+/// It is parsed from text like `"Ctrl+Shift+S"` or `"Ctrl+K, Ctrl+C"`: the chords are
+/// separated by commas, and the modifiers `Ctrl`, `Alt`, `Shift` and `Meta` come before
+/// the key, joined with `+`. The names are not case sensitive.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let keys: KeySequence = "ctrl+k, ctrl+shift+c".parse().unwrap();
+/// let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+/// assert_eq!(keys.chords()[1], KeyChord::new(ctrl_shift, KeyCode::Alpha('C')));
+/// assert_eq!(keys.to_string(), "Ctrl+K, Ctrl+Shift+C");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence {
+    chords: Vec<KeyChord>,
+}
+
+impl KeySequence {
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.chords
+    }
+
+    /// Check if the sequence starts with all the chords of `other`.
+    pub fn starts_with(&self, other: &KeySequence) -> bool {
+        self.chords.starts_with(&other.chords)
+    }
+}
+
+impl From<KeyChord> for KeySequence {
+    fn from(chord: KeyChord) -> Self {
+        Self {
+            chords: vec![chord],
+        }
+    }
+}
+
+/// The names of the keys which are not a single char, as they are parsed and displayed.
+/// The first name of a key is the one it is displayed with.
+const KEY_NAMES: [(&str, KeyCode); 29] = [
+    ("Tab", KeyCode::Tab),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Return", KeyCode::Enter),
+    ("Backspace", KeyCode::Backspace),
+    ("Esc", KeyCode::Esc),
+    ("Escape", KeyCode::Esc),
+    ("Del", KeyCode::Delete),
+    ("Delete", KeyCode::Delete),
+    ("Ins", KeyCode::Insert),
+    ("Insert", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PgUp", KeyCode::PageUp),
+    ("PageUp", KeyCode::PageUp),
+    ("PgDown", KeyCode::PageDown),
+    ("PageDown", KeyCode::PageDown),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Clear", KeyCode::Clear),
+    ("NumAdd", KeyCode::NumAdd),
+    ("NumSub", KeyCode::NumSub),
+    ("NumMul", KeyCode::NumMul),
+    ("NumDiv", KeyCode::NumDiv),
+    ("NumDot", KeyCode::NumDot),
+    ("CapsLock", KeyCode::CapsLock),
+    ("ScrollLock", KeyCode::ScrollLock),
+];
+
+/// The modifiers in the order they are displayed.
+const MODIFIER_NAMES: [(&str, Modifiers); 4] = [
+    ("Ctrl", Modifiers::CTRL),
+    ("Alt", Modifiers::ALT),
+    ("Shift", Modifiers::SHIFT),
+    ("Meta", Modifiers::META),
+];
+
+/// The keys of an American keyboard that are written as their symbol.
+const SYMBOLS: &str = "`,./;'[]\\-=";
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(Modifiers::CTRL),
+        "alt" => Some(Modifiers::ALT),
+        "shift" => Some(Modifiers::SHIFT),
+        "meta" | "win" | "cmd" => Some(Modifiers::META),
+        _ => None,
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return match ch {
+            'a'..='z' | 'A'..='Z' => Some(KeyCode::Alpha(ch.to_ascii_uppercase())),
+            '0'..='9' => Some(KeyCode::N(ch)),
+            ch if SYMBOLS.contains(ch) => Some(KeyCode::Symbol(ch)),
+            _ => None,
+        };
+    }
+    let lower = name.to_ascii_lowercase();
+    let number = |prefix: &str| {
+        lower
+            .strip_prefix(prefix)
+            .and_then(|n| n.parse::<u8>().ok())
+    };
+    if let Some(n) = number("f").filter(|n| (1..=24).contains(n)) {
+        return Some(KeyCode::F(n));
+    }
+    if let Some(n) = number("num").filter(|n| *n <= 9) {
+        return Some(KeyCode::Num(n));
+    }
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, key)| *key)
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Alpha(ch) | KeyCode::N(ch) | KeyCode::Symbol(ch) => ch.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Num(n) => format!("Num{}", n),
+        key => match KEY_NAMES.iter().find(|(_, k)| *k == key) {
+            Some((name, _)) => name.to_string(),
+            None => format!("{:?}", key),
+        },
+    }
+}
+
+/// Split `text` into its chords at the commas, except a comma that is the key of a chord.
+fn split_chords(text: &str) -> Vec<&str> {
+    let (mut chords, mut start) = (Vec::new(), 0);
+    for (i, ch) in text.char_indices() {
+        let chord = text[start..i].trim();
+        if ch == ',' && !chord.is_empty() && !chord.ends_with('+') {
+            chords.push(&text[start..i]);
+            start = i + 1;
+        }
+    }
+    chords.push(&text[start..]);
+    chords
+}
+
+impl FromStr for KeyChord {
+    type Err = ShortcutError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ShortcutError::Empty);
+        }
+        let (mut mods, mut key) = (Modifiers::NONE, None);
+        for name in text
+            .split('+')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if key.is_some() {
+                return Err(ShortcutError::SeveralKeys(text.to_string()));
+            }
+            match (parse_modifier(name), parse_key(name)) {
+                (Some(modifier), _) => mods |= modifier,
+                (None, Some(code)) => key = Some(code),
+                (None, None) => return Err(ShortcutError::UnknownKey(name.to_string())),
+            }
+        }
+        match key {
+            Some(key) => Ok(KeyChord::new(mods, key)),
+            None => Err(ShortcutError::NoKey(text.to_string())),
+        }
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = ShortcutError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let chords = split_chords(text).into_iter().map(str::parse);
+        Ok(Self {
+            chords: chords.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, modifier) in MODIFIER_NAMES {
+            if self.mods.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        f.write_str(&key_name(self.key))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, chord) in self.chords.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", chord)?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason why a shortcut could not be created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutError {
+    /// The key sequence, or one of its chords, is empty.
+    Empty,
+    /// The name is neither a modifier nor a key.
+    UnknownKey(String),
+    /// The chord only has modifiers.
+    NoKey(String),
+    /// The chord has more than one key besides the modifiers, or a modifier after the key.
+    SeveralKeys(String),
+    /// Another shortcut that can be active at the same time uses the same key sequence,
+    /// or one that starts with this one or that this one starts with.
+    Conflict(KeySequence),
+}
+
+impl fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the key sequence is empty"),
+            Self::UnknownKey(name) => write!(f, "unknown key {:?}", name),
+            Self::NoKey(chord) => write!(f, "the chord {:?} has no key", chord),
+            Self::SeveralKeys(chord) => write!(f, "the chord {:?} has several keys", chord),
+            Self::Conflict(keys) => write!(f, "the shortcut {} is already taken", keys),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError {}
+
+/// Where the focus has to be for a shortcut to be active.
+/// A shortcut is never active while its window is hidden or disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShortcutContext {
+    /// The focus is in the top-level window of the window of the shortcut.
+    #[default]
+    Window,
+    /// The focus is in any window of the application.
+    Application,
+}
+
+struct Entry {
+    id: u64,
+    keys: KeySequence,
+    /// The top-level window of the window of the shortcut.
+    top: Handle,
+    window: Handle,
+    context: ShortcutContext,
+    responder: Responder<KeySequence>,
+}
+
+impl Entry {
+    /// Check if the shortcut and `other` can be active at the same time.
+    fn overlaps(&self, top: Handle, context: ShortcutContext) -> bool {
+        self.context == ShortcutContext::Application
+            || context == ShortcutContext::Application
+            || self.top == top
+    }
+
+    fn is_active(&self, focus_top: Handle) -> bool {
+        Platform::is_window_visible(self.window)
+            && Platform::is_window_enabled(self.window)
+            && (self.context == ShortcutContext::Application || self.top == focus_top)
+    }
+}
+
+thread_local! {
+    static SHORTCUTS: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    /// The chords of a key sequence pressed so far.
+    static PENDING: RefCell<Vec<KeyChord>> = const { RefCell::new(Vec::new()) };
+    /// Whether the char of the key pressed last goes with it, because a shortcut took the key.
+    /// It is only set for the keys that type a char, and cleared when the key is released.
+    static SWALLOW_INPUT: Cell<bool> = const { Cell::new(false) };
+}
+
+fn top_level(window: Handle) -> Handle {
+    let mut top = window;
+    while let Some(parent) = Platform::parent_window(top) {
+        top = parent;
+    }
+    top
+}
+
+/// Find a shortcut other than `id` that conflicts with `keys` in `context`.
+fn find_conflict(
+    id: u64,
+    keys: &KeySequence,
+    top: Handle,
+    context: ShortcutContext,
+) -> Option<KeySequence> {
+    SHORTCUTS.with(|shortcuts| {
+        shortcuts
+            .borrow()
+            .iter()
+            .filter(|entry| entry.id != id && entry.overlaps(top, context))
+            .find(|entry| entry.keys.starts_with(keys) || keys.starts_with(&entry.keys))
+            .map(|entry| entry.keys.clone())
+    })
+}
+
+/// A keyboard shortcut of the application. The responder is called with the key sequence when
+/// the user types it while the shortcut is active, and the keys go no further.
+/// The shortcut is removed when it is dropped, so it has to be kept alive.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let form = Block::create(rect!(50, 50, 400, 300), None);
+/// let save = Shortcut::new(
+///     "Ctrl+S",
+///     form.as_window(),
+///     Responder::new(|_: &KeySequence| println!("saved")),
+/// )
+/// .unwrap();
+/// // the same keys cannot be taken twice in the same window
+/// let again = Shortcut::new("Ctrl+S", form.as_window(), Responder::new(|_: &KeySequence| {}));
+/// assert_eq!(again.err(), Some(ShortcutError::Conflict(save.keys().clone())));
+/// ```
+pub struct Shortcut {
+    id: u64,
+    keys: KeySequence,
+    context: ShortcutContext,
+}
+
+impl Shortcut {
+    /// Create a shortcut for `keys` in the context of `window`, which is active while the
+    /// focus is in its top-level window.
+    /// # Errors
+    /// If `keys` is not a valid key sequence, or another shortcut of the top-level window or
+    /// of the application conflicts with it.
+    pub fn new(
+        keys: &str,
+        window: &Window,
+        responder: Responder<KeySequence>,
+    ) -> Result<Self, ShortcutError> {
+        let keys: KeySequence = keys.parse()?;
+        let (window, context) = (window.get_id().handle, ShortcutContext::Window);
+        let top = top_level(window);
+        let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+        if let Some(taken) = find_conflict(id, &keys, top, context) {
+            return Err(ShortcutError::Conflict(taken));
+        }
+        SHORTCUTS.with(|shortcuts| {
+            shortcuts.borrow_mut().push(Entry {
+                id,
+                keys: keys.clone(),
+                top,
+                window,
+                context,
+                responder,
+            })
+        });
+        Ok(Self { id, keys, context })
+    }
+
+    pub fn keys(&self) -> &KeySequence {
+        &self.keys
+    }
+
+    pub fn context(&self) -> ShortcutContext {
+        self.context
+    }
+
+    /// Set where the focus has to be for the shortcut to be active.
+    /// # Errors
+    /// If another shortcut conflicts with this one in the new context.
+    /// The context does not change then.
+    pub fn set_context(&mut self, context: ShortcutContext) -> Result<(), ShortcutError> {
+        let top = SHORTCUTS.with(|shortcuts| {
+            let shortcuts = shortcuts.borrow();
+            shortcuts
+                .iter()
+                .find(|entry| entry.id == self.id)
+                .map(|entry| entry.top)
+        });
+        let top = top.unwrap_or(Handle::NULL);
+        if let Some(taken) = find_conflict(self.id, &self.keys, top, context) {
+            return Err(ShortcutError::Conflict(taken));
+        }
+        SHORTCUTS.with(|shortcuts| {
+            let mut shortcuts = shortcuts.borrow_mut();
+            if let Some(entry) = shortcuts.iter_mut().find(|entry| entry.id == self.id) {
+                entry.context = context;
+            }
+        });
+        self.context = context;
+        Ok(())
+    }
+}
+
+impl Drop for Shortcut {
+    fn drop(&mut self) {
+        // the thread local may be gone when the thread exits
+        let _ = SHORTCUTS.try_with(|shortcuts| {
+            shortcuts.borrow_mut().retain(|entry| entry.id != self.id);
+        });
+    }
+}
+
+/// Match the key event that `window` gets against the shortcuts active there.
+/// Return `EventResult::Handled` if the key belongs to a shortcut, even if the sequence is
+/// not complete yet, and for the char of that key.
+pub(crate) fn match_shortcut(window: Handle, event: &Event) -> EventResult {
+    let (key, mods) = match *event {
+        Event::Input { .. } if SWALLOW_INPUT.with(|swallow| swallow.replace(false)) => {
+            return EventResult::Handled;
+        }
+        Event::KeyPressed { key, mods, .. } if Modifiers::from_key(key).is_empty() => (key, mods),
+        Event::KeyReleased { .. } => {
+            SWALLOW_INPUT.with(|swallow| swallow.set(false));
+            return EventResult::Ignored;
+        }
+        _ => return EventResult::Ignored,
+    };
+    let focus_top = top_level(window);
+    let chord = KeyChord::new(mods, key);
+    let mut typed = PENDING.with(|pending| pending.take());
+    typed.push(chord);
+    let mut found = find_typed(&typed, focus_top);
+    if found.is_none() && typed.len() > 1 {
+        // a chord that does not go on with the sequence may start another one
+        typed = vec![chord];
+        found = find_typed(&typed, focus_top);
+    }
+    let Some((keys, responder)) = found else {
+        SWALLOW_INPUT.with(|swallow| swallow.set(false));
+        return EventResult::Ignored;
+    };
+    SWALLOW_INPUT.with(|swallow| swallow.set(key_char(key).is_some()));
+    if keys.chords().len() > typed.len() {
+        PENDING.with(|pending| *pending.borrow_mut() = typed);
+    } else {
+        // the responder may add or drop shortcuts
+        (RefCell::borrow_mut(&responder))(&keys);
+    }
+    EventResult::Handled
+}
+
+/// Find an active shortcut whose key sequence starts with the `typed` chords.
+fn find_typed(
+    typed: &[KeyChord],
+    focus_top: Handle,
+) -> Option<(KeySequence, Responder<KeySequence>)> {
+    SHORTCUTS.with(|shortcuts| {
+        let shortcuts = shortcuts.borrow();
+        let entry = shortcuts
+            .iter()
+            .filter(|entry| entry.keys.chords().starts_with(typed))
+            .find(|entry| entry.is_active(focus_top))?;
+        Some((entry.keys.clone(), entry.responder.clone()))
+    })
+}
@@ -0,0 +1,229 @@
+//! This file contains the text layout shared by all the backends.
+//! A text is broken into lines at its newlines and, if asked, wrapped at a width.
+//! The lines are then aligned in a rectangle and drawn one by one by the surface.
+
+use crate::*;
+
+/// The char that ends a truncated line.
+pub(crate) const ELLIPSIS: &str = "…";
+
+/// The `TextFormat` is used to specify how `Canvas::formatted_text` lays out a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextFormat {
+    pub align: TextAlign,  // Alignment of the lines in the rectangle
+    pub wrap: bool,        // Wrap the lines at the width of the rectangle
+    pub line_spacing: i32, // Extra space between two lines
+    pub ellipsis: bool,    // End the lines that do not fit with "…"
+}
+
+impl From<TextAlign> for TextFormat {
+    fn from(align: TextAlign) -> Self {
+        Self {
+            align,
+            ..Self::default()
+        }
+    }
+}
+
+/// Break `text` into lines at its newlines, and wrap them at `width` if there is one.
+/// `measure` gives the width of the text in a byte range, and the width of a line is the sum of
+/// the widths of its chars.
+/// Lines are wrapped at the last space that fits, which is left out of both lines,
+/// or in the middle of a word that is wider than `width`.
+/// Return the byte range of every line; a line holds at least one char.
+pub(crate) fn wrap_lines(
+    text: &str,
+    width: Option<i32>,
+    measure: impl Fn(usize, usize) -> i32,
+) -> Vec<(usize, usize)> {
+    let next_char = |pos: usize| pos + text[pos..].chars().next().map_or(0, char::len_utf8);
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for paragraph in text.split('\n') {
+        let end = start + paragraph.len();
+        let mut line = start;
+        while let Some(width) = width.filter(|_| line < end) {
+            // the longest part of the line that fits, with at least one char in it;
+            // the widths of the chars add up, so that every char is measured once
+            let mut fit = next_char(line);
+            let mut fit_width = measure(line, fit);
+            while fit < end {
+                let next = next_char(fit);
+                fit_width += measure(fit, next);
+                if fit_width > width {
+                    break;
+                }
+                fit = next;
+            }
+            if fit == end {
+                break;
+            }
+            // break at a space, or in the middle of the word if there is none
+            let space = match text[fit..].starts_with(' ') {
+                true => Some(fit),
+                false => text[line..fit].rfind(' ').map(|i| line + i),
+            };
+            match space {
+                Some(space) if space > line => {
+                    lines.push((line, space));
+                    line = space + 1;
+                }
+                _ => {
+                    lines.push((line, fit));
+                    line = fit;
+                }
+            }
+        }
+        lines.push((line, end));
+        start = end + 1;
+    }
+    lines
+}
+
+/// Measures the parts of a text for `TextLayout`.
+pub(crate) trait TextMeasure {
+    /// Get the width of the text in the byte range `start..end`.
+    fn width(&self, start: usize, end: usize) -> i32;
+    /// Get the width of the ellipsis that follows the text ending at `end`.
+    fn ellipsis_width(&self, end: usize) -> i32;
+    /// Get the metrics of the tallest font in the byte range `start..end`,
+    /// or of the font at `start` if the range is empty.
+    fn metrics(&self, start: usize, end: usize) -> FontMetrics;
+}
+
+/// A line of a `TextLayout`.
+pub(crate) struct TextLine {
+    /// The byte range of the text shown on the line.
+    pub start: usize,
+    pub end: usize,
+    /// The line is truncated and ends with an ellipsis.
+    pub ellipsis: bool,
+    pub width: i32,
+    pub ascent: i32,
+    pub height: i32,
+}
+
+/// A text broken into lines, ready to be aligned and drawn.
+pub(crate) struct TextLayout {
+    pub lines: Vec<TextLine>,
+    pub line_spacing: i32,
+}
+
+impl TextLayout {
+    /// Lay out `text` in a box of `bounds`, where a None side is unbounded.
+    pub fn new(
+        text: &str,
+        format: &TextFormat,
+        bounds: (Option<i32>, Option<i32>),
+        measure: &impl TextMeasure,
+    ) -> Self {
+        let (width, height) = bounds;
+        let wrap = width.filter(|_| format.wrap);
+        let mut lines: Vec<TextLine> =
+            wrap_lines(text, wrap, |start, end| measure.width(start, end))
+                .into_iter()
+                .map(|(start, end)| {
+                    let end = start + text[start..end].trim_end_matches('\r').len();
+                    let metrics = measure.metrics(start, end);
+                    TextLine {
+                        start,
+                        end,
+                        ellipsis: false,
+                        width: measure.width(start, end),
+                        ascent: metrics.ascent,
+                        height: metrics.line_height,
+                    }
+                })
+                .collect();
+        if format.ellipsis {
+            let mut cut = false;
+            if let Some(height) = height {
+                // keep the lines that fit, and at least one
+                let mut bottom = 0;
+                let fit = lines.iter().position(|line| {
+                    bottom += line.height;
+                    let outside = bottom > height;
+                    bottom += format.line_spacing;
+                    outside
+                });
+                if let Some(fit) = fit.map(|fit| fit.max(1)) {
+                    cut = fit < lines.len();
+                    lines.truncate(fit);
+                }
+            }
+            if let Some(width) = width {
+                let last = lines.len() - 1;
+                for (i, line) in lines.iter_mut().enumerate() {
+                    if line.width > width || (cut && i == last) {
+                        elide(text, line, width, measure);
+                    }
+                }
+            }
+        }
+        Self {
+            lines,
+            line_spacing: format.line_spacing,
+        }
+    }
+
+    /// Get the size of the box taken by all the lines.
+    pub fn size(&self) -> Size {
+        let width = self.lines.iter().map(|line| line.width).max().unwrap_or(0);
+        let height = self.lines.iter().map(|line| line.height).sum::<i32>();
+        let spacing = (self.lines.len() as i32 - 1).max(0) * self.line_spacing;
+        size!(width, height + spacing)
+    }
+
+    /// Get the top-left corner of every line when the lines are aligned in `rect`.
+    /// In an empty rectangle, the lines are aligned around its position.
+    pub fn place(&self, rect: Rect, align: TextAlign) -> Vec<Point> {
+        let (x, y, w, h) = rect.into();
+        let height = self.size().height;
+        let mut top = match align {
+            TextAlign::LeftTop | TextAlign::CenterTop | TextAlign::RightTop => y,
+            TextAlign::LeftMiddle | TextAlign::Center | TextAlign::RightMiddle => {
+                y + (h - height) / 2
+            }
+            TextAlign::LeftBottom | TextAlign::CenterBottom | TextAlign::RightBottom => {
+                y + h - height
+            }
+        };
+        self.lines
+            .iter()
+            .map(|line| {
+                let left = match align {
+                    TextAlign::LeftTop | TextAlign::LeftMiddle | TextAlign::LeftBottom => x,
+                    TextAlign::CenterTop | TextAlign::Center | TextAlign::CenterBottom => {
+                        x + (w - line.width) / 2
+                    }
+                    TextAlign::RightTop | TextAlign::RightMiddle | TextAlign::RightBottom => {
+                        x + w - line.width
+                    }
+                };
+                let pos = pos!(left, top);
+                top += line.height + self.line_spacing;
+                pos
+            })
+            .collect()
+    }
+}
+
+/// Shorten `line` so that it fits in `width` with an ellipsis after it.
+fn elide(text: &str, line: &mut TextLine, width: i32, measure: &impl TextMeasure) {
+    let mut end = line.end;
+    loop {
+        let shown = line.start + text[line.start..end].trim_end().len();
+        let shown_width = measure.width(line.start, shown) + measure.ellipsis_width(shown);
+        if end == line.start || shown_width <= width {
+            line.end = shown;
+            line.width = shown_width;
+            line.ellipsis = true;
+            return;
+        }
+        end = line.start
+            + text[line.start..end]
+                .char_indices()
+                .last()
+                .map_or(0, |(i, _)| i);
+    }
+}
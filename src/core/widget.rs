@@ -2,7 +2,7 @@
 
 use std::ops::{Deref, DerefMut};
 
-use crate::{send_window_created_msg, Rect};
+use crate::{Backend, Canvas, Image, Platform, Rect, Size};
 
 use super::{Ele, Window};
 
@@ -44,7 +44,7 @@ impl<T: Ele> Widget<T> {
             addr,
         };
         *ret.as_window_mut() = Window::new(title, rect, parent, &ret);
-        send_window_created_msg(ret.as_window().hwnd);
+        Platform::send_created(ret.as_window().handle);
         ret
     }
 
@@ -52,10 +52,35 @@ impl<T: Ele> Widget<T> {
     pub fn addr(&self) -> usize {
         self.addr
     }
+
+    /// Draw the element into an image of `size` with the software renderer.
+    /// The window is resized to `size` first if needed, so the element lays itself out for it.
+    /// Child windows are not drawn. It works without showing the window.
+    /// # Example
+    /// ```no_run
+    /// use rusty_gui::*;
+    ///
+    /// let form = Block::create(rect!(100, 100, 400, 300), None);
+    /// let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+    /// button.render_to_image(size!(120, 40)).save_png("button.png").unwrap();
+    /// ```
+    pub fn render_to_image(&mut self, size: Size) -> Image {
+        if self.as_window().rect().size != size {
+            self.as_window().set_size(size);
+        }
+        let mut canvas = Canvas::offscreen(size);
+        self.draw(&mut canvas);
+        canvas.into_image().unwrap()
+    }
 }
 
 impl<T: Ele> Drop for Widget<T> {
     fn drop(&mut self) {
+        // the window must not reach the element after it is freed
+        let handle = self.as_window().handle;
+        if !handle.is_null() {
+            Platform::release_window(handle);
+        }
         // drop the type data
         let ty_box = self.type_data.take().unwrap();
         let _ = Box::into_raw(ty_box);
@@ -2,8 +2,9 @@
 
 use crate::*;
 use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
-use std::{os::raw::c_void, ptr::null_mut};
 
 use super::{Ele, KeyCode, Widget};
 
@@ -12,37 +13,50 @@ use super::{Ele, KeyCode, Widget};
 /// Any GUI element must have a field of type Window. It is used to implement trait `AsWindow`.
 #[derive(Clone)]
 pub struct Window {
-    pub(crate) hwnd: *mut c_void,
+    pub(crate) handle: Handle,
     userdata: Option<Arc<dyn Any>>,
     pub(crate) min_width: Option<i32>,
     pub(crate) min_height: Option<i32>,
     pub(crate) max_width: Option<i32>,
     pub(crate) max_height: Option<i32>,
+    pub(crate) size_policy: (SizePolicy, SizePolicy),
+    layout: Option<Rc<RefCell<dyn Layout>>>,
+    anchors: Option<(Anchors, Margins)>,
+    focus_policy: FocusPolicy,
+    tab_index: Option<u32>,
+    /// The place of the window in the order of creation, which is its default tab order.
+    pub(crate) order: u64,
 }
 
 // It is used to identify the window.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WindowID {
-    pub(crate) hwnd: *mut c_void,
+    pub(crate) handle: Handle,
 }
 
 impl Default for Window {
     /// This method returns an empty Window struct as a placeholder.
     fn default() -> Self {
         Self {
-            hwnd: null_mut(),
+            handle: Handle::NULL,
             userdata: None,
             min_width: None,
             min_height: None,
             max_width: None,
             max_height: None,
+            size_policy: (SizePolicy::Preferred, SizePolicy::Preferred),
+            layout: None,
+            anchors: None,
+            focus_policy: FocusPolicy::None,
+            tab_index: None,
+            order: 0,
         }
     }
 }
 
 impl Window {
-    fn check_hwnd(&self) {
-        if self.hwnd.is_null() {
+    fn check_handle(&self) {
+        if self.handle.is_null() {
             panic!("Window cannot be default.");
         }
     }
@@ -58,7 +72,8 @@ impl Window {
         widget: &Widget<T>,
     ) -> Self {
         Self {
-            hwnd: create_window(title, rect, parent, widget) as _,
+            handle: Platform::create_window(title, rect, parent.map(|p| p.handle), widget),
+            order: creation_order(),
             ..Default::default()
         }
     }
@@ -68,24 +83,24 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn rect(&self) -> Rect {
-        self.check_hwnd();
-        get_rect(self.hwnd)
+        self.check_handle();
+        Platform::window_rect(self.handle)
     }
 
     /// Get the title of the window
     /// # Panics
     /// If the window is default, it will panic.
     pub fn title(&self) -> String {
-        self.check_hwnd();
-        get_window_title(self.hwnd)
+        self.check_handle();
+        Platform::window_title(self.handle)
     }
 
     /// Get the absolute rect of the window relative to the screen, including the title bar and borders.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn absrect(&self) -> Rect {
-        self.check_hwnd();
-        get_absolute_rect(self.hwnd)
+        self.check_handle();
+        Platform::absolute_rect(self.handle)
     }
 
     /// Update the window.
@@ -103,60 +118,62 @@ impl Window {
     /// default_as_window!(YouWindow);
     ///
     /// impl EventListener for YouWindow{
-    ///     fn on_event(&mut self, event: &Event) {
+    ///     fn on_event(&mut self, event: &Event) -> EventResult {
     ///         if let Event::MouseButtonPressed { button, ..} = event {
     ///             // When left button is pressed.
     ///             self.data += 1; // change the data.
     ///             self.this.update(); // update the window content to apply new data.
+    ///             return EventResult::Handled;
     ///         }
+    ///         EventResult::Ignored
     ///     }
     /// }
     /// ```
     /// # Panics
     /// If the window is default, it will panic.
     pub fn update(&self) {
-        self.check_hwnd();
-        update_window(self.hwnd);
+        self.check_handle();
+        Platform::update_window(self.handle);
     }
 
     /// Set the rect of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_rect(&self, rect: Rect) {
-        self.check_hwnd();
-        set_window_rect(self.hwnd, rect);
+        self.check_handle();
+        Platform::set_window_rect(self.handle, rect);
     }
 
     /// Set the position of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_pos(&self, pos: Point) {
-        self.check_hwnd();
-        set_window_pos(self.hwnd, pos);
+        self.check_handle();
+        Platform::set_window_pos(self.handle, pos);
     }
 
     /// Set the size of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_size(&self, size: Size) {
-        self.check_hwnd();
-        set_window_size(self.hwnd, size);
+        self.check_handle();
+        Platform::set_window_size(self.handle, size);
     }
 
     /// Set the title of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_title(&self, title: &str) {
-        self.check_hwnd();
-        set_window_title(self.hwnd, title);
+        self.check_handle();
+        Platform::set_window_title(self.handle, title);
     }
 
     /// Set the visibility of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_visible(&self, visible: bool) {
-        self.check_hwnd();
-        set_window_visible(self.hwnd, visible);
+        self.check_handle();
+        Platform::set_window_visible(self.handle, visible);
     }
 
     /// Set the focus to the window.
@@ -164,15 +181,15 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_focus(&self) {
-        self.check_hwnd();
-        set_window_focus(self.hwnd);
+        self.check_handle();
+        Platform::set_focus(self.handle);
     }
 
     /// Set the minimum width of the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_min_width(&mut self, width: i32) {
-        self.check_hwnd();
+        self.check_handle();
         self.min_width = Some(width);
     }
 
@@ -180,7 +197,7 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_min_height(&mut self, height: i32) {
-        self.check_hwnd();
+        self.check_handle();
         self.min_height = Some(height);
     }
 
@@ -190,7 +207,7 @@ impl Window {
     /// # Note
     /// This function will also disable the maximize button of the window.
     pub fn set_max_width(&mut self, width: i32) {
-        self.check_hwnd();
+        self.check_handle();
         self.max_width = Some(width);
         self.disable_maximize();
     }
@@ -201,7 +218,7 @@ impl Window {
     /// # Note
     /// This function will also disable the maximize button of the window.
     pub fn set_max_height(&mut self, height: i32) {
-        self.check_hwnd();
+        self.check_handle();
         self.max_height = Some(height);
         self.disable_maximize();
     }
@@ -211,7 +228,7 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_min_size(&mut self, size: Size) {
-        self.check_hwnd();
+        self.check_handle();
         self.min_width = Some(size.width);
         self.min_height = Some(size.height);
     }
@@ -223,33 +240,163 @@ impl Window {
     /// # Note
     /// This function will also disable the maximize button of the window.
     pub fn set_max_size(&mut self, size: Size) {
-        self.check_hwnd();
+        self.check_handle();
         self.max_width = Some(size.width);
         self.max_height = Some(size.height);
         self.disable_maximize();
     }
 
+    /// Let `layout` place the child windows, and apply it right away.
+    /// The layout is applied again every time the window is resized.
+    /// It replaces the layout set before.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let mut form = Block::create(rect!(50, 50, 400, 300), None);
+    /// let ok = PushButton::create("OK", rect!(0, 0, 80, 30), form.as_window());
+    /// let cancel = PushButton::create("Cancel", rect!(0, 0, 80, 30), form.as_window());
+    ///
+    /// let mut buttons = HBoxLayout::new();
+    /// buttons.set_margins(Margins::uniform(10));
+    /// buttons.set_spacing(5);
+    /// buttons.add_stretch(1);
+    /// buttons.add_widget(ok.as_window(), 0);
+    /// buttons.add_widget(cancel.as_window(), 0);
+    /// form.as_window_mut().set_layout(buttons);
+    /// ```
+    /// # Panics
+    /// If the window is default, it will panic.
+    pub fn set_layout<L: Layout + 'static>(&mut self, layout: L) {
+        self.check_handle();
+        self.layout = Some(Rc::new(RefCell::new(layout)));
+        self.relayout();
+    }
+
+    /// Remove the layout of the window. The child windows stay where they are.
+    pub fn clear_layout(&mut self) {
+        self.layout = None;
+    }
+
+    /// Apply the layout of the window again, if there is one.
+    /// It is needed after the layout is changed by hand.
+    /// # Panics
+    /// If the window is default, it will panic.
+    pub fn relayout(&self) {
+        self.check_handle();
+        if let Some(layout) = &self.layout {
+            layout.borrow_mut().apply(self.rect());
+        }
+    }
+
+    /// Attach the window to edges of its parent, keeping the `margins` to them,
+    /// and move it there right away.
+    /// From then on, the window follows its parent whenever the parent is resized.
+    /// It replaces the anchors set before. A window placed by a layout should not have anchors.
+    /// # Panics
+    /// If the window is default, it will panic.
+    pub fn set_anchors(&mut self, anchors: Anchors, margins: Margins) {
+        self.check_handle();
+        self.anchors = Some((anchors, margins));
+        if let Some(parent) = Platform::parent_window(self.handle) {
+            self.follow_anchors(Platform::window_rect(parent).size);
+        }
+    }
+
+    /// Get the anchors of the window and the margins kept to them.
+    pub fn anchors(&self) -> Option<(Anchors, Margins)> {
+        self.anchors
+    }
+
+    /// Detach the window from its parent. It stays where it is.
+    pub fn clear_anchors(&mut self) {
+        self.anchors = None;
+    }
+
+    /// Move the window by its anchors inside a parent of `parent` size.
+    fn follow_anchors(&self, parent: Size) {
+        let Some((anchors, margins)) = self.anchors else {
+            return;
+        };
+        let rect = Rect {
+            pos: Platform::window_pos(self.handle),
+            size: Platform::window_rect(self.handle).size,
+        };
+        let placed = anchors.place(margins, parent, rect);
+        if placed != rect {
+            Platform::set_window_rect(self.handle, placed);
+        }
+    }
+
+    /// Called by the backend when the client area of the window gets the new `size`,
+    /// before the element gets `Event::WindowResized`.
+    /// The layout places the children first, then the anchored children follow.
+    pub(crate) fn resized(&self, size: Size) {
+        if let Some(layout) = &self.layout {
+            layout.borrow_mut().apply(rect!(0, 0, size.width, size.height));
+        }
+        for child in Platform::child_windows(self.handle) {
+            Platform::visit_window(child, &mut |ele| ele.as_window().follow_anchors(size));
+        }
+    }
+
+    /// Get how layouts may resize the window horizontally and vertically.
+    pub fn size_policy(&self) -> (SizePolicy, SizePolicy) {
+        self.size_policy
+    }
+
+    /// Set how layouts may resize the window horizontally and vertically,
+    /// relative to the size hint of its element.
+    /// The limits such as `min_width` still apply, whatever the policy is.
+    pub fn set_size_policy(&mut self, horizontal: SizePolicy, vertical: SizePolicy) {
+        self.size_policy = (horizontal, vertical);
+    }
+
+    /// Get how the window takes the keyboard focus.
+    pub fn focus_policy(&self) -> FocusPolicy {
+        self.focus_policy
+    }
+
+    /// Set how the window takes the keyboard focus: when it is clicked, with Tab, or both.
+    /// Windows do not take it by default, but the built-in widgets that use the keyboard do.
+    pub fn set_focus_policy(&mut self, policy: FocusPolicy) {
+        self.focus_policy = policy;
+    }
+
+    /// Get the place of the window in the tab order, if it is set.
+    pub fn tab_index(&self) -> Option<u32> {
+        self.tab_index
+    }
+
+    /// Set the place of the window in the tab order of its top-level window.
+    /// Tab moves the focus through the windows with a tab index first, from the lowest one,
+    /// then through the others in the order they were created.
+    /// `None` puts the window back in the order of creation.
+    pub fn set_tab_index(&mut self, index: Option<u32>) {
+        self.tab_index = index;
+    }
+
     /// Check if the window has focus.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn has_focus(&self) -> bool {
-        self.check_hwnd();
-        is_window_onfocus(self.hwnd)
+        self.check_handle();
+        Platform::has_focus(self.handle)
     }
 
     /// Show the window and update it.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn show(&self) {
-        self.check_hwnd();
-        show_and_update(self.hwnd);
+        self.check_handle();
+        Platform::show_window(self.handle);
     }
 
     /// Hide the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn hide(&self) {
-        self.check_hwnd();
+        self.check_handle();
         self.set_visible(false);
     }
 
@@ -257,29 +404,29 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn minimize(&self) {
-        self.check_hwnd();
-        set_window_minimized(self.hwnd);
+        self.check_handle();
+        Platform::minimize_window(self.handle);
     }
 
     /// Maximize the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn maximize(&self) {
-        self.check_hwnd();
-        set_window_maximized(self.hwnd);
+        self.check_handle();
+        Platform::maximize_window(self.handle);
     }
 
     pub fn disable_maximize(&self) {
-        self.check_hwnd();
-        disable_maximize_window(self.hwnd);
+        self.check_handle();
+        Platform::disable_maximize(self.handle);
     }
 
     /// Restore the window from maximized or minimized state.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn restore(&self) {
-        self.check_hwnd();
-        set_window_restored(self.hwnd);
+        self.check_handle();
+        Platform::restore_window(self.handle);
     }
 
     /// Disable the window.
@@ -287,19 +434,21 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn disable(&self) {
-        self.check_hwnd();
-        disable_window(self.hwnd);
+        self.check_handle();
+        Platform::set_window_enabled(self.handle, false);
     }
 
     /// Enable the window.
     /// # Panics
     /// If the window is default, it will panic.
     pub fn enable(&self) {
-        self.check_hwnd();
-        enable_window(self.hwnd);
+        self.check_handle();
+        Platform::set_window_enabled(self.handle, true);
     }
 
     /// Register a hotkey for the window.
+    /// The hotkey is system-wide: the other applications do not get its keys anymore.
+    /// For keys that only work while the application has the focus, use `Shortcut`.
     /// The `id` is the identifier of the hotkey. It should be between 0 and 0xBFFF.
     /// If the `id` has registered, this function's behavior is undefined.
     /// The `modifiers` and `key` is the hotkey combination.
@@ -320,12 +469,13 @@ impl Window {
     /// # Panics
     /// If the hotkey `id` is not between 0 and 0xBFFF (Out of range is invalid).
     /// If the window is default, it will panic.
+    #[allow(clippy::manual_range_contains)]
     pub fn register_hotkey(&mut self, id: i32, modifiers: HotKeyFlags, key: KeyCode) {
         if id < 0 || id > 0xBFFF {
             panic!("Invalid hotkey id: {}", id);
         }
-        self.check_hwnd();
-        register_hotkey_for_window(self.hwnd, id, key, modifiers);
+        self.check_handle();
+        Platform::register_hotkey(self.handle, id, key, modifiers);
     }
 
     /// Create a timer for the window.
@@ -342,8 +492,8 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn set_timer(&self, id: usize, interval: u32) {
-        self.check_hwnd();
-        set_window_timer(self.hwnd, id, interval);
+        self.check_handle();
+        Platform::set_timer(self.handle, id, interval);
     }
 
     /// Kill a timer for the window.
@@ -358,19 +508,20 @@ impl Window {
     /// default_as_window!(YouWindow);
     ///
     /// impl EventListener for YouWindow {
-    ///     fn on_event(&mut self, event: &Event) {
+    ///     fn on_event(&mut self, event: &Event) -> EventResult {
     ///         if let Event::Timer { id, .. } = event {
     ///             self.this.kill_timer(*id);   // Kill the timer when it triggers. Make it only trigger once.
     ///             // Do something else.
     ///         }
+    ///         EventResult::Ignored
     ///     }
     /// }
     /// ```
     /// # Panics
     /// If the window is default, it will panic.
     pub fn kill_timer(&self, id: usize) {
-        self.check_hwnd();
-        kill_window_timer(self.hwnd, id);
+        self.check_handle();
+        Platform::kill_timer(self.handle, id);
     }
 
     /// For each child window of the window, call the given function.
@@ -399,8 +550,8 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn foreach<F: FnMut(&mut dyn Ele) + 'static>(&self, f: F) {
-        self.check_hwnd();
-        for_each_child_window(self.hwnd, Box::new(f));
+        self.check_handle();
+        Platform::for_each_child(self.handle, Box::new(f));
     }
 
     /// Read the data stored in the window.
@@ -423,7 +574,7 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn read_data<T: Clone + 'static>(&self) -> Option<T> {
-        self.check_hwnd();
+        self.check_handle();
         let userdata = self.userdata.as_ref().map(|d| d.clone());
         userdata.map(|d| -> Option<T> {
             let d = d.downcast_ref::<T>()?;
@@ -437,15 +588,17 @@ impl Window {
     /// # Panics
     /// If the window is default, it will panic.
     pub fn write_data<T: Clone + 'static>(&mut self, data: T) {
-        self.check_hwnd();
+        self.check_handle();
         self.userdata = Some(Arc::new(data));
     }
 
     /// Get the identifier of the window.
     /// It can be used to post message to the window.
     pub fn get_id(&self) -> WindowID {
-        self.check_hwnd();
-        WindowID { hwnd: self.hwnd }
+        self.check_handle();
+        WindowID {
+            handle: self.handle,
+        }
     }
 
     /// Post a message to the window.
@@ -467,6 +620,6 @@ impl Window {
     /// ```
     /// You can handle the message in the `on_message()` method of the window.
     pub fn post<T: Any + 'static>(id: WindowID, msg: T) {
-        send_user_def_msg(id.hwnd, Box::new(Box::new(msg)));
+        Platform::send_message(id.handle, Box::new(msg));
     }
 }
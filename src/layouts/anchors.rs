@@ -0,0 +1,92 @@
+//! This file contains the anchors, which attach a child window to the edges of its parent.
+//! They are lighter than a layout: every window follows its parent by itself.
+
+use crate::*;
+
+/// The edges and the centers of the parent a child window is attached to.
+///
+/// An edge keeps its distance to the same edge of the parent, as given by the margins.
+/// With both opposite edges, the window stretches with the parent.
+/// A center keeps the window in the middle of the parent, shifted by the difference
+/// between the margins on that axis.
+/// On an axis without any anchor, the window stays where it is.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let form = Block::create(rect!(50, 50, 400, 300), None);
+/// let mut ok = PushButton::create("OK", rect!(0, 0, 80, 30), form.as_window());
+/// // stick to the bottom-right corner, 10 pixels away from the edges
+/// ok.as_window_mut().set_anchors(
+///     Anchors {
+///         right: true,
+///         bottom: true,
+///         ..Default::default()
+///     },
+///     Margins::uniform(10),
+/// );
+/// assert_eq!(ok.as_window().absrect().pos, pos!(50 + 310, 50 + 260));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Anchors {
+    pub left: bool,
+    pub top: bool,
+    pub right: bool,
+    pub bottom: bool,
+    pub horizontal_center: bool,
+    pub vertical_center: bool,
+}
+
+impl Anchors {
+    /// Attached to all four edges, so the window fills the parent inside the margins.
+    pub const FILL: Anchors = Anchors {
+        left: true,
+        top: true,
+        right: true,
+        bottom: true,
+        horizontal_center: false,
+        vertical_center: false,
+    };
+    /// Kept in the middle of the parent.
+    pub const CENTER: Anchors = Anchors {
+        left: false,
+        top: false,
+        right: false,
+        bottom: false,
+        horizontal_center: true,
+        vertical_center: true,
+    };
+
+    /// Compute where a window at `rect` goes inside a parent of `parent` size.
+    pub fn place(&self, margins: Margins, parent: Size, rect: Rect) -> Rect {
+        let (x, width) = anchor_axis(
+            (self.left, self.right, self.horizontal_center),
+            (margins.left, margins.right),
+            parent.width,
+            (rect.pos.x, rect.size.width),
+        );
+        let (y, height) = anchor_axis(
+            (self.top, self.bottom, self.vertical_center),
+            (margins.top, margins.bottom),
+            parent.height,
+            (rect.pos.y, rect.size.height),
+        );
+        rect!(x, y, width, height)
+    }
+}
+
+/// Place a window along one axis, and return its offset and length.
+fn anchor_axis(
+    (start, end, center): (bool, bool, bool),
+    (start_margin, end_margin): (i32, i32),
+    parent: i32,
+    (offset, length): (i32, i32),
+) -> (i32, i32) {
+    match (start, end) {
+        (true, true) => (start_margin, (parent - start_margin - end_margin).max(0)),
+        (true, false) => (start_margin, length),
+        (false, true) => (parent - end_margin - length, length),
+        (false, false) if center => ((parent - length) / 2 + start_margin - end_margin, length),
+        (false, false) => (offset, length),
+    }
+}
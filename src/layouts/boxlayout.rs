@@ -0,0 +1,215 @@
+//! This file contains the box layouts, which line up child windows in a row or a column.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::*;
+
+enum Item {
+    Window { id: WindowID, stretch: u32 },
+    Stretch(u32),
+    Spacing(i32),
+}
+
+/// Lines up child windows in one direction.
+/// Each window fills the layout across the direction, unless its size policy keeps it smaller.
+/// Along the direction, a window with a stretch factor of 0 takes the length of its size hint,
+/// and the space left over is shared by the other items in proportion to their stretch factors.
+/// If no item has a stretch factor, the space left over goes to the windows whose size policy
+/// is `SizePolicy::Expanding`.
+/// The minimum and maximum sizes of the windows are respected.
+/// `HBoxLayout` and `VBoxLayout` are the horizontal and vertical forms of it.
+pub struct BoxLayout {
+    orientation: Orientation,
+    items: Vec<Item>,
+    spacing: i32,
+    margins: Margins,
+}
+
+impl BoxLayout {
+    /// Create an empty layout without spacing and margins.
+    pub fn new(orientation: Orientation) -> Self {
+        Self {
+            orientation,
+            items: Vec::new(),
+            spacing: 0,
+            margins: Margins::default(),
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Add a child window at the end of the layout.
+    pub fn add_widget(&mut self, window: &Window, stretch: u32) {
+        self.items.push(Item::Window {
+            id: window.get_id(),
+            stretch,
+        });
+    }
+
+    /// Add an empty item that takes a share of the free space, as a window with `stretch` would.
+    pub fn add_stretch(&mut self, stretch: u32) {
+        self.items.push(Item::Stretch(stretch));
+    }
+
+    /// Add an empty item of a fixed length.
+    pub fn add_spacing(&mut self, length: i32) {
+        self.items.push(Item::Spacing(length));
+    }
+
+    /// Set the stretch factor of a window in the layout.
+    /// Return false if the window is not in the layout.
+    pub fn set_stretch(&mut self, window: &Window, stretch: u32) -> bool {
+        let target = window.get_id();
+        for item in self.items.iter_mut() {
+            if let Item::Window { id, stretch: s } = item {
+                if *id == target {
+                    *s = stretch;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Remove a window from the layout. The window itself is left where it is.
+    pub fn remove_widget(&mut self, window: &Window) {
+        let target = window.get_id();
+        self.items
+            .retain(|item| !matches!(item, Item::Window { id, .. } if *id == target));
+    }
+
+    pub fn spacing(&self) -> i32 {
+        self.spacing
+    }
+
+    /// Set the space between two neighbouring items.
+    pub fn set_spacing(&mut self, spacing: i32) {
+        self.spacing = spacing;
+    }
+
+    pub fn margins(&self) -> Margins {
+        self.margins
+    }
+
+    /// Set the space between the edges of the parent and the items.
+    pub fn set_margins(&mut self, margins: Margins) {
+        self.margins = margins;
+    }
+}
+
+impl Layout for BoxLayout {
+    fn apply(&mut self, rect: Rect) {
+        let inner = rect.inset(self.margins);
+        let dir = self.orientation;
+        let cross = dir.cross();
+        // without stretch factors, the free space goes to the expanding windows
+        let stretched = self.items.iter().any(|item| match item {
+            Item::Window { stretch, .. } | Item::Stretch(stretch) => *stretch > 0,
+            Item::Spacing(_) => false,
+        });
+        let asks: Vec<(Share, Option<Hints>)> = self
+            .items
+            .iter()
+            .map(|item| match item {
+                Item::Window { id, stretch } => {
+                    let hints = hints(*id);
+                    let expanding = !stretched && hints.expanding(dir);
+                    let share = hints.share(dir, if expanding { 1 } else { *stretch });
+                    (share, Some(hints))
+                }
+                Item::Stretch(stretch) => {
+                    let share = Share {
+                        min: 0,
+                        natural: 0,
+                        max: i32::MAX,
+                        stretch: *stretch,
+                    };
+                    (share, None)
+                }
+                Item::Spacing(length) => (Share::fixed(*length), None),
+            })
+            .collect();
+        let shares: Vec<Share> = asks.iter().map(|ask| ask.0).collect();
+        let gaps = self.spacing * (self.items.len() as i32 - 1).max(0);
+        let lengths = distribute(dir.main(inner.size) - gaps, &shares);
+
+        let (start, cross_start) = match dir {
+            Orientation::Horizontal => (inner.pos.x, inner.pos.y),
+            Orientation::Vertical => (inner.pos.y, inner.pos.x),
+        };
+        let cross_length = cross.main(inner.size);
+        let mut offset = start;
+        for ((item, length), (_, hints)) in self.items.iter().zip(lengths).zip(asks) {
+            if let (Item::Window { id, .. }, Some(hints)) = (item, hints) {
+                // a window that can not fill the layout across is centered
+                let across = cross_length
+                    .min(cross.main(hints.max))
+                    .max(cross.main(hints.min));
+                let at = Align::Center.place(cross_start, cross_length, across);
+                let rect = dir.rect((offset, length), (at, across));
+                Platform::set_window_rect(id.handle, rect);
+            }
+            offset += length + self.spacing;
+        }
+    }
+}
+
+macro_rules! box_layout {
+    ($name:ident, $orientation:expr, $doc:literal) => {
+        #[doc = $doc]
+        /// It is a `BoxLayout`, so all of its methods can be called on it.
+        /// # Example
+        /// ```
+        /// use rusty_gui::*;
+        ///
+        #[doc = concat!("let mut layout = ", stringify!($name), "::new();")]
+        /// layout.set_spacing(4);
+        /// layout.set_margins(Margins::uniform(8));
+        /// ```
+        pub struct $name(BoxLayout);
+
+        impl $name {
+            pub fn new() -> Self {
+                Self(BoxLayout::new($orientation))
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Deref for $name {
+            type Target = BoxLayout;
+            fn deref(&self) -> &BoxLayout {
+                &self.0
+            }
+        }
+
+        impl DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut BoxLayout {
+                &mut self.0
+            }
+        }
+
+        impl Layout for $name {
+            fn apply(&mut self, rect: Rect) {
+                self.0.apply(rect);
+            }
+        }
+    };
+}
+
+box_layout!(
+    HBoxLayout,
+    Orientation::Horizontal,
+    "Lines up child windows from left to right."
+);
+box_layout!(
+    VBoxLayout,
+    Orientation::Vertical,
+    "Lines up child windows from top to bottom."
+);
@@ -0,0 +1,426 @@
+//! This file contains the flex layout, which works like the flexible box layout of CSS.
+//! The geometry is computed from plain `Rect` and `Size` values by `FlexLayout::compute`,
+//! so it does not need any window.
+
+use crate::*;
+
+/// The direction of the main axis of a `FlexLayout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlexDirection {
+    /// From left to right.
+    #[default]
+    Row,
+    /// From right to left.
+    RowReverse,
+    /// From top to bottom.
+    Column,
+    /// From bottom to top.
+    ColumnReverse,
+}
+
+impl FlexDirection {
+    fn orientation(self) -> Orientation {
+        match self {
+            FlexDirection::Row | FlexDirection::RowReverse => Orientation::Horizontal,
+            FlexDirection::Column | FlexDirection::ColumnReverse => Orientation::Vertical,
+        }
+    }
+
+    fn reversed(self) -> bool {
+        matches!(
+            self,
+            FlexDirection::RowReverse | FlexDirection::ColumnReverse
+        )
+    }
+}
+
+/// Whether the items of a `FlexLayout` may be broken into several lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlexWrap {
+    /// All items are in one line, shrinking if needed.
+    #[default]
+    NoWrap,
+    /// A new line is started below (or to the right of) the last one when an item does not fit.
+    Wrap,
+    /// Like `Wrap`, but the lines are stacked from the other side.
+    WrapReverse,
+}
+
+/// How the free space of a line is put between its items along the main axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JustifyContent {
+    /// The items are packed at the start of the line.
+    #[default]
+    Start,
+    /// The items are packed at the end of the line.
+    End,
+    /// The items are packed in the middle of the line.
+    Center,
+    /// The first and the last items touch the ends, and the space is shared between the items.
+    SpaceBetween,
+    /// Every item gets the same space on both of its sides.
+    SpaceAround,
+    /// The spaces between the items and at the ends are all the same.
+    SpaceEvenly,
+}
+
+/// The flex properties of a child of a `FlexLayout`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexItem {
+    /// The share of the free space of the line the item takes.
+    pub grow: f32,
+    /// How much the item gives, relative to its basis, when the line is too small.
+    pub shrink: f32,
+    /// The length of the item along the main axis before it grows or shrinks.
+    /// `None` means its size hint.
+    pub basis: Option<i32>,
+    /// The alignment across the line, instead of the one of the layout.
+    pub align_self: Option<Align>,
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self {
+            grow: 0.0,
+            shrink: 1.0,
+            basis: None,
+            align_self: None,
+        }
+    }
+}
+
+/// An item as seen by `FlexLayout::compute`: its flex properties and its sizes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexBox {
+    pub item: FlexItem,
+    /// The size the item would like to have.
+    pub size: Size,
+    pub min: Size,
+    pub max: Size,
+}
+
+impl FlexBox {
+    /// Create a box of `size` without size limits.
+    pub fn new(item: FlexItem, size: Size) -> Self {
+        Self {
+            item,
+            size,
+            min: size!(0, 0),
+            max: size!(i32::MAX, i32::MAX),
+        }
+    }
+}
+
+/// Places child windows along a main axis, like the flexible box layout of CSS.
+///
+/// Every item starts at its basis, then the items of a line grow into the free space by
+/// their `grow` factors, or shrink by their `shrink` factors if the line is too small,
+/// always within their minimum and maximum sizes.
+/// With wrapping, the lines are as thick as their thickest item and packed at the start.
+/// Without it, the only line fills the layout across.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let mut layout = FlexLayout::new(FlexDirection::Row);
+/// layout.set_wrap(FlexWrap::Wrap);
+/// layout.set_justify_content(JustifyContent::SpaceBetween);
+/// layout.set_gap(10);
+///
+/// let grow = FlexItem { grow: 1.0, ..Default::default() };
+/// let rects = layout.compute(
+///     rect!(0, 0, 200, 100),
+///     &[FlexBox::new(grow, size!(50, 20)), FlexBox::new(FlexItem::default(), size!(40, 30))],
+/// );
+/// assert_eq!(rects, vec![rect!(0, 0, 150, 30), rect!(160, 0, 40, 30)]);
+/// ```
+pub struct FlexLayout {
+    direction: FlexDirection,
+    wrap: FlexWrap,
+    justify_content: JustifyContent,
+    align_items: Align,
+    /// The gaps between the items of a line and between the lines.
+    gap: (i32, i32),
+    margins: Margins,
+    items: Vec<(WindowID, FlexItem)>,
+}
+
+impl FlexLayout {
+    /// Create an empty layout that packs its items at the start of one line,
+    /// and stretches them across it.
+    pub fn new(direction: FlexDirection) -> Self {
+        Self {
+            direction,
+            wrap: FlexWrap::NoWrap,
+            justify_content: JustifyContent::Start,
+            align_items: Align::Fill,
+            gap: (0, 0),
+            margins: Margins::default(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Add a child window at the end of the layout.
+    pub fn add_widget(&mut self, window: &Window, item: FlexItem) {
+        self.items.push((window.get_id(), item));
+    }
+
+    /// Change the flex properties of a window in the layout.
+    /// Return false if the window is not in the layout.
+    pub fn set_item(&mut self, window: &Window, item: FlexItem) -> bool {
+        let id = window.get_id();
+        match self.items.iter_mut().find(|(i, _)| *i == id) {
+            Some((_, old)) => {
+                *old = item;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a window from the layout. The window itself is left where it is.
+    pub fn remove_widget(&mut self, window: &Window) {
+        let id = window.get_id();
+        self.items.retain(|(i, _)| *i != id);
+    }
+
+    pub fn set_direction(&mut self, direction: FlexDirection) {
+        self.direction = direction;
+    }
+
+    pub fn set_wrap(&mut self, wrap: FlexWrap) {
+        self.wrap = wrap;
+    }
+
+    pub fn set_justify_content(&mut self, justify_content: JustifyContent) {
+        self.justify_content = justify_content;
+    }
+
+    /// Set how the items are placed across their line.
+    pub fn set_align_items(&mut self, align_items: Align) {
+        self.align_items = align_items;
+    }
+
+    /// Set the gap between the items of a line and between the lines.
+    pub fn set_gap(&mut self, gap: i32) {
+        self.gap = (gap, gap);
+    }
+
+    /// Set the gap between the items of a line, and the gap between the lines, separately.
+    pub fn set_gaps(&mut self, main: i32, cross: i32) {
+        self.gap = (main, cross);
+    }
+
+    /// Set the space between the edges of the parent and the items.
+    pub fn set_margins(&mut self, margins: Margins) {
+        self.margins = margins;
+    }
+
+    /// Compute the rects of the boxes placed inside `rect`.
+    /// It only uses the settings of the layout, not its windows.
+    pub fn compute(&self, rect: Rect, boxes: &[FlexBox]) -> Vec<Rect> {
+        let inner = rect.inset(self.margins);
+        let dir = self.direction.orientation();
+        let cross = dir.cross();
+        let (main_size, cross_size) = (dir.main(inner.size), cross.main(inner.size));
+        let (main_gap, cross_gap) = self.gap;
+        let (main_start, cross_start) = match dir {
+            Orientation::Horizontal => (inner.pos.x, inner.pos.y),
+            Orientation::Vertical => (inner.pos.y, inner.pos.x),
+        };
+
+        let hypothetical: Vec<i32> = boxes
+            .iter()
+            .map(|b| {
+                let basis = b.item.basis.unwrap_or(dir.main(b.size));
+                basis.min(dir.main(b.max)).max(dir.main(b.min))
+            })
+            .collect();
+        let lines = self.break_lines(&hypothetical, main_size);
+
+        let mut rects = vec![rect!(0, 0, 0, 0); boxes.len()];
+        let mut line_offset = 0;
+        let single = self.wrap == FlexWrap::NoWrap;
+        for line in lines {
+            let line_boxes: Vec<&FlexBox> = line.clone().map(|i| &boxes[i]).collect();
+            let lengths = resolve(
+                &line_boxes,
+                &hypothetical[line.clone()],
+                main_size - main_gap * (line.len() as i32 - 1),
+                dir,
+            );
+            // the cross sizes are the ones the items ask for, but a stretched item fills the line
+            let thickness = if single {
+                cross_size
+            } else {
+                line_boxes
+                    .iter()
+                    .map(|b| {
+                        cross
+                            .main(b.size)
+                            .min(cross.main(b.max))
+                            .max(cross.main(b.min))
+                    })
+                    .max()
+                    .unwrap_or(0)
+            };
+            let used: i32 = lengths.iter().sum::<i32>() + main_gap * (line.len() as i32 - 1);
+            let (mut offset, spacing) = justify(self.justify_content, main_size - used, line.len());
+            for ((i, b), length) in line.zip(line_boxes).zip(lengths) {
+                let align = b.item.align_self.unwrap_or(self.align_items);
+                let wanted = match align {
+                    Align::Fill => thickness,
+                    _ => cross.main(b.size),
+                };
+                let across = wanted.min(cross.main(b.max)).max(cross.main(b.min));
+                let at = align.place(line_offset, thickness, across);
+
+                let start = offset.round() as i32;
+                let end = (offset + length as f32).round() as i32;
+                let main_pos = match self.direction.reversed() {
+                    false => start,
+                    true => main_size - end,
+                };
+                let cross_pos = match self.wrap {
+                    FlexWrap::WrapReverse => cross_size - at - across,
+                    _ => at,
+                };
+                rects[i] = dir.rect(
+                    (main_start + main_pos, end - start),
+                    (cross_start + cross_pos, across),
+                );
+                offset += (length + main_gap) as f32 + spacing;
+            }
+            line_offset += thickness + cross_gap;
+        }
+        rects
+    }
+
+    /// Split the items into lines, so the lengths in every line fit in `main_size`.
+    fn break_lines(&self, lengths: &[i32], main_size: i32) -> Vec<std::ops::Range<usize>> {
+        if self.wrap == FlexWrap::NoWrap {
+            return std::iter::once(0..lengths.len()).collect();
+        }
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut used = 0;
+        for (i, length) in lengths.iter().enumerate() {
+            if i > start && used + self.gap.0 + length > main_size {
+                lines.push(start..i);
+                start = i;
+                used = 0;
+            }
+            used += if i > start { self.gap.0 } else { 0 } + length;
+        }
+        if start < lengths.len() {
+            lines.push(start..lengths.len());
+        }
+        lines
+    }
+}
+
+/// Grow or shrink the items of a line, so their lengths add up to `space` if possible.
+/// An item that reaches its minimum or maximum is frozen there,
+/// and the others share the rest again.
+fn resolve(boxes: &[&FlexBox], hypothetical: &[i32], space: i32, dir: Orientation) -> Vec<i32> {
+    let mut lengths: Vec<f32> = hypothetical.iter().map(|&l| l as f32).collect();
+    let mut frozen = vec![false; boxes.len()];
+    let growing = space > hypothetical.iter().sum::<i32>();
+    loop {
+        let free = space as f32
+            - lengths
+                .iter()
+                .zip(&frozen)
+                .map(|(l, f)| if *f { *l } else { 0.0 })
+                .sum::<f32>()
+            - hypothetical
+                .iter()
+                .zip(&frozen)
+                .map(|(l, f)| if *f { 0.0 } else { *l as f32 })
+                .sum::<f32>();
+        // growing is weighted by the grow factors, shrinking by the shrink factors and the bases
+        let weights: Vec<f32> = boxes
+            .iter()
+            .zip(hypothetical)
+            .map(|(b, &h)| match growing {
+                true => b.item.grow,
+                false => b.item.shrink * h as f32,
+            })
+            .collect();
+        let total: f32 = (0..boxes.len())
+            .filter(|&i| !frozen[i])
+            .map(|i| weights[i])
+            .sum();
+        let mut clamped = false;
+        for i in 0..boxes.len() {
+            if frozen[i] {
+                continue;
+            }
+            let share = if total > 0.0 {
+                free * weights[i] / total
+            } else {
+                0.0
+            };
+            let target = hypothetical[i] as f32 + share;
+            let (min, max) = (dir.main(boxes[i].min) as f32, dir.main(boxes[i].max) as f32);
+            lengths[i] = target.min(max).max(min);
+            if lengths[i] != target {
+                frozen[i] = true;
+                clamped = true;
+            }
+        }
+        if !clamped || total <= 0.0 {
+            break;
+        }
+    }
+    // round the running total, so the lengths add up like the exact ones
+    let mut acc = 0.0;
+    let mut given = 0;
+    lengths
+        .into_iter()
+        .map(|length| {
+            acc += length;
+            let end = acc.round() as i32;
+            let length = end - given;
+            given = end;
+            length
+        })
+        .collect()
+}
+
+/// Get the offset of the first item and the extra space after every item of a line.
+fn justify(justify_content: JustifyContent, free: i32, count: usize) -> (f32, f32) {
+    let (free, count) = (free as f32, count as f32);
+    match justify_content {
+        JustifyContent::Start => (0.0, 0.0),
+        JustifyContent::End => (free, 0.0),
+        JustifyContent::Center => (free / 2.0, 0.0),
+        // without free space, the spaces can not be shared
+        _ if free <= 0.0 => (0.0, 0.0),
+        JustifyContent::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+        JustifyContent::SpaceBetween => (0.0, 0.0),
+        JustifyContent::SpaceAround => (free / count / 2.0, free / count),
+        JustifyContent::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+    }
+}
+
+impl Layout for FlexLayout {
+    fn apply(&mut self, rect: Rect) {
+        let boxes: Vec<FlexBox> = self
+            .items
+            .iter()
+            .map(|(id, item)| {
+                let hints = hints(*id);
+                FlexBox {
+                    item: *item,
+                    size: hints.hint,
+                    min: hints.min,
+                    max: hints.max,
+                }
+            })
+            .collect();
+        for ((id, _), rect) in self.items.iter().zip(self.compute(rect, &boxes)) {
+            Platform::set_window_rect(id.handle, rect);
+        }
+    }
+}
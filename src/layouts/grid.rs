@@ -0,0 +1,328 @@
+//! This file contains the grid layout, which places child windows in the cells of a table.
+
+use crate::*;
+
+struct Cell {
+    id: WindowID,
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+    alignment: Alignment,
+}
+
+/// The sizing of a row or a column.
+#[derive(Debug, Clone, Copy, Default)]
+struct Track {
+    min: i32,
+    preferred: i32,
+    stretch: u32,
+}
+
+/// Places child windows in the cells of a table.
+///
+/// A window occupies `row_span` rows and `column_span` columns starting at its cell.
+/// Every row and column is at least as large as its minimum and the minimum sizes of the
+/// windows in it, and it takes its preferred length, or the size hints of the windows in it
+/// if they are larger, when there is space for it.
+/// The space left over is shared by the rows and the columns with a stretch factor,
+/// or by the ones holding a window with the `SizePolicy::Expanding` policy if none has one.
+/// Inside its cells, a window is placed by its alignment, and it fills them by default.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let mut form = Block::create(rect!(50, 50, 400, 300), None);
+/// let name = LineEdit::create("Name", rect!(0, 0, 100, 30), form.as_window());
+/// let ok = PushButton::create("OK", rect!(0, 0, 80, 30), form.as_window());
+///
+/// let mut grid = GridLayout::new();
+/// grid.set_spacing(8);
+/// grid.add_widget(name.as_window(), 0, 0, 1, 2);
+/// grid.add_widget(ok.as_window(), 1, 1, 1, 1);
+/// grid.set_alignment(ok.as_window(), Alignment::new(Align::End, Align::Start));
+/// grid.set_column_stretch(0, 1);
+/// form.as_window_mut().set_layout(grid);
+/// ```
+pub struct GridLayout {
+    cells: Vec<Cell>,
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    horizontal_spacing: i32,
+    vertical_spacing: i32,
+    margins: Margins,
+}
+
+impl Default for GridLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GridLayout {
+    /// Create an empty grid without spacing and margins.
+    pub fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+            rows: Vec::new(),
+            columns: Vec::new(),
+            horizontal_spacing: 0,
+            vertical_spacing: 0,
+            margins: Margins::default(),
+        }
+    }
+
+    /// Put a child window in the grid, covering `row_span` rows and `column_span` columns
+    /// from the cell at `row` and `column`.
+    /// # Panics
+    /// If a span is 0.
+    pub fn add_widget(
+        &mut self,
+        window: &Window,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) {
+        if row_span == 0 || column_span == 0 {
+            panic!("The span of a grid cell cannot be 0.");
+        }
+        self.cells.push(Cell {
+            id: window.get_id(),
+            row,
+            column,
+            row_span,
+            column_span,
+            alignment: Alignment::FILL,
+        });
+    }
+
+    /// Set how a window is placed inside its cells.
+    /// Return false if the window is not in the grid.
+    pub fn set_alignment(&mut self, window: &Window, alignment: Alignment) -> bool {
+        let id = window.get_id();
+        match self.cells.iter_mut().find(|cell| cell.id == id) {
+            Some(cell) => {
+                cell.alignment = alignment;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a window from the grid. The window itself is left where it is.
+    pub fn remove_widget(&mut self, window: &Window) {
+        let id = window.get_id();
+        self.cells.retain(|cell| cell.id != id);
+    }
+
+    /// Get the number of rows, including the ones only given a sizing.
+    pub fn row_count(&self) -> usize {
+        let used = self.cells.iter().map(|c| c.row + c.row_span);
+        used.max().unwrap_or(0).max(self.rows.len())
+    }
+
+    /// Get the number of columns, including the ones only given a sizing.
+    pub fn column_count(&self) -> usize {
+        let used = self.cells.iter().map(|c| c.column + c.column_span);
+        used.max().unwrap_or(0).max(self.columns.len())
+    }
+
+    fn track(tracks: &mut Vec<Track>, index: usize) -> &mut Track {
+        if tracks.len() <= index {
+            tracks.resize(index + 1, Track::default());
+        }
+        &mut tracks[index]
+    }
+
+    pub fn set_row_minimum_height(&mut self, row: usize, height: i32) {
+        Self::track(&mut self.rows, row).min = height;
+    }
+
+    /// Set the height the row takes if there is space for it.
+    pub fn set_row_preferred_height(&mut self, row: usize, height: i32) {
+        Self::track(&mut self.rows, row).preferred = height;
+    }
+
+    /// Set the share of the free space the row takes.
+    pub fn set_row_stretch(&mut self, row: usize, stretch: u32) {
+        Self::track(&mut self.rows, row).stretch = stretch;
+    }
+
+    pub fn set_column_minimum_width(&mut self, column: usize, width: i32) {
+        Self::track(&mut self.columns, column).min = width;
+    }
+
+    /// Set the width the column takes if there is space for it.
+    pub fn set_column_preferred_width(&mut self, column: usize, width: i32) {
+        Self::track(&mut self.columns, column).preferred = width;
+    }
+
+    /// Set the share of the free space the column takes.
+    pub fn set_column_stretch(&mut self, column: usize, stretch: u32) {
+        Self::track(&mut self.columns, column).stretch = stretch;
+    }
+
+    /// Set the space between both the rows and the columns.
+    pub fn set_spacing(&mut self, spacing: i32) {
+        self.horizontal_spacing = spacing;
+        self.vertical_spacing = spacing;
+    }
+
+    /// Set the space between two neighbouring columns.
+    pub fn set_horizontal_spacing(&mut self, spacing: i32) {
+        self.horizontal_spacing = spacing;
+    }
+
+    /// Set the space between two neighbouring rows.
+    pub fn set_vertical_spacing(&mut self, spacing: i32) {
+        self.vertical_spacing = spacing;
+    }
+
+    pub fn margins(&self) -> Margins {
+        self.margins
+    }
+
+    /// Set the space between the edges of the parent and the cells.
+    pub fn set_margins(&mut self, margins: Margins) {
+        self.margins = margins;
+    }
+}
+
+/// What a window asks of the rows or the columns it covers.
+struct Span {
+    first: usize,
+    count: usize,
+    min: i32,
+    natural: i32,
+    expanding: bool,
+}
+
+/// Compute the offset and the length of every row or column.
+fn place_tracks(
+    tracks: &[Track],
+    count: usize,
+    spans: &[Span],
+    start: i32,
+    total: i32,
+    spacing: i32,
+) -> Vec<(i32, i32)> {
+    let mut tracks = tracks.to_vec();
+    tracks.resize(count.max(tracks.len()), Track::default());
+    // without stretch factors, the free space goes to the tracks of the expanding windows
+    let stretched = tracks.iter().any(|t| t.stretch > 0);
+    for span in spans.iter().filter(|s| s.count == 1) {
+        let track = &mut tracks[span.first];
+        track.min = track.min.max(span.min);
+        track.preferred = track.preferred.max(span.natural);
+        if !stretched && span.expanding {
+            track.stretch = 1;
+        }
+    }
+    // a window over several tracks makes all of them larger if it does not fit
+    for span in spans.iter().filter(|s| s.count > 1) {
+        let covered = &mut tracks[span.first..span.first + span.count];
+        let gaps = spacing * (span.count as i32 - 1);
+        let missing = span.min - covered.iter().map(|t| t.min).sum::<i32>() - gaps;
+        if missing > 0 {
+            let count = span.count as i32;
+            for (i, track) in covered.iter_mut().enumerate() {
+                track.min += missing / count + ((i as i32) < missing % count) as i32;
+            }
+        }
+        if !stretched && span.expanding {
+            covered.iter_mut().for_each(|t| t.stretch = 1);
+        }
+    }
+    let shares: Vec<Share> = tracks
+        .iter()
+        .map(|t| Share {
+            min: t.min,
+            natural: t.preferred.max(t.min),
+            max: i32::MAX,
+            stretch: t.stretch,
+        })
+        .collect();
+    let gaps = spacing * (tracks.len() as i32 - 1).max(0);
+    let mut offset = start;
+    distribute(total - gaps, &shares)
+        .into_iter()
+        .map(|length| {
+            let track = (offset, length);
+            offset += length + spacing;
+            track
+        })
+        .collect()
+}
+
+impl Layout for GridLayout {
+    fn apply(&mut self, rect: Rect) {
+        let inner = rect.inset(self.margins);
+        let hints: Vec<Hints> = self.cells.iter().map(|c| hints(c.id)).collect();
+        // only a real size hint makes a track larger, not the size the grid gave before
+        let spans = |dir: Orientation| -> Vec<Span> {
+            self.cells
+                .iter()
+                .zip(&hints)
+                .map(|(cell, hints)| {
+                    let (first, count) = match dir {
+                        Orientation::Horizontal => (cell.column, cell.column_span),
+                        Orientation::Vertical => (cell.row, cell.row_span),
+                    };
+                    Span {
+                        first,
+                        count,
+                        min: dir.main(hints.min),
+                        natural: if hints.hinted {
+                            dir.main(hints.hint)
+                        } else {
+                            0
+                        },
+                        expanding: hints.expanding(dir),
+                    }
+                })
+                .collect()
+        };
+        let rows = place_tracks(
+            &self.rows,
+            self.row_count(),
+            &spans(Orientation::Vertical),
+            inner.pos.y,
+            inner.size.height,
+            self.vertical_spacing,
+        );
+        let columns = place_tracks(
+            &self.columns,
+            self.column_count(),
+            &spans(Orientation::Horizontal),
+            inner.pos.x,
+            inner.size.width,
+            self.horizontal_spacing,
+        );
+
+        let span = |tracks: &[(i32, i32)], first: usize, count: usize| {
+            let (start, _) = tracks[first];
+            let (last, length) = tracks[first + count - 1];
+            (start, last + length - start)
+        };
+        let fit = |align: Align, space: i32, hint: i32, min: i32, max: i32| match align {
+            Align::Fill => space.min(max).max(min),
+            _ => hint.min(space).min(max).max(min),
+        };
+        for (cell, hints) in self.cells.iter().zip(hints) {
+            let (x, space_x) = span(&columns, cell.column, cell.column_span);
+            let (y, space_y) = span(&rows, cell.row, cell.row_span);
+            let (h, v) = (cell.alignment.horizontal, cell.alignment.vertical);
+            let (min, hint, max) = (hints.min, hints.hint, hints.max);
+            let width = fit(h, space_x, hint.width, min.width, max.width);
+            let height = fit(v, space_y, hint.height, min.height, max.height);
+            let rect = rect!(
+                h.place(x, space_x, width),
+                v.place(y, space_y, height),
+                width,
+                height,
+            );
+            Platform::set_window_rect(cell.id.handle, rect);
+        }
+    }
+}
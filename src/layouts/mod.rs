@@ -0,0 +1,295 @@
+//! Layouts place the child windows of a window automatically.
+//! A layout is installed on the parent with `Window::set_layout`,
+//! and it is applied again every time the parent gets `Event::WindowResized`.
+
+mod anchors;
+mod boxlayout;
+mod flex;
+mod grid;
+
+pub use anchors::*;
+pub use boxlayout::*;
+pub use flex::*;
+pub use grid::*;
+
+use crate::*;
+
+/// A trait for the objects that place child windows inside their parent.
+pub trait Layout {
+    /// Place the managed windows inside `rect`, which is the client area of the parent.
+    fn apply(&mut self, rect: Rect);
+}
+
+/// The direction in which a layout lines up its items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    /// Get the length of `size` along the direction.
+    pub(crate) fn main(self, size: Size) -> i32 {
+        match self {
+            Orientation::Horizontal => size.width,
+            Orientation::Vertical => size.height,
+        }
+    }
+
+    /// Get the other direction.
+    pub(crate) fn cross(self) -> Orientation {
+        match self {
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Horizontal,
+        }
+    }
+
+    /// Build a rect from its offset and length along the direction and across it.
+    pub(crate) fn rect(self, main: (i32, i32), cross: (i32, i32)) -> Rect {
+        match self {
+            Orientation::Horizontal => rect!(main.0, cross.0, main.1, cross.1),
+            Orientation::Vertical => rect!(cross.0, main.0, cross.1, main.1),
+        }
+    }
+}
+
+/// How much of the space along one direction an item asks for.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Share {
+    pub min: i32,
+    pub natural: i32,
+    pub max: i32,
+    pub stretch: u32,
+}
+
+impl Share {
+    pub fn fixed(length: i32) -> Self {
+        Self {
+            min: length,
+            natural: length,
+            max: length,
+            stretch: 0,
+        }
+    }
+}
+
+/// Split `total` pixels among the items.
+/// Items without a stretch factor start at their natural length, and the others at their
+/// minimum. The space left over is shared by the items with a stretch factor in proportion
+/// to it, as long as none of them goes below its minimum or above its maximum.
+/// If there is not enough space, the items shrink towards their minimum in proportion to how
+/// much each of them can give.
+pub(crate) fn distribute(total: i32, items: &[Share]) -> Vec<i32> {
+    let mut lengths: Vec<i32> = items
+        .iter()
+        .map(|i| match i.stretch {
+            0 => i.natural.min(i.max).max(i.min),
+            _ => i.min,
+        })
+        .collect();
+    let extra = total - lengths.iter().sum::<i32>();
+    if extra < 0 {
+        let room: Vec<i64> = items
+            .iter()
+            .zip(&lengths)
+            .map(|(item, length)| (length - item.min) as i64)
+            .collect();
+        let deficit = -extra as i64;
+        let total_room: i64 = room.iter().sum();
+        if total_room <= deficit {
+            return items.iter().map(|i| i.min).collect();
+        }
+        let mut acc = 0;
+        let mut taken = 0;
+        for (length, room) in lengths.iter_mut().zip(room) {
+            // round the running total, so the lengths add up to `total` exactly
+            acc += room;
+            let end = deficit * acc / total_room;
+            *length -= (end - taken) as i32;
+            taken = end;
+        }
+        return lengths;
+    }
+    let mut growing: Vec<usize> = (0..items.len()).filter(|&i| items[i].stretch > 0).collect();
+    let mut pool = extra as i64 + growing.iter().map(|&i| lengths[i] as i64).sum::<i64>();
+    while !growing.is_empty() {
+        let stretch: i64 = growing.iter().map(|&i| items[i].stretch as i64).sum();
+        let mut acc = 0;
+        let mut given = 0;
+        let shares: Vec<i64> = growing
+            .iter()
+            .map(|&i| {
+                acc += items[i].stretch as i64;
+                let end = pool * acc / stretch;
+                let share = end - given;
+                given = end;
+                share
+            })
+            .collect();
+        // an item out of its limits is held at the limit, and the rest is shared again
+        let below = |(&i, &share): (&usize, &i64)| share < items[i].min as i64;
+        let above = |(&i, &share): (&usize, &i64)| share > items[i].max as i64;
+        let held: Vec<(usize, i32)> = if growing.iter().zip(&shares).any(below) {
+            let held = growing.iter().zip(&shares).filter(|s| below(*s));
+            held.map(|(&i, _)| (i, items[i].min)).collect()
+        } else if growing.iter().zip(&shares).any(above) {
+            let held = growing.iter().zip(&shares).filter(|s| above(*s));
+            held.map(|(&i, _)| (i, items[i].max)).collect()
+        } else {
+            for (&i, share) in growing.iter().zip(shares) {
+                lengths[i] = share as i32;
+            }
+            break;
+        };
+        for (i, length) in held {
+            lengths[i] = length;
+            pool -= length as i64;
+            growing.retain(|&g| g != i);
+        }
+    }
+    lengths
+}
+
+/// How a window may be resized by a layout along one direction, relative to its size hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizePolicy {
+    /// The window always has the length of its size hint.
+    Fixed,
+    /// The size hint is the smallest length, but the window may grow.
+    Minimum,
+    /// The size hint is the best length. The window may shrink down to its minimum size hint,
+    /// and it may grow.
+    #[default]
+    Preferred,
+    /// Like `Preferred`, but the window wants all the space it can get.
+    /// In a layout without stretch factors, the free space goes to the expanding windows.
+    Expanding,
+}
+
+/// What a window asks of a layout.
+pub(crate) struct Hints {
+    pub min: Size,
+    pub hint: Size,
+    pub max: Size,
+    /// If the element has a size hint, rather than falling back to the current size.
+    pub hinted: bool,
+    pub expanding: (bool, bool),
+}
+
+impl Hints {
+    /// Get the share of the space the window asks for along `dir`.
+    pub fn share(&self, dir: Orientation, stretch: u32) -> Share {
+        Share {
+            min: dir.main(self.min),
+            natural: dir.main(self.hint),
+            max: dir.main(self.max),
+            stretch,
+        }
+    }
+
+    pub fn expanding(&self, dir: Orientation) -> bool {
+        match dir {
+            Orientation::Horizontal => self.expanding.0,
+            Orientation::Vertical => self.expanding.1,
+        }
+    }
+}
+
+/// Collect the size hints, the size policy and the size limits of the window.
+/// The limits set on the window, such as `Window::min_width`, win over the size policy.
+pub(crate) fn hints(id: WindowID) -> Hints {
+    let current = Platform::window_rect(id.handle).size;
+    let mut hints = Hints {
+        min: size!(0, 0),
+        hint: current,
+        max: size!(i32::MAX, i32::MAX),
+        hinted: false,
+        expanding: (false, false),
+    };
+    Platform::visit_window(id.handle, &mut |ele| {
+        let window = ele.as_window();
+        let hint = ele.size_hint();
+        let min_hint = ele.minimum_size_hint().unwrap_or(size!(0, 0));
+        let (horizontal, vertical) = window.size_policy;
+        let axis = |policy, hint: i32, min_hint: i32, min: Option<i32>, max: Option<i32>| {
+            let (policy_min, policy_max) = match policy {
+                SizePolicy::Fixed => (hint, hint),
+                SizePolicy::Minimum => (hint, i32::MAX),
+                SizePolicy::Preferred | SizePolicy::Expanding => (min_hint, i32::MAX),
+            };
+            let min = min.unwrap_or(policy_min);
+            let max = max.unwrap_or(policy_max).max(min);
+            (min, hint.min(max).max(min), max)
+        };
+        let hint_size = hint.unwrap_or(current);
+        let (min_w, hint_w, max_w) = axis(
+            horizontal,
+            hint_size.width,
+            min_hint.width,
+            window.min_width,
+            window.max_width,
+        );
+        let (min_h, hint_h, max_h) = axis(
+            vertical,
+            hint_size.height,
+            min_hint.height,
+            window.min_height,
+            window.max_height,
+        );
+        hints = Hints {
+            min: size!(min_w, min_h),
+            hint: size!(hint_w, hint_h),
+            max: size!(max_w, max_h),
+            hinted: hint.is_some(),
+            expanding: (
+                horizontal == SizePolicy::Expanding,
+                vertical == SizePolicy::Expanding,
+            ),
+        };
+    });
+    hints
+}
+
+/// Where an item is put inside a space larger than itself, along one direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    /// The item is stretched over the whole space.
+    #[default]
+    Fill,
+    Start,
+    Center,
+    End,
+}
+
+impl Align {
+    /// Place an item of `length` inside the space starting at `start`, and return its offset.
+    /// The length of a filling item is expected to be the length of the space.
+    pub(crate) fn place(self, start: i32, space: i32, length: i32) -> i32 {
+        match self {
+            Align::Fill | Align::Start => start,
+            Align::Center => start + (space - length) / 2,
+            Align::End => start + space - length,
+        }
+    }
+}
+
+/// Where an item is put inside a cell larger than itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Alignment {
+    pub horizontal: Align,
+    pub vertical: Align,
+}
+
+impl Alignment {
+    /// The item fills the cell.
+    pub const FILL: Alignment = Alignment::new(Align::Fill, Align::Fill);
+    /// The item is put in the middle of the cell.
+    pub const CENTER: Alignment = Alignment::new(Align::Center, Align::Center);
+
+    pub const fn new(horizontal: Align, vertical: Align) -> Self {
+        Self {
+            horizontal,
+            vertical,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod core;
+mod layouts;
 mod syslayer;
 mod types;
 mod utils;
@@ -7,6 +8,7 @@ mod widgets;
 pub use syslayer::*;
 
 pub use core::*;
+pub use layouts::*;
 pub use types::*;
 pub use utils::*;
 pub use widgets::*;
\ No newline at end of file
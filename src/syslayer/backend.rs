@@ -0,0 +1,123 @@
+//! This file defines the `Backend` trait, which is the only way `core` talks to the platform.
+//! Each module of `syslayer` implements it for one platform, and `Platform` selects the one in use.
+
+use std::{any::Any, os::raw::c_void, ptr::null_mut};
+
+use crate::*;
+
+/// An opaque handle to a window, a device context or a drawing object owned by the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Handle(pub(crate) *mut c_void);
+
+impl Handle {
+    pub const NULL: Handle = Handle(null_mut());
+
+    pub fn is_null(&self) -> bool {
+        self.0.is_null()
+    }
+}
+
+/// The callback type used to visit child windows.
+pub(crate) type ChildVisitor = Box<dyn FnMut(&mut dyn Ele)>;
+
+/// The interface every platform has to provide.
+/// All functions are associated functions, because the platform is chosen at compile time.
+pub(crate) trait Backend {
+    // application
+
+    /// Initialize the platform. The console is detached if `show_console` is false.
+    fn init(show_console: bool);
+    /// Run the event loop until the application exits.
+    fn event_loop();
+    /// Ask the event loop to exit with `code`.
+    #[allow(unused)]
+    fn exit(code: i32);
+    /// Get the scale factor of the screen.
+    fn dpi_scale() -> f32;
+
+    // window management
+
+    /// Create a window whose events are delivered to `widget`.
+    fn create_window<T: Ele>(
+        title: &str,
+        rect: Rect,
+        parent: Option<Handle>,
+        widget: &Widget<T>,
+    ) -> Handle;
+    /// Stop delivering events to the element of the window, because it is being freed.
+    fn release_window(window: Handle);
+    fn window_rect(window: Handle) -> Rect;
+    fn absolute_rect(window: Handle) -> Rect;
+    /// Get the position of the window relative to the client area of its parent,
+    /// or to the screen for a top-level window.
+    fn window_pos(window: Handle) -> Point;
+    fn parent_window(window: Handle) -> Option<Handle>;
+    /// Get the direct children of the window.
+    fn child_windows(window: Handle) -> Vec<Handle>;
+    fn set_window_rect(window: Handle, rect: Rect);
+    fn set_window_pos(window: Handle, pos: Point);
+    fn set_window_size(window: Handle, size: Size);
+    fn window_title(window: Handle) -> String;
+    fn set_window_title(window: Handle, title: &str);
+    fn show_window(window: Handle);
+    fn set_window_visible(window: Handle, visible: bool);
+    fn minimize_window(window: Handle);
+    fn maximize_window(window: Handle);
+    fn restore_window(window: Handle);
+    fn disable_maximize(window: Handle);
+    fn set_window_enabled(window: Handle, enabled: bool);
+    #[allow(unused)]
+    fn window_style(window: Handle) -> WindowStyle;
+    #[allow(unused)]
+    fn set_window_style(window: Handle, style: WindowStyle);
+    /// Check if the window and all of its ancestors are visible.
+    fn is_window_visible(window: Handle) -> bool;
+    fn is_window_enabled(window: Handle) -> bool;
+    /// Give the focus to the window. The window that had it gets `Event::FocusOut`,
+    /// then the window gets `Event::FocusIn`.
+    fn set_focus(window: Handle);
+    fn has_focus(window: Handle) -> bool;
+    /// Redraw the window.
+    fn update_window(window: Handle);
+    /// Call `f` for every descendant of the window.
+    fn for_each_child(window: Handle, f: ChildVisitor);
+    /// Call `f` with the element of the window, unless it has been released.
+    fn visit_window(window: Handle, f: &mut dyn FnMut(&mut dyn Ele));
+
+    // input pump
+
+    /// Deliver a user-defined message to the element of the window.
+    fn send_message(window: Handle, msg: Box<dyn Any>);
+    /// Deliver `Event::WindowCreated` to the element of the window.
+    fn send_created(window: Handle);
+    fn register_hotkey(window: Handle, id: i32, key: KeyCode, modifiers: HotKeyFlags);
+    /// Deliver the input event to the element of the window as if it came from the user.
+    fn send_input(window: Handle, event: Event);
+    /// Find the window at `pos` on the screen that would receive mouse input there,
+    /// and return it with `pos` relative to its client area.
+    fn window_at(pos: Point) -> Option<(Handle, Point)>;
+    /// Get the window that has the keyboard focus.
+    fn focused_window() -> Option<Handle>;
+
+    // timers
+
+    fn set_timer(window: Handle, id: usize, interval: u32);
+    fn kill_timer(window: Handle, id: usize);
+
+    // clipboard
+
+    fn clipboard_text() -> Option<String>;
+    fn set_clipboard_text(text: &str);
+    fn clipboard_image() -> Option<Image>;
+    fn set_clipboard_image(image: &Image);
+
+    // drawing objects
+
+    fn new_pen(style: &PenStyle) -> Handle;
+    fn new_brush(color: Color) -> Handle;
+    fn new_font(style: &FontStyle) -> Handle;
+    /// Measure the single-line `text` drawn with `font`.
+    fn text_extent(font: &FontStyle, text: &str) -> Size;
+    fn font_metrics(font: &FontStyle) -> FontMetrics;
+    fn delete_object(obj: Handle);
+}
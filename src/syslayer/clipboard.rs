@@ -0,0 +1,41 @@
+//! This file contains the `Clipboard` struct, which shares data with other applications.
+
+use crate::*;
+
+/// The clipboard of the system.
+/// It holds one piece of data at a time, so putting text on it removes an image, and the
+/// other way around. In headless mode, the clipboard only lives in the memory of the
+/// application.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let _app = Application::new(true);
+/// Clipboard::set_text("Hello");
+/// assert_eq!(Clipboard::get_text().as_deref(), Some("Hello"));
+/// ```
+pub struct Clipboard;
+
+impl Clipboard {
+    /// Get the text on the clipboard.
+    /// Return None if the clipboard does not hold text, or it can not be opened.
+    pub fn get_text() -> Option<String> {
+        Platform::clipboard_text()
+    }
+
+    /// Put `text` on the clipboard in place of its data.
+    pub fn set_text(text: &str) {
+        Platform::set_clipboard_text(text);
+    }
+
+    /// Get the image on the clipboard.
+    /// Return None if the clipboard does not hold an image in a format that can be read.
+    pub fn get_image() -> Option<Image> {
+        Platform::clipboard_image()
+    }
+
+    /// Put `image` on the clipboard in place of its data.
+    pub fn set_image(image: &Image) {
+        Platform::set_clipboard_image(image);
+    }
+}
@@ -0,0 +1,86 @@
+//! This file contains the delivery of input events through the window tree, which is shared by
+//! all the backends. An input event first goes down from the top-level window to the parent of
+//! its target in the capture phase, then to the target, and bubbles back up from the parent
+//! until an element handles it.
+//! The key events are matched against the shortcuts of the application first.
+//! Clicks give the focus to the windows that take it when clicked, and Tab and Shift+Tab move
+//! the focus when no element handles them.
+
+use crate::*;
+
+/// Deliver `event` to the element of `window`.
+/// Events that are not input are only delivered to that element.
+/// The elements are only borrowed while they handle the event, because changing the focus
+/// delivers `Event::FocusIn` and `Event::FocusOut` to them too.
+pub(crate) fn deliver(window: Handle, event: &Event) -> EventResult {
+    if !event.is_input() {
+        let result = with_element(window, |ele| ele.on_event(event));
+        return result.unwrap_or(EventResult::Ignored);
+    }
+    if match_shortcut(window, event) == EventResult::Handled {
+        return EventResult::Handled;
+    }
+    if let Event::MouseButtonPressed { .. } = event {
+        let takes_focus =
+            with_element(window, |ele| ele.as_window().focus_policy().accepts_click());
+        if takes_focus == Some(true) && !Platform::has_focus(window) {
+            Platform::set_focus(window);
+        }
+    }
+    let result = propagate(window, event);
+    match event {
+        Event::KeyPressed {
+            key: KeyCode::Tab,
+            mods,
+            ..
+        } if result == EventResult::Ignored && !mods.alt() && !mods.meta() => {
+            match move_focus(window, mods.shift()) {
+                true => EventResult::Handled,
+                false => EventResult::Ignored,
+            }
+        }
+        _ => result,
+    }
+}
+
+/// Run `f` with the element of `window`, if the window has one.
+fn with_element<R>(window: Handle, mut f: impl FnMut(&mut dyn Ele) -> R) -> Option<R> {
+    let mut result = None;
+    Platform::visit_window(window, &mut |ele| result = Some(f(ele)));
+    result
+}
+
+/// Deliver the input `event` to the ancestors of `window` in the capture phase, then to
+/// `window`, then to the ancestors in the bubble phase, until one of them handles it.
+fn propagate(window: Handle, event: &Event) -> EventResult {
+    // the ancestors from the parent up, with the position of the window in each of them
+    let mut ancestors = Vec::new();
+    let (mut current, mut offset) = (window, pos!(0, 0));
+    while let Some(parent) = Platform::parent_window(current) {
+        offset += Platform::window_pos(current);
+        ancestors.push((parent, offset));
+        current = parent;
+    }
+    let visit = |window: Handle, offset: Point, capture: bool| {
+        let event = event.translated(offset);
+        let result = with_element(window, |ele| match capture {
+            true => ele.on_capture(&event),
+            false => ele.on_event(&event),
+        });
+        result == Some(EventResult::Handled)
+    };
+    for (ancestor, offset) in ancestors.iter().rev() {
+        if visit(*ancestor, *offset, true) {
+            return EventResult::Handled;
+        }
+    }
+    if visit(window, pos!(0, 0), false) {
+        return EventResult::Handled;
+    }
+    for (ancestor, offset) in ancestors {
+        if visit(ancestor, offset, false) {
+            return EventResult::Handled;
+        }
+    }
+    EventResult::Ignored
+}
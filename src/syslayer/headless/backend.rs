@@ -0,0 +1,455 @@
+use std::{any::Any, collections::HashMap};
+
+use crate::*;
+
+use super::super::software;
+use super::*;
+
+/// The backend that keeps every window in memory.
+pub(crate) struct Headless;
+
+/// Move and resize the window, sending `WindowMoved` and `WindowResized` if they changed.
+fn move_window(window: Handle, rect: Rect, ty: WindowSize) {
+    let id = to_id(window.0);
+    let old = with_tree(|tree| {
+        let node = tree.node_mut(id);
+        let old = node.rect;
+        node.rect = rect;
+        if let WindowSize::Resize | WindowSize::Restore = ty {
+            node.normal_rect = rect;
+        }
+        if old.size != rect.size {
+            tree.invalidate(id);
+        }
+        old
+    });
+    if old.pos != rect.pos {
+        dispatch(id, Event::WindowMoved { pos: rect.pos });
+    }
+    if old.size != rect.size || ty != WindowSize::Restore {
+        dispatch(
+            id,
+            Event::WindowResized {
+                size: rect.size,
+                ty,
+            },
+        );
+    }
+}
+
+impl Backend for Headless {
+    /// There is no console to detach from, and the screen is never scaled.
+    fn init(_show_console: bool) {}
+
+    /// Process everything that is pending and return when there is nothing left to do.
+    /// Timers are fired in virtual time, so the loop never sleeps.
+    /// It also returns as soon as `exit` has been called.
+    fn event_loop() {
+        loop {
+            if with_tree(|tree| tree.quit.take()).is_some() {
+                break;
+            }
+            let dirty = with_tree(|tree| {
+                if tree.dirty.is_empty() {
+                    None
+                } else {
+                    Some(tree.dirty.remove(0))
+                }
+            });
+            if let Some(id) = dirty {
+                paint(id);
+                continue;
+            }
+            let timer = with_tree(|tree| {
+                let (id, timer_id, due) = tree
+                    .nodes
+                    .iter()
+                    .filter(|(_, node)| !node.object.is_null())
+                    .flat_map(|(id, node)| {
+                        node.timers
+                            .iter()
+                            .map(move |(t, timer)| (*id, *t, timer.due))
+                    })
+                    .min_by_key(|(id, t, due)| (*due, *id, *t))?;
+                tree.clock = tree.clock.max(due);
+                let timer = tree.node_mut(id).timers.get_mut(&timer_id).unwrap();
+                timer.due = due + timer.interval.max(1) as u64;
+                Some((id, timer_id))
+            });
+            if let Some((id, timer_id)) = timer {
+                dispatch(id, Event::Timer { id: timer_id });
+                continue;
+            }
+            break;
+        }
+    }
+
+    fn exit(code: i32) {
+        with_tree(|tree| tree.quit = Some(code));
+    }
+
+    fn dpi_scale() -> f32 {
+        1.0
+    }
+
+    fn create_window<T: Ele>(
+        title: &str,
+        rect: Rect,
+        parent: Option<Handle>,
+        widget: &Widget<T>,
+    ) -> Handle {
+        with_tree(|tree| {
+            let id = tree.next_id;
+            tree.next_id += 1;
+            let parent = parent.map(|parent| to_id(parent.0));
+            if let Some(parent) = parent {
+                tree.node_mut(parent).children.push(id);
+            }
+            tree.nodes.insert(
+                id,
+                Node {
+                    object: widget.addr() as _,
+                    parent,
+                    children: Vec::new(),
+                    title: title.to_string(),
+                    rect,
+                    normal_rect: rect,
+                    visible: false,
+                    enabled: true,
+                    style: WindowStyle {
+                        child: parent.is_some(),
+                        ..Default::default()
+                    },
+                    timers: HashMap::new(),
+                    hotkeys: HashMap::new(),
+                },
+            );
+            Handle(to_hwnd(id))
+        })
+    }
+
+    fn release_window(window: Handle) {
+        with_tree(|tree| {
+            let id = to_id(window.0);
+            let Some(node) = tree.nodes.get(&id) else {
+                return;
+            };
+            if let Some(parent) = node.parent.and_then(|parent| tree.nodes.get_mut(&parent)) {
+                parent.children.retain(|child| *child != id);
+            }
+            // the child windows go with it, as they do when a window is destroyed
+            for id in tree.descendants(id).into_iter().chain([id]) {
+                tree.nodes.remove(&id);
+            }
+            if tree.focus.is_some_and(|focus| !tree.nodes.contains_key(&focus)) {
+                tree.focus = None;
+            }
+            tree.dirty.retain(|dirty| tree.nodes.contains_key(dirty));
+        });
+    }
+
+    fn window_rect(window: Handle) -> Rect {
+        let size = with_tree(|tree| tree.node(to_id(window.0)).rect.size);
+        rect!(0, 0, size.width, size.height)
+    }
+
+    fn absolute_rect(window: Handle) -> Rect {
+        with_tree(|tree| {
+            let node = tree.node(to_id(window.0));
+            let mut rect = node.rect;
+            let mut parent = node.parent;
+            while let Some(id) = parent {
+                let node = tree.node(id);
+                rect.pos += node.rect.pos;
+                parent = node.parent;
+            }
+            rect
+        })
+    }
+
+    fn window_pos(window: Handle) -> Point {
+        with_tree(|tree| tree.node(to_id(window.0)).rect.pos)
+    }
+
+    fn parent_window(window: Handle) -> Option<Handle> {
+        with_tree(|tree| {
+            tree.node(to_id(window.0))
+                .parent
+                .map(|id| Handle(to_hwnd(id)))
+        })
+    }
+
+    fn child_windows(window: Handle) -> Vec<Handle> {
+        with_tree(|tree| {
+            let children = tree.node(to_id(window.0)).children.iter();
+            children.map(|id| Handle(to_hwnd(*id))).collect()
+        })
+    }
+
+    fn set_window_rect(window: Handle, rect: Rect) {
+        move_window(window, rect, WindowSize::Restore);
+    }
+
+    fn set_window_pos(window: Handle, pos: Point) {
+        let size = with_tree(|tree| tree.node(to_id(window.0)).rect.size);
+        Self::set_window_rect(window, Rect { pos, size });
+    }
+
+    fn set_window_size(window: Handle, size: Size) {
+        let pos = with_tree(|tree| tree.node(to_id(window.0)).rect.pos);
+        Self::set_window_rect(window, Rect { pos, size });
+    }
+
+    fn window_title(window: Handle) -> String {
+        with_tree(|tree| tree.node(to_id(window.0)).title.clone())
+    }
+
+    fn set_window_title(window: Handle, title: &str) {
+        with_tree(|tree| tree.node_mut(to_id(window.0)).title = title.to_string());
+    }
+
+    fn show_window(window: Handle) {
+        Self::set_window_visible(window, true);
+    }
+
+    fn set_window_visible(window: Handle, visible: bool) {
+        with_tree(|tree| {
+            let id = to_id(window.0);
+            tree.node_mut(id).visible = visible;
+            if visible {
+                tree.invalidate(id);
+                for child in tree.descendants(id) {
+                    if tree.is_shown(child) {
+                        tree.invalidate(child);
+                    }
+                }
+            }
+        });
+    }
+
+    fn minimize_window(window: Handle) {
+        let rect = with_tree(|tree| tree.node(to_id(window.0)).rect);
+        move_window(
+            window,
+            rect!(rect.pos.x, rect.pos.y, 0, 0),
+            WindowSize::Minimize,
+        );
+    }
+
+    fn maximize_window(window: Handle) {
+        let rect = with_tree(|tree| {
+            let node = tree.node(to_id(window.0));
+            match node.parent {
+                Some(parent) => {
+                    let size = tree.node(parent).rect.size;
+                    rect!(0, 0, size.width, size.height)
+                }
+                None => rect!(0, 0, SCREEN_SIZE.width, SCREEN_SIZE.height),
+            }
+        });
+        move_window(window, rect, WindowSize::Maximize);
+    }
+
+    fn restore_window(window: Handle) {
+        let rect = with_tree(|tree| tree.node(to_id(window.0)).normal_rect);
+        move_window(window, rect, WindowSize::Restore);
+    }
+
+    fn disable_maximize(window: Handle) {
+        with_tree(|tree| tree.node_mut(to_id(window.0)).style.maxbox = false);
+    }
+
+    fn set_window_enabled(window: Handle, enabled: bool) {
+        let id = to_id(window.0);
+        let changed = with_tree(|tree| {
+            let node = tree.node_mut(id);
+            let changed = node.enabled != enabled;
+            node.enabled = enabled;
+            changed
+        });
+        if changed {
+            dispatch(
+                id,
+                if enabled {
+                    Event::WindowEnable
+                } else {
+                    Event::WindowDisable
+                },
+            );
+        }
+    }
+
+    fn window_style(window: Handle) -> WindowStyle {
+        with_tree(|tree| tree.node(to_id(window.0)).style)
+    }
+
+    fn set_window_style(window: Handle, style: WindowStyle) {
+        with_tree(|tree| tree.node_mut(to_id(window.0)).style = style);
+    }
+
+    fn is_window_visible(window: Handle) -> bool {
+        with_tree(|tree| tree.is_shown(to_id(window.0)))
+    }
+
+    fn is_window_enabled(window: Handle) -> bool {
+        with_tree(|tree| tree.node(to_id(window.0)).enabled)
+    }
+
+    fn set_focus(window: Handle) {
+        let id = to_id(window.0);
+        let old = with_tree(|tree| tree.focus.replace(id));
+        if old == Some(id) {
+            return;
+        }
+        if let Some(old) = old {
+            dispatch(old, Event::FocusOut);
+        }
+        dispatch(id, Event::FocusIn);
+    }
+
+    fn has_focus(window: Handle) -> bool {
+        with_tree(|tree| tree.focus == Some(to_id(window.0)))
+    }
+
+    fn update_window(window: Handle) {
+        with_tree(|tree| tree.invalidate(to_id(window.0)));
+    }
+
+    fn for_each_child(window: Handle, mut f: ChildVisitor) {
+        let objects = with_tree(|tree| {
+            tree.descendants(to_id(window.0))
+                .into_iter()
+                .map(|id| tree.node(id).object)
+                .collect::<Vec<_>>()
+        });
+        for object in objects {
+            if let Some((obj, _)) = unsafe { object.as_mut() } {
+                f(obj.as_mut());
+            }
+        }
+    }
+
+    fn visit_window(window: Handle, f: &mut dyn FnMut(&mut dyn Ele)) {
+        let object = with_tree(|tree| tree.nodes.get(&to_id(window.0)).map(|n| n.object));
+        if let Some((obj, _)) = object.and_then(|o| unsafe { o.as_mut() }) {
+            f(obj.as_mut());
+        }
+    }
+
+    fn send_message(window: Handle, msg: Box<dyn Any>) {
+        dispatch_message(to_id(window.0), msg);
+    }
+
+    fn send_created(window: Handle) {
+        dispatch(to_id(window.0), Event::WindowCreated);
+    }
+
+    fn register_hotkey(window: Handle, id: i32, key: KeyCode, modifiers: HotKeyFlags) {
+        with_tree(|tree| {
+            tree.node_mut(to_id(window.0))
+                .hotkeys
+                .insert(id, (key, modifiers));
+        });
+    }
+
+    fn send_input(window: Handle, event: Event) {
+        dispatch(to_id(window.0), event);
+    }
+
+    /// Windows on top are found first: topmost windows, then the ones created later.
+    /// Nothing is found if the window under `pos` is disabled, because it can not receive input.
+    fn window_at(pos: Point) -> Option<(Handle, Point)> {
+        with_tree(|tree| {
+            let mut roots: Vec<usize> = tree
+                .nodes
+                .iter()
+                .filter(|(_, node)| node.parent.is_none())
+                .map(|(id, _)| *id)
+                .collect();
+            roots.sort_by_key(|id| (tree.node(*id).style.topmost, *id));
+            let mut candidates = roots;
+            let mut pos = pos;
+            let mut found = None;
+            while let Some(id) = candidates.iter().rev().copied().find(|id| {
+                let node = tree.node(*id);
+                node.visible && !node.object.is_null() && pos.within(&node.rect)
+            }) {
+                let node = tree.node(id);
+                pos -= node.rect.pos;
+                found = Some(id);
+                if !node.enabled {
+                    return None;
+                }
+                candidates = node.children.clone();
+            }
+            found.map(|id| (Handle(to_hwnd(id)), pos))
+        })
+    }
+
+    fn focused_window() -> Option<Handle> {
+        with_tree(|tree| tree.focus.map(|id| Handle(to_hwnd(id))))
+    }
+
+    fn set_timer(window: Handle, id: usize, interval: u32) {
+        with_tree(|tree| {
+            let due = tree.clock + interval.max(1) as u64;
+            tree.node_mut(to_id(window.0))
+                .timers
+                .insert(id, Timer { interval, due });
+        });
+    }
+
+    fn kill_timer(window: Handle, id: usize) {
+        with_tree(|tree| {
+            tree.node_mut(to_id(window.0)).timers.remove(&id);
+        });
+    }
+
+    fn clipboard_text() -> Option<String> {
+        with_tree(|tree| match &tree.clipboard {
+            Some(ClipboardData::Text(text)) => Some(text.clone()),
+            _ => None,
+        })
+    }
+
+    fn set_clipboard_text(text: &str) {
+        with_tree(|tree| tree.clipboard = Some(ClipboardData::Text(text.to_string())));
+    }
+
+    fn clipboard_image() -> Option<Image> {
+        with_tree(|tree| match &tree.clipboard {
+            Some(ClipboardData::Image(image)) => Some(image.clone()),
+            _ => None,
+        })
+    }
+
+    fn set_clipboard_image(image: &Image) {
+        with_tree(|tree| tree.clipboard = Some(ClipboardData::Image(image.clone())));
+    }
+
+    // Windows are painted by the software renderer, which reads the styles of the drawing
+    // objects directly, so no platform object is ever created.
+
+    fn new_pen(_style: &PenStyle) -> Handle {
+        Handle::NULL
+    }
+
+    fn new_brush(_color: Color) -> Handle {
+        Handle::NULL
+    }
+
+    fn new_font(_style: &FontStyle) -> Handle {
+        Handle::NULL
+    }
+
+    /// Text is measured with the font of the software renderer, which draws it.
+    fn text_extent(font: &FontStyle, text: &str) -> Size {
+        software::text_size(font, text)
+    }
+
+    fn font_metrics(font: &FontStyle) -> FontMetrics {
+        software::font_metrics(font)
+    }
+
+    fn delete_object(_obj: Handle) {}
+}
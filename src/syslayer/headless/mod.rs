@@ -0,0 +1,9 @@
+//! The headless backend keeps every window in an in-memory tree instead of asking
+//! the operating system for one, so the library can run without a display.
+//! It is used on every platform other than Windows, or when the `headless` feature is enabled.
+
+mod backend;
+mod tree;
+
+pub(crate) use backend::Headless;
+pub(crate) use tree::*;
@@ -0,0 +1,170 @@
+//! This file contains the in-memory window tree used by the headless backend.
+//! It plays the role of the window manager: it stores the state of every window
+//! and delivers events to the elements in the same way as `winproc` does on Windows.
+
+use std::{cell::RefCell, collections::HashMap, os::raw::c_void};
+
+use crate::*;
+
+/// The size of the virtual screen.
+pub const SCREEN_SIZE: Size = size!(1920, 1080);
+
+pub(crate) type Object = *mut (Box<dyn Ele>, bool);
+
+pub(crate) struct Timer {
+    pub interval: u32,
+    pub due: u64,
+}
+
+pub(crate) struct Node {
+    pub object: Object,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+    pub title: String,
+    /// Position relative to the parent (or the screen) and size of the window.
+    pub rect: Rect,
+    /// The rect to go back to when the window is restored.
+    pub normal_rect: Rect,
+    pub visible: bool,
+    pub enabled: bool,
+    pub style: WindowStyle,
+    pub timers: HashMap<usize, Timer>,
+    pub hotkeys: HashMap<i32, (KeyCode, HotKeyFlags)>,
+}
+
+#[derive(Default)]
+pub(crate) struct Tree {
+    pub nodes: HashMap<usize, Node>,
+    pub next_id: usize,
+    pub focus: Option<usize>,
+    /// Windows waiting to be painted, in the order they were invalidated.
+    pub dirty: Vec<usize>,
+    pub quit: Option<i32>,
+    /// Virtual time in milliseconds, advanced by the event loop when timers fire.
+    pub clock: u64,
+    pub clipboard: Option<ClipboardData>,
+}
+
+/// The data on the in-memory clipboard.
+pub(crate) enum ClipboardData {
+    Text(String),
+    Image(Image),
+}
+
+thread_local! {
+    static TREE: RefCell<Tree> = RefCell::new(Tree {
+        next_id: 1,
+        ..Default::default()
+    });
+}
+
+/// Run `f` with the window tree of the current thread.
+/// The tree must not be borrowed again inside `f`, so no element code may be called from it.
+pub(crate) fn with_tree<R>(f: impl FnOnce(&mut Tree) -> R) -> R {
+    TREE.with(|tree| f(&mut tree.borrow_mut()))
+}
+
+pub(crate) fn to_id(hwnd: *mut c_void) -> usize {
+    hwnd as usize
+}
+
+pub(crate) fn to_hwnd(id: usize) -> *mut c_void {
+    id as *mut c_void
+}
+
+impl Tree {
+    pub fn node(&self, id: usize) -> &Node {
+        self.nodes
+            .get(&id)
+            .unwrap_or_else(|| panic!("Invalid window handle: {}", id))
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.nodes
+            .get_mut(&id)
+            .unwrap_or_else(|| panic!("Invalid window handle: {}", id))
+    }
+
+    /// Check if the window and all of its ancestors are visible.
+    pub fn is_shown(&self, id: usize) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let node = self.node(id);
+            if !node.visible {
+                return false;
+            }
+            current = node.parent;
+        }
+        true
+    }
+
+    /// Get all descendants of the window in depth-first order.
+    pub fn descendants(&self, id: usize) -> Vec<usize> {
+        let mut ret = Vec::new();
+        for child in self.node(id).children.iter() {
+            ret.push(*child);
+            ret.extend(self.descendants(*child));
+        }
+        ret
+    }
+
+    /// Mark the window as needing to be painted.
+    pub fn invalidate(&mut self, id: usize) {
+        if !self.dirty.contains(&id) {
+            self.dirty.push(id);
+        }
+    }
+}
+
+/// Deliver the event to the element of the window, and input events to its ancestors too.
+/// It keeps track of the hover state as `winproc` does.
+pub(crate) fn dispatch(id: usize, event: Event) {
+    let object = with_tree(|tree| tree.nodes.get(&id).map(|n| n.object));
+    let Some((obj, hover)) = object.and_then(|o| unsafe { o.as_mut() }) else {
+        return;
+    };
+    match event {
+        Event::Hover { .. } => {
+            if *hover {
+                return;
+            }
+            *hover = true;
+        }
+        Event::Leave => {
+            if !*hover {
+                return;
+            }
+            *hover = false;
+        }
+        Event::WindowResized { size, ty } if ty != WindowSize::Minimize => {
+            obj.as_window().resized(size);
+        }
+        _ => {}
+    }
+    deliver(Handle(to_hwnd(id)), &event);
+}
+
+/// Deliver the user-defined message to the element of the window.
+pub(crate) fn dispatch_message(id: usize, msg: Box<dyn std::any::Any>) {
+    let object = with_tree(|tree| tree.nodes.get(&id).map(|n| n.object));
+    if let Some((obj, _)) = object.and_then(|o| unsafe { o.as_mut() }) {
+        obj.on_message(msg);
+    }
+}
+
+/// Paint the window if it is visible.
+pub(crate) fn paint(id: usize) {
+    let target = with_tree(|tree| {
+        let node = tree.nodes.get(&id)?;
+        if node.object.is_null() || !tree.is_shown(id) {
+            return None;
+        }
+        Some((node.object, node.rect.size))
+    });
+    let Some((object, size)) = target else {
+        return;
+    };
+    let (obj, _) = unsafe { object.as_mut().unwrap() };
+    let mut canvas = Canvas::offscreen(size);
+    obj.draw(&mut canvas);
+}
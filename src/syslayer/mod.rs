@@ -1,3 +1,88 @@
+mod backend;
+mod clipboard;
+mod dispatch;
+mod simulate;
+mod software;
+mod surface;
+
+pub(crate) use backend::*;
+pub use clipboard::Clipboard;
+pub(crate) use dispatch::deliver;
+pub(crate) use simulate::key_char;
+pub(crate) use surface::*;
+
+#[cfg(all(windows, not(feature = "headless")))]
 mod windows;
+/// The backend of the current platform.
+#[cfg(all(windows, not(feature = "headless")))]
+pub(crate) type Platform = windows::Win32;
+
+#[cfg(any(not(windows), feature = "headless"))]
+mod headless;
+/// The backend of the current platform.
+#[cfg(any(not(windows), feature = "headless"))]
+pub(crate) type Platform = headless::Headless;
+
+/// Application interface of the current platform.
+pub struct Application;
+
+impl Application {
+    /// Initialize the application.
+    pub fn new(show_console: bool) -> Self {
+        Platform::init(show_console);
+        Self
+    }
+
+    /// Run the application event loop.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    /// fn main() {
+    ///     let app = Application::new(true);
+    ///     // ... other code here
+    ///     app.exec();
+    /// }
+    /// ```
+    pub fn exec(&self) {
+        Platform::event_loop();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HotKeyFlags {
+    pub alt: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub win: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowStyle {
+    // pub hscroll: bool,
+    // pub vscroll: bool,
+    pub border: bool,
+    pub resize: bool,
+    pub caption: bool,
+    pub child: bool,
+    pub sysmenu: bool,
+    pub maxbox: bool,
+    pub minbox: bool,
+    pub topmost: bool,
+}
 
-pub use windows::*;
\ No newline at end of file
+impl Default for WindowStyle {
+    fn default() -> Self {
+        Self {
+            // hscroll: false,
+            // vscroll: false,
+            border: false,
+            resize: true,
+            caption: true,
+            child: false,
+            sysmenu: true,
+            maxbox: true,
+            minbox: true,
+            topmost: false,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! This file contains the simulation of user input.
+//! Simulated events go through the same dispatch as real input, so interactions can be tested
+//! without a user in front of the screen.
+
+use std::cell::Cell;
+
+use crate::*;
+
+thread_local! {
+    /// The window under the simulated mouse.
+    static HOVERED: Cell<Handle> = const { Cell::new(Handle::NULL) };
+    /// The modifier keys and the mouse buttons held down by the simulated user.
+    static HELD: Cell<Modifiers> = const { Cell::new(Modifiers::NONE) };
+}
+
+/// Get the modifier keys and the mouse buttons held down.
+fn held() -> Modifiers {
+    HELD.with(Cell::get)
+}
+
+/// Press (or release if `down` is false) the modifier keys or the mouse buttons of `mods`.
+fn hold(mods: Modifiers, down: bool) {
+    HELD.with(|held| {
+        let mut value = held.get();
+        value.set(mods, down);
+        held.set(value);
+    });
+}
+
+/// Get the character typed by `key` without modifiers, if there is one.
+pub(crate) fn key_char(key: KeyCode) -> Option<char> {
+    match key {
+        KeyCode::Alpha(c) => Some(c.to_ascii_lowercase()),
+        KeyCode::N(c) | KeyCode::Symbol(c) => Some(c),
+        KeyCode::Num(n) if n < 10 => char::from_digit(n as u32, 10),
+        KeyCode::NumAdd => Some('+'),
+        KeyCode::NumSub => Some('-'),
+        KeyCode::NumMul => Some('*'),
+        KeyCode::NumDiv => Some('/'),
+        KeyCode::NumDot => Some('.'),
+        KeyCode::Tab => Some('\t'),
+        KeyCode::Space => Some(' '),
+        KeyCode::Enter => Some('\r'),
+        KeyCode::Backspace => Some('\x08'),
+        KeyCode::Esc => Some('\x1b'),
+        _ => None,
+    }
+}
+
+/// Move the simulated mouse onto `window`.
+/// The window under the mouse before gets `Event::Leave`, and `window` gets `Event::Hover`.
+fn hover(window: Handle, pos: Point, mods: Modifiers) {
+    let old = HOVERED.with(|hovered| hovered.replace(window));
+    if old == window {
+        return;
+    }
+    if !old.is_null() {
+        Platform::send_input(old, Event::Leave);
+    }
+    Platform::send_input(window, Event::Hover { pos, mods });
+}
+
+impl Application {
+    /// Deliver `event` to the window as if it came from the user.
+    /// Mouse events move the simulated mouse onto the window first, so it gets `Event::Hover`
+    /// and the window under the mouse before gets `Event::Leave`.
+    /// The positions of the events are relative to the window.
+    /// Pressing or releasing a modifier key or a mouse button also holds it down or lets it go
+    /// for the input simulated afterwards, such as `Application::press_key`.
+    /// # Example
+    /// ```
+    /// use rusty_gui::*;
+    ///
+    /// let app = Application::new(true);
+    /// let form = Block::create(rect!(100, 100, 400, 300), None);
+    /// let button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+    /// app.simulate(button.as_window().get_id(), Event::MouseButtonPressed {
+    ///     button: MouseButton::Left,
+    ///     pos: pos!(10, 10),
+    ///     mods: Modifiers::NONE,
+    /// });
+    /// ```
+    pub fn simulate(&self, window: WindowID, event: Event) {
+        let window = window.handle;
+        let (flags, down) = match event {
+            Event::KeyPressed { key, .. } => (Modifiers::from_key(key), true),
+            Event::KeyReleased { key, .. } => (Modifiers::from_key(key), false),
+            Event::MouseButtonPressed { button, .. } => (Modifiers::from_button(button), true),
+            Event::MouseButtonReleased { button, .. } => (Modifiers::from_button(button), false),
+            _ => (Modifiers::NONE, false),
+        };
+        hold(flags, down);
+        match event {
+            Event::MouseMoved { pos, mods }
+            | Event::MouseButtonPressed { pos, mods, .. }
+            | Event::MouseButtonReleased { pos, mods, .. }
+            | Event::MouseDoubleClicked { pos, mods, .. }
+            | Event::MouseWheelScrolled { pos, mods, .. } => hover(window, pos, mods),
+            Event::Hover { .. } => {
+                HOVERED.with(|hovered| hovered.set(window));
+            }
+            Event::Leave => {
+                HOVERED.with(|hovered| {
+                    if hovered.get() == window {
+                        hovered.set(Handle::NULL);
+                    }
+                });
+            }
+            _ => {}
+        }
+        Platform::send_input(window, event);
+    }
+
+    /// Move the simulated mouse to `pos` on the screen.
+    /// The window under the mouse gets `Event::MouseMoved`.
+    pub fn move_mouse(&self, pos: Point) {
+        match Platform::window_at(pos) {
+            Some((window, pos)) => {
+                let mods = held();
+                hover(window, pos, mods);
+                Platform::send_input(window, Event::MouseMoved { pos, mods });
+            }
+            None => {
+                let old = HOVERED.with(|hovered| hovered.replace(Handle::NULL));
+                if !old.is_null() {
+                    Platform::send_input(old, Event::Leave);
+                }
+            }
+        }
+    }
+
+    /// Click the left mouse button at `pos` on the screen.
+    /// The window under the mouse gets `Event::MouseMoved`, `Event::MouseButtonPressed`
+    /// and `Event::MouseButtonReleased`.
+    /// Nothing is clicked if there is no enabled window at `pos`.
+    pub fn click(&self, pos: Point) {
+        self.move_mouse(pos);
+        let Some((window, pos)) = Platform::window_at(pos) else {
+            return;
+        };
+        let button = MouseButton::Left;
+        let mods = held() | Modifiers::LEFT_BUTTON;
+        Platform::send_input(window, Event::MouseButtonPressed { button, pos, mods });
+        let mods = held();
+        Platform::send_input(window, Event::MouseButtonReleased { button, pos, mods });
+    }
+
+    /// Scroll by `delta` with the mouse at `pos` on the screen.
+    /// The window under the mouse gets `Event::MouseMoved` and `Event::MouseWheelScrolled`,
+    /// and its parents get the scrolling it ignores.
+    pub fn scroll(&self, pos: Point, delta: ScrollDelta) {
+        self.move_mouse(pos);
+        let Some((window, pos)) = Platform::window_at(pos) else {
+            return;
+        };
+        let mods = held();
+        Platform::send_input(window, Event::MouseWheelScrolled { delta, pos, mods });
+    }
+
+    /// Type `text` into the window that has the focus.
+    /// Every character is delivered as `Event::Input`, like an input method does.
+    pub fn type_text(&self, text: &str) {
+        let Some(window) = Platform::focused_window() else {
+            return;
+        };
+        for ch in text.chars() {
+            Platform::send_input(window, Event::Input { ch });
+        }
+    }
+
+    /// Press and release `key` in the window that has the focus.
+    /// Keys that type a character, such as `KeyCode::Enter`, also deliver `Event::Input`
+    /// between `Event::KeyPressed` and `Event::KeyReleased`.
+    /// The events tell the modifier keys held down with `Application::simulate`.
+    pub fn press_key(&self, key: KeyCode) {
+        let Some(window) = Platform::focused_window() else {
+            return;
+        };
+        let mods = held() | Modifiers::from_key(key);
+        Platform::send_input(window, Event::KeyPressed { key, sys: false, mods });
+        if let Some(ch) = key_char(key) {
+            Platform::send_input(window, Event::Input { ch });
+        }
+        let mods = held();
+        Platform::send_input(window, Event::KeyReleased { key, sys: false, mods });
+    }
+}
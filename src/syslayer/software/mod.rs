@@ -0,0 +1,215 @@
+//! This module contains the software renderer.
+//! It draws the shapes and the text of a `Canvas` into an `Image`, so it works on any platform.
+
+mod outline;
+mod raster;
+mod stroke;
+mod text;
+
+use std::cell::RefCell;
+
+use crate::*;
+
+use raster::{fill_polygons, FillRule, Polygon, Vertex};
+pub(crate) use text::{font_metrics, text_size};
+use stroke::*;
+
+struct State {
+    image: Image,
+    pen: PenStyle,
+    brush: Color,
+    font: FontStyle,
+    text_color: Color,
+}
+
+/// A `Surface` that draws into an `Image`.
+pub(crate) struct Raster {
+    state: RefCell<State>,
+}
+
+/// Get the center of the pixel at `(x, y)`.
+fn center(x: i32, y: i32) -> Vertex {
+    (x as f32 + 0.5, y as f32 + 0.5)
+}
+
+fn centers(points: &[Point]) -> Vec<Vertex> {
+    points.iter().map(|p| center(p.x, p.y)).collect()
+}
+
+fn circle_rect(pos: Point, radius: i32) -> Rect {
+    rect!(pos.x - radius, pos.y - radius, 2 * radius, 2 * radius)
+}
+
+impl Raster {
+    /// Create a new `Raster` with an image of `size` filled with `color`.
+    /// The pen, the brush, the font and the text color have the same defaults as a GDI device context.
+    pub fn new(size: Size, color: Color) -> Self {
+        Self {
+            state: RefCell::new(State {
+                image: Image::new(size, color),
+                pen: PenStyle::default(),
+                brush: Color::WHITE,
+                font: FontStyle::default(),
+                text_color: Color::BLACK,
+            }),
+        }
+    }
+
+    fn bounds(state: &State) -> Rect {
+        rect!(0, 0, state.image.size().width, state.image.size().height)
+    }
+
+    /// Draw the outline of the polyline with the current pen.
+    fn stroke(&self, points: &[Vertex], closed: bool) {
+        let state = &mut *self.state.borrow_mut();
+        let polygons = stroke(points, closed, &state.pen);
+        let clip = Self::bounds(state);
+        fill_polygons(&mut state.image, &polygons, FillRule::NonZero, state.pen.color, clip);
+    }
+
+    /// Fill the polygon with the current brush, then draw its outline with the current pen.
+    fn fill(&self, polygon: Polygon, rule: FillRule) {
+        {
+            let state = &mut *self.state.borrow_mut();
+            let clip = Self::bounds(state);
+            let polygons = [polygon.clone()];
+            fill_polygons(&mut state.image, &polygons, rule, state.brush, clip);
+        }
+        self.stroke(&polygon, true);
+    }
+}
+
+impl Surface for Raster {
+    fn clear(&self, rect: Rect, color: Color) {
+        let state = &mut *self.state.borrow_mut();
+        // the position of the window is in its parent, and the image starts at the window
+        let rect = rect!(0, 0, rect.size.width, rect.size.height);
+        if let Some(rect) = rect & Self::bounds(state) {
+            for y in rect.top()..rect.bottom() {
+                for x in rect.left()..rect.right() {
+                    state.image.set_pixel(pos!(x, y), color);
+                }
+            }
+        }
+    }
+
+    fn set_pen(&self, pen: &Pen) -> Pen {
+        let old = std::mem::replace(&mut self.state.borrow_mut().pen, pen.style);
+        Pen {
+            hpen: Handle::NULL,
+            style: old,
+        }
+    }
+
+    fn set_brush(&self, brush: &Brush) -> Brush {
+        let old = std::mem::replace(&mut self.state.borrow_mut().brush, brush.color);
+        Brush {
+            hbrush: Handle::NULL,
+            color: old,
+        }
+    }
+
+    fn set_font(&self, font: &Font) -> Font {
+        let old = std::mem::replace(&mut self.state.borrow_mut().font, font.style.clone());
+        Font {
+            hfont: Handle::NULL,
+            style: old,
+        }
+    }
+
+    fn set_text_color(&self, color: Color) -> Color {
+        std::mem::replace(&mut self.state.borrow_mut().text_color, color)
+    }
+
+    fn line(&self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        self.stroke(&[center(x1, y1), center(x2, y2)], false);
+    }
+
+    fn rect(&self, rect: Rect) {
+        let (l, t) = center(rect.left(), rect.top());
+        let (r, b) = center(rect.right(), rect.bottom());
+        self.stroke(&rect_points((l, t, r, b)), true);
+    }
+
+    fn round_rect(&self, rect: Rect, rx: i32, ry: i32) {
+        let (l, t) = center(rect.left(), rect.top());
+        let (r, b) = center(rect.right(), rect.bottom());
+        self.stroke(&round_rect_points((l, t, r, b), rx as f32, ry as f32), true);
+    }
+
+    fn polyline(&self, points: &[Point]) {
+        if points.len() < 2 {
+            panic!("At least two points are required to draw a polyline");
+        }
+        self.stroke(&centers(points), false);
+    }
+
+    fn polygon(&self, points: &[Point]) {
+        if points.len() < 2 {
+            panic!("At least two points are required to draw a polygon");
+        }
+        self.stroke(&centers(points), true);
+    }
+
+    fn arc(&self, rect: Rect, start: f32, sweep: f32) {
+        self.stroke(&arc_points(inner_bounds(rect), start, sweep), false);
+    }
+
+    fn pie(&self, rect: Rect, start: f32, sweep: f32) {
+        self.stroke(&pie_points(inner_bounds(rect), start, sweep), true);
+    }
+
+    fn ellipse(&self, rect: Rect) {
+        self.stroke(&ellipse_points(inner_bounds(rect)), true);
+    }
+
+    fn circle(&self, pos: Point, radius: i32) {
+        self.ellipse(circle_rect(pos, radius));
+    }
+
+    fn fill_rect(&self, rect: Rect) {
+        self.fill(rect_points(inner_bounds(rect)), FillRule::NonZero);
+    }
+
+    fn fill_round_rect(&self, rect: Rect, rx: i32, ry: i32) {
+        let points = round_rect_points(inner_bounds(rect), rx as f32, ry as f32);
+        self.fill(points, FillRule::NonZero);
+    }
+
+    fn fill_polygon(&self, points: &[Point]) {
+        if points.len() < 2 {
+            panic!("At least two points are required to draw a filled polygon");
+        }
+        self.fill(centers(points), FillRule::EvenOdd);
+    }
+
+    fn fill_pie(&self, rect: Rect, start: f32, sweep: f32) {
+        self.fill(pie_points(inner_bounds(rect), start, sweep), FillRule::NonZero);
+    }
+
+    fn fill_ellipse(&self, rect: Rect) {
+        self.fill(ellipse_points(inner_bounds(rect)), FillRule::NonZero);
+    }
+
+    fn fill_circle(&self, pos: Point, radius: i32) {
+        self.fill_ellipse(circle_rect(pos, radius));
+    }
+
+    fn text_line(&self, pos: Point, text: &str, clip: Option<Rect>) {
+        let state = &mut *self.state.borrow_mut();
+        let clip = clip.unwrap_or(Self::bounds(state));
+        text::draw_text(&mut state.image, pos, text, &state.font, state.text_color, clip);
+    }
+
+    fn text_extent(&self, text: &str) -> Size {
+        text::text_size(&self.state.borrow().font, text)
+    }
+
+    fn font_metrics(&self) -> FontMetrics {
+        text::font_metrics(&self.state.borrow().font)
+    }
+
+    fn into_image(self: Box<Self>) -> Option<Image> {
+        Some(self.state.into_inner().image)
+    }
+}
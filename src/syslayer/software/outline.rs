@@ -0,0 +1,391 @@
+//! Text drawing with the outlines of a loaded `FontFace`.
+//! Glyphs are rasterized with anti-aliasing, and kept in a cache keyed by face, size and glyph.
+//! Bold and italic are synthesized when the face does not have them.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+use crate::*;
+
+use super::raster::{fill_polygons, FillRule};
+use super::text::line_height;
+
+/// How much synthesized italic glyphs lean right, per pixel above the baseline.
+const SLANT: f32 = 0.2;
+/// The most glyphs kept in the cache. The cache is emptied when it is full.
+const CACHE_LIMIT: usize = 4096;
+
+/// A glyph rasterized at a size.
+struct Glyph {
+    /// The position of the top-left pixel from the origin of the glyph on the baseline.
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    /// The part of every pixel covered by the glyph, between 0 and 255.
+    coverage: Vec<u8>,
+}
+
+/// The face id, the size, the glyph id, and whether the glyph is made bold and italic.
+type GlyphKey = (usize, i32, u16, bool, bool);
+
+static GLYPHS: Mutex<BTreeMap<GlyphKey, Arc<Glyph>>> = Mutex::new(BTreeMap::new());
+
+/// A face scaled to the size of a font.
+struct Scaled<'a> {
+    id: usize,
+    face: Face<'a>,
+    size: i32,
+    /// Pixels per font unit.
+    scale: f32,
+    ascent: i32,
+    /// Whether bold and italic are synthesized.
+    bold: bool,
+    italic: bool,
+}
+
+impl<'a> Scaled<'a> {
+    fn new(face: &'a FontFace, font: &FontStyle) -> Self {
+        let id = face.id();
+        let (bold, italic) = (face.is_bold(), face.is_italic());
+        let face = face.face();
+        let size = line_height(font);
+        // the size is the height of the cell of the font, as with GDI
+        let cell = (face.ascender() as i32 - face.descender() as i32).max(1);
+        let scale = size as f32 / cell as f32;
+        Self {
+            id,
+            size,
+            scale,
+            ascent: (face.ascender() as f32 * scale).round() as i32,
+            bold: !bold && font.weight as i32 >= FontWeight::SemiBold as i32,
+            italic: !italic && font.italic,
+            face,
+        }
+    }
+
+    /// Get the glyph of every char with its position from the start of the text.
+    fn layout(&self, text: &str) -> (Vec<(GlyphId, i32)>, i32) {
+        let mut pen = 0.0_f32;
+        let glyphs = text
+            .chars()
+            .map(|c| {
+                let glyph = self.face.glyph_index(c).unwrap_or(GlyphId(0));
+                let x = pen.round() as i32;
+                pen += self.face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * self.scale;
+                (glyph, x)
+            })
+            .collect();
+        (glyphs, pen.round() as i32)
+    }
+
+    /// Get the width of a stroke, which synthesized bold glyphs are widened by.
+    fn stroke(&self) -> i32 {
+        (self.size / 16).max(1)
+    }
+
+    fn glyph(&self, glyph: GlyphId) -> Arc<Glyph> {
+        let key = (self.id, self.size, glyph.0, self.bold, self.italic);
+        if let Some(cached) = GLYPHS.lock().unwrap().get(&key) {
+            return cached.clone();
+        }
+        let rasterized = Arc::new(self.rasterize(glyph));
+        let mut cache = GLYPHS.lock().unwrap();
+        if cache.len() >= CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, rasterized.clone());
+        rasterized
+    }
+
+    fn rasterize(&self, glyph: GlyphId) -> Glyph {
+        let mut outline = Outline {
+            scale: self.scale,
+            slant: if self.italic { SLANT } else { 0.0 },
+            lines: Vec::new(),
+            start: (0.0, 0.0),
+            last: (0.0, 0.0),
+        };
+        self.face.outline_glyph(glyph, &mut outline);
+        let lines = outline.lines;
+        if lines.is_empty() {
+            return Glyph {
+                left: 0,
+                top: 0,
+                width: 0,
+                height: 0,
+                coverage: Vec::new(),
+            };
+        }
+        let points = lines.iter().flat_map(|(a, b)| [a, b]);
+        let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+        for p in points {
+            min = (min.0.min(p.0), min.1.min(p.1));
+            max = (max.0.max(p.0), max.1.max(p.1));
+        }
+        let (left, top) = (min.0.floor() as i32, min.1.floor() as i32);
+        let width = max.0.ceil() as i32 - left;
+        let height = max.1.ceil() as i32 - top;
+        let mut coverage = Coverage::new(width, height);
+        let offset = |p: (f32, f32)| (p.0 - left as f32, p.1 - top as f32);
+        for (a, b) in lines {
+            coverage.line(offset(a), offset(b));
+        }
+        let mut glyph = Glyph {
+            left,
+            top,
+            width,
+            height,
+            coverage: coverage.into_alpha(),
+        };
+        if self.bold {
+            embolden(&mut glyph, self.stroke());
+        }
+        glyph
+    }
+}
+
+/// Collects the outline of a glyph as lines in pixels, with y going down from the baseline.
+struct Outline {
+    scale: f32,
+    slant: f32,
+    lines: Vec<((f32, f32), (f32, f32))>,
+    start: (f32, f32),
+    last: (f32, f32),
+}
+
+impl Outline {
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = (x * self.scale, y * self.scale);
+        (x + y * self.slant, -y)
+    }
+
+    fn line(&mut self, to: (f32, f32)) {
+        self.lines.push((self.last, to));
+        self.last = to;
+    }
+
+    /// Flatten a curve given by `at`, which maps 0..=1 to the points of the curve.
+    fn curve(&mut self, to: (f32, f32), at: impl Fn(f32) -> (f32, f32)) {
+        let from = self.last;
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length.sqrt() * 2.0).ceil().clamp(1.0, 32.0) as i32;
+        for i in 1..steps {
+            self.line(at(i as f32 / steps as f32));
+        }
+        self.line(to);
+    }
+}
+
+impl OutlineBuilder for Outline {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.point(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.line(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p0, p1, p2) = (self.last, self.point(x1, y1), self.point(x, y));
+        self.curve(p2, |t| {
+            let u = 1.0 - t;
+            let at = |a: f32, b: f32, c: f32| u * u * a + 2.0 * u * t * b + t * t * c;
+            (at(p0.0, p1.0, p2.0), at(p0.1, p1.1, p2.1))
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p0, p1, p2, p3) = (
+            self.last,
+            self.point(x1, y1),
+            self.point(x2, y2),
+            self.point(x, y),
+        );
+        self.curve(p3, |t| {
+            let u = 1.0 - t;
+            let at = |a: f32, b: f32, c: f32, d: f32| {
+                u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+            };
+            (at(p0.0, p1.0, p2.0, p3.0), at(p0.1, p1.1, p2.1, p3.1))
+        });
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.line(self.start);
+        }
+    }
+}
+
+/// Accumulates the signed area that the lines of an outline cover in every pixel.
+/// Summing a row from the left gives the coverage of its pixels.
+struct Coverage {
+    width: i32,
+    height: i32,
+    /// The rows of the accumulation buffer have two more cells than the glyph,
+    /// for the lines that end on its right edge.
+    area: Vec<f32>,
+}
+
+impl Coverage {
+    fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            area: vec![0.0; ((width + 2) * height) as usize],
+        }
+    }
+
+    fn add(&mut self, x: i32, y: i32, area: f32) {
+        let x = x.clamp(0, self.width + 1);
+        self.area[(y * (self.width + 2) + x) as usize] += area;
+    }
+
+    /// Add the line from `a` to `b`. Lines going down add area, and lines going up remove it.
+    fn line(&mut self, a: (f32, f32), b: (f32, f32)) {
+        if a.1 == b.1 {
+            return;
+        }
+        let (dir, a, b) = if a.1 < b.1 { (1.0, a, b) } else { (-1.0, b, a) };
+        let dxdy = (b.0 - a.0) / (b.1 - a.1);
+        let mut x = a.0;
+        let first = a.1.max(0.0) as i32;
+        let last = (b.1.ceil() as i32).min(self.height);
+        for y in first..last {
+            let dy = ((y + 1) as f32).min(b.1) - (y as f32).max(a.1);
+            let next = x + dxdy * dy;
+            let d = dy * dir;
+            let (x0, x1) = if x < next { (x, next) } else { (next, x) };
+            let (x0floor, x1ceil) = (x0.floor(), x1.ceil());
+            let (x0i, x1i) = (x0floor as i32, x1ceil as i32);
+            if x1i <= x0i + 1 {
+                // the line stays in one pixel of the row
+                let mid = 0.5 * (x + next) - x0floor;
+                self.add(x0i, y, d - d * mid);
+                self.add(x0i + 1, y, d * mid);
+            } else {
+                let s = (x1 - x0).recip();
+                let x0f = x0 - x0floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+                self.add(x0i, y, d * a0);
+                if x1i == x0i + 2 {
+                    self.add(x0i + 1, y, d * (1.0 - a0 - am));
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.add(x0i + 1, y, d * (a1 - a0));
+                    for xi in x0i + 2..x1i - 1 {
+                        self.add(xi, y, d * s);
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.add(x1i - 1, y, d * (1.0 - a2 - am));
+                }
+                self.add(x1i, y, d * am);
+            }
+            x = next;
+        }
+    }
+
+    fn into_alpha(self) -> Vec<u8> {
+        let stride = (self.width + 2) as usize;
+        let mut alpha = Vec::with_capacity((self.width * self.height) as usize);
+        for row in self.area.chunks(stride) {
+            let mut sum = 0.0;
+            for area in &row[..self.width as usize] {
+                sum += area;
+                alpha.push((sum.abs().min(1.0) * 255.0 + 0.5) as u8);
+            }
+        }
+        alpha
+    }
+}
+
+/// Widen `glyph` to the right by `stroke` pixels.
+fn embolden(glyph: &mut Glyph, stroke: i32) {
+    let width = glyph.width + stroke;
+    let mut coverage = vec![0; (width * glyph.height) as usize];
+    for y in 0..glyph.height {
+        for x in 0..width {
+            let at = |x: i32| match (0..glyph.width).contains(&x) {
+                true => glyph.coverage[(y * glyph.width + x) as usize],
+                false => 0,
+            };
+            coverage[(y * width + x) as usize] = (0..=stroke).map(|d| at(x - d)).max().unwrap();
+        }
+    }
+    glyph.width = width;
+    glyph.coverage = coverage;
+}
+
+/// Get the size of the box taken by the single-line `text` drawn with `face`.
+pub(crate) fn text_size(face: &FontFace, font: &FontStyle, text: &str) -> Size {
+    let scaled = Scaled::new(face, font);
+    size!(scaled.layout(text).1, scaled.size)
+}
+
+pub(crate) fn font_metrics(face: &FontFace, font: &FontStyle) -> FontMetrics {
+    let scaled = Scaled::new(face, font);
+    FontMetrics {
+        ascent: scaled.ascent,
+        descent: scaled.size - scaled.ascent,
+        line_height: scaled.size,
+    }
+}
+
+/// Draw the single-line `text` with `face`, with its top-left corner at `pos`.
+pub(crate) fn draw_text(
+    image: &mut Image,
+    pos: Point,
+    text: &str,
+    face: &FontFace,
+    font: &FontStyle,
+    color: Color,
+    clip: Rect,
+) {
+    let Some(clip) = clip & rect!(0, 0, image.size().width, image.size().height) else {
+        return;
+    };
+    let scaled = Scaled::new(face, font);
+    let baseline = pos.y + scaled.ascent;
+    let (glyphs, width) = scaled.layout(text);
+    for (id, x) in glyphs {
+        let glyph = scaled.glyph(id);
+        let (left, top) = (pos.x + x + glyph.left, baseline + glyph.top);
+        for gy in 0..glyph.height {
+            for gx in 0..glyph.width {
+                let coverage = glyph.coverage[(gy * glyph.width + gx) as usize];
+                let p = pos!(left + gx, top + gy);
+                if coverage > 0 && p.within(&clip) {
+                    image.blend_pixel(p, color, coverage);
+                }
+            }
+        }
+    }
+    let mut line = |metrics: Option<ttf_parser::LineMetrics>, default: i32| {
+        let (y, thickness) = match metrics {
+            Some(metrics) => (
+                baseline - (metrics.position as f32 * scaled.scale).round() as i32,
+                ((metrics.thickness as f32 * scaled.scale).round() as i32).max(1),
+            ),
+            None => (baseline - default, scaled.stroke()),
+        };
+        let (l, t) = (pos.x as f32, y as f32);
+        let (r, b) = (l + width as f32, t + thickness as f32);
+        let polygon = vec![(l, t), (r, t), (r, b), (l, b)];
+        fill_polygons(image, &[polygon], FillRule::NonZero, color, clip);
+    };
+    if font.underline {
+        line(scaled.face.underline_metrics(), -scaled.stroke());
+    }
+    if font.strikeout {
+        line(scaled.face.strikeout_metrics(), scaled.ascent / 3);
+    }
+}
@@ -0,0 +1,102 @@
+//! Scanline filling of polygons.
+//! Pixels are sampled at their centers without anti-aliasing, like GDI does.
+
+use crate::*;
+
+/// A point in pixel space, where the center of pixel `(x, y)` is `(x + 0.5, y + 0.5)`.
+pub(crate) type Vertex = (f32, f32);
+
+/// A closed polygon.
+pub(crate) type Polygon = Vec<Vertex>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+struct Edge {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    winding: i32,
+}
+
+/// Fill `polygons` with `color`, treating them as one shape.
+/// Only the pixels inside `clip` are touched.
+pub(crate) fn fill_polygons(
+    image: &mut Image,
+    polygons: &[Polygon],
+    rule: FillRule,
+    color: Color,
+    clip: Rect,
+) {
+    let mut edges = Vec::new();
+    let (mut top, mut bottom) = (f32::MAX, f32::MIN);
+    for polygon in polygons {
+        for i in 0..polygon.len() {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            if a.1 == b.1 {
+                continue;
+            }
+            top = top.min(a.1.min(b.1));
+            bottom = bottom.max(a.1.max(b.1));
+            let (winding, (x0, y0), (x1, y1)) = if a.1 < b.1 { (1, a, b) } else { (-1, b, a) };
+            edges.push(Edge {
+                x0,
+                y0,
+                x1,
+                y1,
+                winding,
+            });
+        }
+    }
+    if edges.is_empty() {
+        return;
+    }
+    let clip = match clip & rect!(0, 0, image.size().width, image.size().height) {
+        Some(clip) => clip,
+        None => return,
+    };
+    let first_row = ((top - 0.5).ceil() as i32).max(clip.top());
+    let last_row = ((bottom - 0.5).ceil() as i32).min(clip.bottom());
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+    for y in first_row..last_row {
+        let yc = y as f32 + 0.5;
+        crossings.clear();
+        for edge in edges.iter() {
+            if edge.y0 <= yc && yc < edge.y1 {
+                let x = edge.x0 + (yc - edge.y0) * (edge.x1 - edge.x0) / (edge.y1 - edge.y0);
+                crossings.push((x, edge.winding));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut winding = 0;
+        for i in 0..crossings.len() {
+            winding += crossings[i].1;
+            let inside = match rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+            if !inside || i + 1 == crossings.len() {
+                continue;
+            }
+            let start = ((crossings[i].0 - 0.5).ceil() as i32).max(clip.left());
+            let end = ((crossings[i + 1].0 - 0.5).ceil() as i32).min(clip.right());
+            for x in start..end {
+                image.blend_pixel(pos!(x, y), color, 255);
+            }
+        }
+    }
+}
+
+/// Get the signed area of the polygon. It is positive for clockwise polygons on the screen.
+pub(crate) fn signed_area(polygon: &[Vertex]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    area / 2.0
+}
@@ -0,0 +1,250 @@
+//! Conversion of shapes into polylines, and of polylines into the polygons covered by a pen.
+
+use std::f32::consts::PI;
+
+use crate::*;
+
+use super::raster::{signed_area, Polygon, Vertex};
+
+/// GDI's default miter limit: longer miters are drawn as bevels.
+const MITER_LIMIT: f32 = 10.0;
+
+fn segments_for(radius: f32, sweep: f32) -> usize {
+    ((radius.max(1.0) * sweep.abs() / 2.0).ceil() as usize).clamp(4, 256)
+}
+
+/// The left, top, right and bottom edges of a shape in pixel space.
+pub(crate) type Bounds = (f32, f32, f32, f32);
+
+/// Get the bounds of a shape drawn inside `rect`.
+/// Like GDI, the right and the bottom edges are excluded.
+pub(crate) fn inner_bounds(rect: Rect) -> Bounds {
+    let (l, t) = (rect.left() as f32 + 0.5, rect.top() as f32 + 0.5);
+    let (r, b) = (rect.right() as f32 - 0.5, rect.bottom() as f32 - 0.5);
+    (l, t, r.max(l), b.max(t))
+}
+
+/// Get the points of an elliptic arc inside `bounds`, from angle `start` to `start + sweep`.
+/// The angles are in radians and increase clockwise on the screen.
+pub(crate) fn arc_points(bounds: Bounds, start: f32, sweep: f32) -> Vec<Vertex> {
+    let (l, t, r, b) = bounds;
+    let (rx, ry) = ((r - l) / 2.0, (b - t) / 2.0);
+    let (cx, cy) = (l + rx, t + ry);
+    let n = segments_for(rx.max(ry), sweep);
+    (0..=n)
+        .map(|i| {
+            let a = start + sweep * i as f32 / n as f32;
+            (cx + rx * a.cos(), cy + ry * a.sin())
+        })
+        .collect()
+}
+
+/// Get the points of an ellipse inside `bounds`, without repeating the first point.
+pub(crate) fn ellipse_points(bounds: Bounds) -> Vec<Vertex> {
+    let mut points = arc_points(bounds, 0.0, 2.0 * PI);
+    points.pop();
+    points
+}
+
+/// Get the points of a pie: the center of `bounds`, then the arc.
+pub(crate) fn pie_points(bounds: Bounds, start: f32, sweep: f32) -> Vec<Vertex> {
+    let (l, t, r, b) = bounds;
+    let mut points = vec![((l + r) / 2.0, (t + b) / 2.0)];
+    points.extend(arc_points(bounds, start, sweep));
+    points
+}
+
+/// Get the corners of `bounds`, clockwise from the top-left one.
+pub(crate) fn rect_points(bounds: Bounds) -> Vec<Vertex> {
+    let (l, t, r, b) = bounds;
+    vec![(l, t), (r, t), (r, b), (l, b)]
+}
+
+/// Get the points of a rectangle with corners rounded by ellipses of radius `rx` and `ry`.
+pub(crate) fn round_rect_points(bounds: Bounds, rx: f32, ry: f32) -> Vec<Vertex> {
+    let (l, t, r, b) = bounds;
+    let rx = rx.clamp(0.0, (r - l) / 2.0);
+    let ry = ry.clamp(0.0, (b - t) / 2.0);
+    if rx == 0.0 || ry == 0.0 {
+        return rect_points(bounds);
+    }
+    let corner = |x: f32, y: f32, start: f32| {
+        arc_points((x - rx, y - ry, x + rx, y + ry), start, PI / 2.0)
+    };
+    let mut points = corner(l + rx, t + ry, PI);
+    points.extend(corner(r - rx, t + ry, 1.5 * PI));
+    points.extend(corner(r - rx, b - ry, 0.0));
+    points.extend(corner(l + rx, b - ry, 0.5 * PI));
+    points
+}
+
+fn sub(a: Vertex, b: Vertex) -> Vertex {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn add(a: Vertex, b: Vertex) -> Vertex {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn scale(a: Vertex, s: f32) -> Vertex {
+    (a.0 * s, a.1 * s)
+}
+
+fn length(a: Vertex) -> f32 {
+    (a.0 * a.0 + a.1 * a.1).sqrt()
+}
+
+fn normalize(a: Vertex) -> Vertex {
+    scale(a, 1.0 / length(a))
+}
+
+fn normal(d: Vertex) -> Vertex {
+    (-d.1, d.0)
+}
+
+fn circle(center: Vertex, radius: f32) -> Polygon {
+    let n = segments_for(radius, 2.0 * PI).max(8);
+    (0..n)
+        .map(|i| {
+            let t = 2.0 * PI * i as f32 / n as f32;
+            (center.0 + radius * t.cos(), center.1 + radius * t.sin())
+        })
+        .collect()
+}
+
+/// Get the dash pattern of the line style, in multiples of the pen width.
+fn dash_pattern(style: LineStyle) -> Option<&'static [f32]> {
+    match style {
+        LineStyle::Dash => Some(&[3.0, 1.0]),
+        LineStyle::Dot => Some(&[1.0, 1.0]),
+        LineStyle::DashDot => Some(&[3.0, 1.0, 1.0, 1.0]),
+        LineStyle::DashDotDot => Some(&[3.0, 1.0, 1.0, 1.0, 1.0, 1.0]),
+        LineStyle::Solid | LineStyle::Null => None,
+    }
+}
+
+/// Split the polyline into the pieces that are drawn by the dash pattern.
+fn split_dashes(points: &[Vertex], pattern: &[f32], unit: f32) -> Vec<Vec<Vertex>> {
+    let mut dashes = Vec::new();
+    let mut current = vec![points[0]];
+    let mut index = 0;
+    let mut left = pattern[0] * unit;
+    for pair in points.windows(2) {
+        let (mut a, b) = (pair[0], pair[1]);
+        let mut remain = length(sub(b, a));
+        while remain > left {
+            let p = add(a, scale(normalize(sub(b, a)), left));
+            remain -= left;
+            a = p;
+            if index % 2 == 0 {
+                current.push(p);
+                dashes.push(std::mem::take(&mut current));
+            } else {
+                current = vec![p];
+            }
+            index = (index + 1) % pattern.len();
+            left = pattern[index] * unit;
+        }
+        left -= remain;
+        if index % 2 == 0 {
+            current.push(b);
+        }
+    }
+    if index % 2 == 0 && current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Add the polygons covering the join between segments `d0` and `d1` at `p`.
+fn join(polygons: &mut Vec<Polygon>, p: Vertex, d0: Vertex, d1: Vertex, hw: f32, style: JoinStyle) {
+    let cross = d0.0 * d1.1 - d0.1 * d1.0;
+    if cross.abs() < 1e-6 {
+        return;
+    }
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let (n0, n1) = (normal(d0), normal(d1));
+    let a = add(p, scale(n0, side * hw));
+    let b = add(p, scale(n1, side * hw));
+    match style {
+        JoinStyle::Round => polygons.push(circle(p, hw)),
+        JoinStyle::Bevel => polygons.push(vec![p, a, b]),
+        JoinStyle::Miter => {
+            let cos = n0.0 * n1.0 + n0.1 * n1.1;
+            if 1.0 + cos < 2.0 / (MITER_LIMIT * MITER_LIMIT) {
+                polygons.push(vec![p, a, b]);
+            } else {
+                let m = add(p, scale(add(n0, n1), side * hw / (1.0 + cos)));
+                polygons.push(vec![p, a, m, b]);
+            }
+        }
+    }
+}
+
+/// Get the polygons covered by a solid stroke along the points.
+fn stroke_solid(points: &[Vertex], closed: bool, hw: f32, pen: &PenStyle) -> Vec<Polygon> {
+    let mut polygons = Vec::new();
+    let n = points.len();
+    let count = if closed { n } else { n - 1 };
+    let dir = |i: usize| normalize(sub(points[(i + 1) % n], points[i]));
+    for i in 0..count {
+        let (mut a, mut b) = (points[i], points[(i + 1) % n]);
+        let d = dir(i);
+        if !closed && pen.cap_style == CapStyle::Square {
+            if i == 0 {
+                a = sub(a, scale(d, hw));
+            }
+            if i + 1 == count {
+                b = add(b, scale(d, hw));
+            }
+        }
+        let o = scale(normal(d), hw);
+        polygons.push(vec![add(a, o), add(b, o), sub(b, o), sub(a, o)]);
+    }
+    let joints = if closed { 0..n } else { 1..n - 1 };
+    for i in joints {
+        let prev = (i + count - 1) % count;
+        join(&mut polygons, points[i], dir(prev), dir(i), hw, pen.join_style);
+    }
+    if !closed && pen.cap_style == CapStyle::Round {
+        polygons.push(circle(points[0], hw));
+        polygons.push(circle(points[n - 1], hw));
+    }
+    polygons
+}
+
+/// Get the polygons covered by the pen along the points.
+/// The result must be filled with the non-zero rule.
+pub(crate) fn stroke(points: &[Vertex], closed: bool, pen: &PenStyle) -> Vec<Polygon> {
+    if pen.line_style == LineStyle::Null {
+        return Vec::new();
+    }
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| length(sub(*a, *b)) < 1e-3);
+    if closed && points.len() > 1 && length(sub(points[0], points[points.len() - 1])) < 1e-3 {
+        points.pop();
+    }
+    if points.len() < 2 {
+        return Vec::new();
+    }
+    let width = pen.width.max(1) as f32;
+    let hw = width / 2.0;
+    let mut polygons = match dash_pattern(pen.line_style) {
+        None => stroke_solid(&points, closed, hw, pen),
+        Some(pattern) => {
+            if closed {
+                points.push(points[0]);
+            }
+            split_dashes(&points, pattern, width)
+                .iter()
+                .flat_map(|dash| stroke_solid(dash, false, hw, pen))
+                .collect()
+        }
+    };
+    for polygon in polygons.iter_mut() {
+        if signed_area(polygon) < 0.0 {
+            polygon.reverse();
+        }
+    }
+    polygons
+}
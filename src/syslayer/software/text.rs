@@ -0,0 +1,224 @@
+//! Text drawing with a built-in bitmap font.
+//! The font covers printable ASCII and the ellipsis; other characters are drawn as a box.
+//! Glyphs are 8x16 pixels and are scaled to the size of the font.
+//! Fonts whose family was loaded as a `FontFace` are drawn with its outlines instead.
+
+use crate::*;
+
+use super::outline;
+use super::raster::{fill_polygons, FillRule};
+
+const GLYPH_WIDTH: i32 = 8;
+const GLYPH_HEIGHT: i32 = 16;
+/// The row of the glyphs on which the text sits.
+const BASELINE: i32 = 12;
+
+/// Glyphs of the characters from `' '` to `'~'`, one byte per row.
+/// The most significant bit is the leftmost pixel.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 16]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // '!'
+    [0x00, 0x00, 0x24, 0x24, 0x24, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x00, 0x00, 0x1a, 0x12, 0x12, 0x7f, 0x34, 0x24, 0xff, 0x2c, 0x68, 0x48, 0x00, 0x00, 0x00, 0x00], // '#'
+    [0x00, 0x00, 0x08, 0x3e, 0x68, 0x68, 0x28, 0x1e, 0x0a, 0x0b, 0x0a, 0x3c, 0x08, 0x08, 0x00, 0x00], // '$'
+    [0x00, 0x00, 0x60, 0xd0, 0x88, 0x50, 0x26, 0x30, 0x4f, 0x09, 0x09, 0x06, 0x00, 0x00, 0x00, 0x00], // '%'
+    [0x00, 0x00, 0x3c, 0x20, 0x20, 0x30, 0x70, 0x49, 0xcd, 0xc7, 0x46, 0x3f, 0x00, 0x00, 0x00, 0x00], // '&'
+    [0x00, 0x00, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '\''
+    [0x00, 0x04, 0x08, 0x08, 0x18, 0x10, 0x10, 0x10, 0x10, 0x10, 0x18, 0x08, 0x08, 0x04, 0x00, 0x00], // '('
+    [0x00, 0x00, 0x10, 0x18, 0x08, 0x08, 0x08, 0x0c, 0x08, 0x08, 0x08, 0x18, 0x10, 0x10, 0x00, 0x00], // ')'
+    [0x00, 0x00, 0x08, 0x6a, 0x18, 0x3c, 0x0a, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '*'
+    [0x00, 0x00, 0x00, 0x00, 0x08, 0x08, 0x08, 0x7f, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18, 0x10, 0x00, 0x00], // ','
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // '.'
+    [0x00, 0x00, 0x02, 0x06, 0x04, 0x0c, 0x08, 0x18, 0x10, 0x30, 0x20, 0x20, 0x40, 0x00, 0x00, 0x00], // '/'
+    [0x00, 0x00, 0x3c, 0x66, 0x62, 0x42, 0x5b, 0x4b, 0x42, 0x62, 0x26, 0x3c, 0x00, 0x00, 0x00, 0x00], // '0'
+    [0x00, 0x00, 0x38, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x3e, 0x00, 0x00, 0x00, 0x00], // '1'
+    [0x00, 0x00, 0x7c, 0x06, 0x02, 0x02, 0x04, 0x0c, 0x18, 0x30, 0x60, 0x7e, 0x00, 0x00, 0x00, 0x00], // '2'
+    [0x00, 0x00, 0x7c, 0x06, 0x02, 0x06, 0x1c, 0x06, 0x02, 0x02, 0x06, 0x7c, 0x00, 0x00, 0x00, 0x00], // '3'
+    [0x00, 0x00, 0x0c, 0x0c, 0x14, 0x34, 0x24, 0x44, 0x7e, 0x7e, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // '4'
+    [0x00, 0x00, 0x7e, 0x60, 0x60, 0x78, 0x2e, 0x02, 0x02, 0x02, 0x06, 0x7c, 0x00, 0x00, 0x00, 0x00], // '5'
+    [0x00, 0x00, 0x3e, 0x20, 0x60, 0x5c, 0x76, 0x62, 0x42, 0x62, 0x22, 0x3c, 0x00, 0x00, 0x00, 0x00], // '6'
+    [0x00, 0x00, 0x7e, 0x02, 0x06, 0x04, 0x0c, 0x08, 0x08, 0x18, 0x10, 0x30, 0x00, 0x00, 0x00, 0x00], // '7'
+    [0x00, 0x00, 0x3e, 0x62, 0x62, 0x22, 0x3c, 0x66, 0x42, 0x43, 0x62, 0x3c, 0x00, 0x00, 0x00, 0x00], // '8'
+    [0x00, 0x00, 0x3c, 0x62, 0x42, 0x42, 0x62, 0x3e, 0x12, 0x02, 0x06, 0x3c, 0x00, 0x00, 0x00, 0x00], // '9'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // ':'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x18, 0x00, 0x00, 0x00, 0x18, 0x18, 0x18, 0x10, 0x00, 0x00], // ';'
+    [0x00, 0x00, 0x00, 0x00, 0x01, 0x06, 0x38, 0x60, 0x70, 0x0e, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00], // '<'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7f, 0x00, 0x7e, 0x7e, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '='
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x70, 0x1c, 0x03, 0x0e, 0x70, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00], // '>'
+    [0x00, 0x00, 0x3e, 0x02, 0x02, 0x06, 0x0c, 0x18, 0x18, 0x00, 0x08, 0x18, 0x00, 0x00, 0x00, 0x00], // '?'
+    [0x00, 0x00, 0x08, 0x36, 0x41, 0x45, 0x9b, 0x91, 0x91, 0x91, 0xcf, 0x40, 0x20, 0x1e, 0x00, 0x00], // '@'
+    [0x00, 0x00, 0x18, 0x1c, 0x14, 0x34, 0x24, 0x26, 0x7e, 0x62, 0x43, 0xc1, 0x00, 0x00, 0x00, 0x00], // 'A'
+    [0x00, 0x00, 0x7e, 0x62, 0x62, 0x62, 0x7c, 0x62, 0x63, 0x63, 0x62, 0x7c, 0x00, 0x00, 0x00, 0x00], // 'B'
+    [0x00, 0x00, 0x1e, 0x20, 0x60, 0x40, 0x40, 0x40, 0x60, 0x60, 0x30, 0x1e, 0x00, 0x00, 0x00, 0x00], // 'C'
+    [0x00, 0x00, 0x7c, 0x46, 0x42, 0x42, 0x43, 0x43, 0x42, 0x42, 0x46, 0x78, 0x00, 0x00, 0x00, 0x00], // 'D'
+    [0x00, 0x00, 0x7e, 0x60, 0x60, 0x60, 0x7e, 0x60, 0x60, 0x60, 0x60, 0x7f, 0x00, 0x00, 0x00, 0x00], // 'E'
+    [0x00, 0x00, 0x3f, 0x20, 0x20, 0x20, 0x3e, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00], // 'F'
+    [0x00, 0x00, 0x3e, 0x60, 0x40, 0x40, 0x40, 0x47, 0x43, 0x63, 0x23, 0x1e, 0x00, 0x00, 0x00, 0x00], // 'G'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x7e, 0x42, 0x42, 0x42, 0x42, 0x42, 0x00, 0x00, 0x00, 0x00], // 'H'
+    [0x00, 0x00, 0x7e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x18, 0x7e, 0x00, 0x00, 0x00, 0x00], // 'I'
+    [0x00, 0x00, 0x1e, 0x06, 0x06, 0x06, 0x06, 0x06, 0x06, 0x04, 0x04, 0x7c, 0x00, 0x00, 0x00, 0x00], // 'J'
+    [0x00, 0x00, 0x43, 0x46, 0x4c, 0x58, 0x78, 0x68, 0x4c, 0x46, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00], // 'K'
+    [0x00, 0x00, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x60, 0x7f, 0x00, 0x00, 0x00, 0x00], // 'L'
+    [0x00, 0x00, 0x63, 0x67, 0x67, 0x55, 0x59, 0x59, 0x41, 0x41, 0x41, 0x41, 0x00, 0x00, 0x00, 0x00], // 'M'
+    [0x00, 0x00, 0x62, 0x62, 0x72, 0x52, 0x5a, 0x4a, 0x4a, 0x46, 0x46, 0x42, 0x00, 0x00, 0x00, 0x00], // 'N'
+    [0x00, 0x00, 0x3c, 0x62, 0x42, 0x43, 0x43, 0x43, 0x43, 0x62, 0x66, 0x3c, 0x00, 0x00, 0x00, 0x00], // 'O'
+    [0x00, 0x00, 0x7e, 0x63, 0x63, 0x63, 0x66, 0x7c, 0x60, 0x60, 0x60, 0x60, 0x00, 0x00, 0x00, 0x00], // 'P'
+    [0x00, 0x00, 0x3c, 0x62, 0x42, 0x43, 0x43, 0x43, 0x43, 0x62, 0x66, 0x3c, 0x04, 0x02, 0x00, 0x00], // 'Q'
+    [0x00, 0x00, 0x7c, 0x46, 0x42, 0x42, 0x6e, 0x7c, 0x46, 0x42, 0x43, 0x41, 0x00, 0x00, 0x00, 0x00], // 'R'
+    [0x00, 0x00, 0x3e, 0x60, 0x40, 0x60, 0x3c, 0x0e, 0x02, 0x02, 0x42, 0x7c, 0x00, 0x00, 0x00, 0x00], // 'S'
+    [0x00, 0x00, 0xff, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // 'T'
+    [0x00, 0x00, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x62, 0x3c, 0x00, 0x00, 0x00, 0x00], // 'U'
+    [0x00, 0x00, 0x43, 0x43, 0x62, 0x62, 0x26, 0x24, 0x34, 0x14, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // 'V'
+    [0x00, 0x00, 0x81, 0xc1, 0xc1, 0x59, 0x59, 0x57, 0x56, 0x66, 0x66, 0x66, 0x00, 0x00, 0x00, 0x00], // 'W'
+    [0x00, 0x00, 0x43, 0x22, 0x34, 0x1c, 0x18, 0x1c, 0x34, 0x26, 0x62, 0xc1, 0x00, 0x00, 0x00, 0x00], // 'X'
+    [0x00, 0x00, 0x43, 0x62, 0x26, 0x34, 0x18, 0x18, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00], // 'Y'
+    [0x00, 0x00, 0x7f, 0x02, 0x06, 0x04, 0x08, 0x18, 0x10, 0x20, 0x60, 0x7f, 0x00, 0x00, 0x00, 0x00], // 'Z'
+    [0x00, 0x1c, 0x18, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1c, 0x00, 0x00], // '['
+    [0x00, 0x00, 0x40, 0x60, 0x20, 0x30, 0x10, 0x18, 0x08, 0x0c, 0x04, 0x06, 0x02, 0x00, 0x00, 0x00], // '\\'
+    [0x00, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x38, 0x00, 0x00], // ']'
+    [0x00, 0x00, 0x18, 0x34, 0x62, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00], // '_'
+    [0x00, 0x10, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x00, 0x00, 0x3c, 0x26, 0x02, 0x3e, 0x62, 0x42, 0x46, 0x3a, 0x00, 0x00, 0x00, 0x00], // 'a'
+    [0x00, 0x00, 0x60, 0x60, 0x7c, 0x76, 0x62, 0x63, 0x63, 0x62, 0x62, 0x7c, 0x00, 0x00, 0x00, 0x00], // 'b'
+    [0x00, 0x00, 0x00, 0x00, 0x1e, 0x32, 0x20, 0x60, 0x60, 0x60, 0x20, 0x1e, 0x00, 0x00, 0x00, 0x00], // 'c'
+    [0x00, 0x02, 0x02, 0x02, 0x1a, 0x66, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3e, 0x00, 0x00, 0x00, 0x00], // 'd'
+    [0x00, 0x00, 0x00, 0x00, 0x1c, 0x26, 0x42, 0x7f, 0x60, 0x40, 0x60, 0x3e, 0x00, 0x00, 0x00, 0x00], // 'e'
+    [0x00, 0x06, 0x08, 0x18, 0x3e, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x00, 0x00, 0x00, 0x00], // 'f'
+    [0x00, 0x00, 0x00, 0x00, 0x1a, 0x66, 0x42, 0x42, 0x42, 0x42, 0x66, 0x3a, 0x02, 0x06, 0x3c, 0x00], // 'g'
+    [0x00, 0x00, 0x60, 0x60, 0x6c, 0x76, 0x62, 0x62, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // 'h'
+    [0x00, 0x08, 0x08, 0x00, 0x38, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x7e, 0x00, 0x00, 0x00, 0x00], // 'i'
+    [0x00, 0x08, 0x08, 0x00, 0x38, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x70, 0x00], // 'j'
+    [0x00, 0x20, 0x20, 0x20, 0x22, 0x26, 0x28, 0x38, 0x2c, 0x24, 0x22, 0x23, 0x00, 0x00, 0x00, 0x00], // 'k'
+    [0x00, 0x70, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x18, 0x0e, 0x00, 0x00, 0x00, 0x00], // 'l'
+    [0x00, 0x00, 0x00, 0x00, 0x76, 0x5b, 0x49, 0x49, 0x49, 0x49, 0x49, 0x49, 0x00, 0x00, 0x00, 0x00], // 'm'
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x76, 0x62, 0x62, 0x62, 0x62, 0x62, 0x62, 0x00, 0x00, 0x00, 0x00], // 'n'
+    [0x00, 0x00, 0x00, 0x00, 0x1c, 0x26, 0x62, 0x42, 0x42, 0x42, 0x62, 0x3c, 0x00, 0x00, 0x00, 0x00], // 'o'
+    [0x00, 0x00, 0x00, 0x00, 0x1c, 0x76, 0x62, 0x63, 0x63, 0x62, 0x62, 0x7c, 0x60, 0x60, 0x60, 0x00], // 'p'
+    [0x00, 0x00, 0x00, 0x00, 0x1a, 0x26, 0x62, 0x42, 0x42, 0x42, 0x66, 0x3e, 0x02, 0x02, 0x02, 0x00], // 'q'
+    [0x00, 0x00, 0x00, 0x00, 0x07, 0x39, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x00, 0x00, 0x00, 0x00], // 'r'
+    [0x00, 0x00, 0x00, 0x00, 0x1c, 0x20, 0x20, 0x30, 0x1e, 0x02, 0x02, 0x3c, 0x00, 0x00, 0x00, 0x00], // 's'
+    [0x00, 0x00, 0x10, 0x10, 0x7e, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x0e, 0x00, 0x00, 0x00, 0x00], // 't'
+    [0x00, 0x00, 0x00, 0x00, 0x02, 0x62, 0x62, 0x62, 0x62, 0x62, 0x26, 0x3a, 0x00, 0x00, 0x00, 0x00], // 'u'
+    [0x00, 0x00, 0x00, 0x00, 0x40, 0x42, 0x62, 0x26, 0x24, 0x14, 0x1c, 0x18, 0x00, 0x00, 0x00, 0x00], // 'v'
+    [0x00, 0x00, 0x00, 0x00, 0x81, 0x81, 0x41, 0x59, 0x5a, 0x56, 0x66, 0x26, 0x00, 0x00, 0x00, 0x00], // 'w'
+    [0x00, 0x00, 0x00, 0x00, 0x42, 0x26, 0x34, 0x18, 0x18, 0x34, 0x26, 0x43, 0x00, 0x00, 0x00, 0x00], // 'x'
+    [0x00, 0x00, 0x00, 0x00, 0x40, 0x42, 0x22, 0x26, 0x34, 0x14, 0x1c, 0x08, 0x18, 0x10, 0x70, 0x00], // 'y'
+    [0x00, 0x00, 0x00, 0x00, 0x3e, 0x06, 0x04, 0x08, 0x18, 0x30, 0x20, 0x7e, 0x00, 0x00, 0x00, 0x00], // 'z'
+    [0x00, 0x06, 0x0c, 0x08, 0x08, 0x08, 0x18, 0x30, 0x18, 0x08, 0x08, 0x08, 0x08, 0x0e, 0x00, 0x00], // '{'
+    [0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00], // '|'
+    [0x00, 0x20, 0x18, 0x18, 0x08, 0x08, 0x08, 0x0e, 0x0c, 0x08, 0x08, 0x08, 0x18, 0x30, 0x00, 0x00], // '}'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7f, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // '~'
+];
+
+/// The glyph drawn for characters that the font does not cover.
+const MISSING: [u8; 16] = [
+    0x00, 0x00, 0x7e, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x42, 0x7e, 0x00, 0x00, 0x00, 0x00,
+];
+
+/// The glyph of `'…'`, which ends truncated lines.
+const ELLIPSIS: [u8; 16] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x49, 0x49, 0x00, 0x00, 0x00, 0x00,
+];
+
+fn glyph(c: char) -> &'static [u8; 16] {
+    match c {
+        ' '..='~' => &GLYPHS[c as usize - ' ' as usize],
+        '…' => &ELLIPSIS,
+        _ => &MISSING,
+    }
+}
+
+/// The height of a line of text in pixels.
+pub(crate) fn line_height(font: &FontStyle) -> i32 {
+    if font.size > 0 {
+        font.size
+    } else {
+        GLYPH_HEIGHT
+    }
+}
+
+/// The horizontal advance of every character in pixels.
+fn advance(font: &FontStyle) -> i32 {
+    (line_height(font) * GLYPH_WIDTH / GLYPH_HEIGHT).max(1)
+}
+
+/// Get the size of the box taken by the single-line `text`.
+pub(crate) fn text_size(font: &FontStyle, text: &str) -> Size {
+    if let Some(face) = FontFace::find(font) {
+        return outline::text_size(&face, font, text);
+    }
+    size!(advance(font) * text.chars().count() as i32, line_height(font))
+}
+
+pub(crate) fn font_metrics(font: &FontStyle) -> FontMetrics {
+    if let Some(face) = FontFace::find(font) {
+        return outline::font_metrics(&face, font);
+    }
+    let height = line_height(font);
+    let ascent = BASELINE * height / GLYPH_HEIGHT;
+    FontMetrics {
+        ascent,
+        descent: height - ascent,
+        line_height: height,
+    }
+}
+
+/// Draw the single-line `text` with its top-left corner at `pos`.
+pub(crate) fn draw_text(
+    image: &mut Image,
+    pos: Point,
+    text: &str,
+    font: &FontStyle,
+    color: Color,
+    clip: Rect,
+) {
+    if let Some(face) = FontFace::find(font) {
+        return outline::draw_text(image, pos, text, &face, font, color, clip);
+    }
+    let Some(clip) = clip & rect!(0, 0, image.size().width, image.size().height) else {
+        return;
+    };
+    let (height, advance) = (line_height(font), advance(font));
+    let stroke = (height / GLYPH_HEIGHT).max(1);
+    let bold = font.weight as i32 >= FontWeight::SemiBold as i32;
+    let baseline = BASELINE * height / GLYPH_HEIGHT;
+    let mut x = pos.x;
+    for c in text.chars() {
+        let rows = glyph(c);
+        for py in 0..height {
+            let row = rows[(py * GLYPH_HEIGHT / height) as usize];
+            if row == 0 {
+                continue;
+            }
+            // italic glyphs lean right by one pixel every four rows above the baseline
+            let shear = if font.italic { (baseline - py) / 4 } else { 0 };
+            for px in 0..advance + stroke {
+                let col = px * GLYPH_WIDTH / advance;
+                let lit = |col: i32| (0..GLYPH_WIDTH).contains(&col) && row & (0x80 >> col) != 0;
+                let on = lit(col) || (bold && (1..=stroke).any(|d| lit((px - d) * GLYPH_WIDTH / advance)));
+                let p = pos!(x + px + shear, pos.y + py);
+                if on && p.within(&clip) {
+                    image.blend_pixel(p, color, 255);
+                }
+            }
+        }
+        x += advance;
+    }
+    let width = x - pos.x;
+    let mut line = |y: i32| {
+        let (l, t) = (pos.x as f32, (pos.y + y) as f32);
+        let (r, b) = (l + width as f32, t + stroke as f32);
+        let polygon = vec![(l, t), (r, t), (r, b), (l, b)];
+        fill_polygons(image, &[polygon], FillRule::NonZero, color, clip);
+    };
+    if font.underline {
+        line(baseline + stroke);
+    }
+    if font.strikeout {
+        line(baseline * 2 / 3);
+    }
+}
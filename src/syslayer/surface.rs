@@ -0,0 +1,50 @@
+//! This file defines the `Surface` trait, the target that a `Canvas` draws on.
+//! `Raster` draws into an `Image`, and the Windows backend provides a surface for its device contexts.
+
+use crate::*;
+
+pub(crate) use super::software::Raster;
+
+/// Something that can be drawn on by a `Canvas`.
+/// The methods mirror the ones of `Canvas`.
+pub(crate) trait Surface {
+    /// Fill the whole target with `color`.
+    /// `rect` is the rect of the window, positioned in its parent, so only its size is used
+    /// and the fill starts at the origin of the target.
+    fn clear(&self, rect: Rect, color: Color);
+    /// Select the pen and return the previous one.
+    fn set_pen(&self, pen: &Pen) -> Pen;
+    /// Select the brush and return the previous one.
+    fn set_brush(&self, brush: &Brush) -> Brush;
+    /// Select the font and return the previous one.
+    fn set_font(&self, font: &Font) -> Font;
+    /// Set the text color and return the previous one.
+    fn set_text_color(&self, color: Color) -> Color;
+    fn line(&self, x1: i32, y1: i32, x2: i32, y2: i32);
+    fn rect(&self, rect: Rect);
+    fn round_rect(&self, rect: Rect, rx: i32, ry: i32);
+    fn polyline(&self, points: &[Point]);
+    fn polygon(&self, points: &[Point]);
+    fn arc(&self, rect: Rect, start: f32, sweep: f32);
+    fn pie(&self, rect: Rect, start: f32, sweep: f32);
+    fn ellipse(&self, rect: Rect);
+    fn circle(&self, pos: Point, radius: i32);
+    fn fill_rect(&self, rect: Rect);
+    fn fill_round_rect(&self, rect: Rect, rx: i32, ry: i32);
+    fn fill_polygon(&self, points: &[Point]);
+    fn fill_pie(&self, rect: Rect, start: f32, sweep: f32);
+    fn fill_ellipse(&self, rect: Rect);
+    fn fill_circle(&self, pos: Point, radius: i32);
+    /// Draw the single-line `text` with its top-left corner at `pos`, clipped to `clip` if any.
+    /// Aligning and breaking the text into lines is left to `Canvas`.
+    fn text_line(&self, pos: Point, text: &str, clip: Option<Rect>);
+    /// Measure `text` with the selected font.
+    fn text_extent(&self, text: &str) -> Size;
+    /// Get the metrics of the selected font.
+    fn font_metrics(&self) -> FontMetrics;
+
+    /// Get the image drawn on the surface, if it has one.
+    fn into_image(self: Box<Self>) -> Option<Image> {
+        None
+    }
+}
@@ -0,0 +1,844 @@
+use std::{
+    any::{type_name, Any},
+    os::raw::c_void,
+    ptr::{null, null_mut},
+};
+
+use winapi::{
+    shared::{
+        minwindef::{LPARAM, UINT, WPARAM},
+        ntdef::WCHAR,
+        windef::{HBRUSH, HWND, POINT, RECT, SIZE},
+    },
+    um::{
+        libloaderapi::GetModuleHandleW,
+        winbase::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE},
+        wincon::FreeConsole,
+        wingdi::*,
+        winuser::*,
+    },
+};
+
+use crate::*;
+
+use super::keys::key_to_vk;
+use super::*;
+
+/// The backend built on the Win32 API and GDI.
+pub(crate) struct Win32;
+
+fn register_class(class_name: &[WCHAR]) {
+    unsafe {
+        let hinstance = GetModuleHandleW(null_mut());
+        let windows = WNDCLASSEXW {
+            cbSize: size_of::<WNDCLASSEXW>() as u32,
+            style: CS_HREDRAW | CS_VREDRAW | CS_DBLCLKS,
+            lpfnWndProc: Some(winproc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: hinstance,
+            hIcon: LoadIconW(null_mut(), IDI_APPLICATION),
+            hCursor: LoadCursorW(null_mut(), IDC_ARROW),
+            hbrBackground: (COLOR_WINDOW + 1) as HBRUSH,
+            lpszMenuName: null_mut(),
+            lpszClassName: class_name.as_ptr() as _,
+            hIconSm: null_mut(),
+        };
+        let mut wnd = windows;
+        if GetClassInfoExW(hinstance, class_name.as_ptr(), &mut wnd as *mut _) == 0 {
+            RegisterClassExW(&windows);
+        }
+    }
+}
+
+fn rect_of(rect: RECT) -> Rect {
+    rect!(
+        rect.left,
+        rect.top,
+        rect.right - rect.left,
+        rect.bottom - rect.top
+    )
+}
+
+/// Get the `MK_*` flags of a mouse message. Alt and the Windows keys have no flag.
+fn mods_to_wparam(mods: Modifiers) -> WPARAM {
+    let flags = [
+        (Modifiers::SHIFT, MK_SHIFT),
+        (Modifiers::CTRL, MK_CONTROL),
+        (Modifiers::LEFT_BUTTON, MK_LBUTTON),
+        (Modifiers::RIGHT_BUTTON, MK_RBUTTON),
+        (Modifiers::MIDDLE_BUTTON, MK_MBUTTON),
+        (Modifiers::X1_BUTTON, MK_XBUTTON1),
+        (Modifiers::X2_BUTTON, MK_XBUTTON2),
+    ];
+    flags
+        .iter()
+        .filter(|(modifier, _)| mods.contains(*modifier))
+        .fold(0, |wparam, (_, flag)| wparam | flag)
+}
+
+fn pos_to_lparam(pos: Point) -> LPARAM {
+    ((pos.x & 0xffff) | ((pos.y & 0xffff) << 16)) as LPARAM
+}
+
+/// Get the message of a mouse button event and the high word of its `wparam`.
+/// `which` selects the message: 0 for pressed, 1 for released and 2 for double clicked.
+fn button_msg(button: MouseButton, which: usize) -> (UINT, WPARAM) {
+    let (msgs, high) = match button {
+        MouseButton::Left => ([WM_LBUTTONDOWN, WM_LBUTTONUP, WM_LBUTTONDBLCLK], 0),
+        MouseButton::Right => ([WM_RBUTTONDOWN, WM_RBUTTONUP, WM_RBUTTONDBLCLK], 0),
+        MouseButton::Middle => ([WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MBUTTONDBLCLK], 0),
+        MouseButton::Other(x) => (
+            [WM_XBUTTONDOWN, WM_XBUTTONUP, WM_XBUTTONDBLCLK],
+            x as WPARAM,
+        ),
+    };
+    (msgs[which], high << 16)
+}
+
+/// Read a device independent bitmap of 24 or 32 bits per pixel without compression.
+/// A 32-bit bitmap whose alpha channel is empty is taken as opaque.
+unsafe fn dib_to_image(ptr: *const u8) -> Option<Image> {
+    let header = std::ptr::read_unaligned(ptr as *const BITMAPINFOHEADER);
+    let bits = header.biBitCount as usize;
+    if header.biCompression != BI_RGB || (bits != 24 && bits != 32) {
+        return None;
+    }
+    let (width, height) = (
+        header.biWidth.max(0) as usize,
+        header.biHeight.unsigned_abs() as usize,
+    );
+    let stride = (width * bits).div_ceil(32) * 4;
+    let pixels = ptr.add(header.biSize as usize + header.biClrUsed as usize * 4);
+    let mut data = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        // rows are stored from the bottom up unless the height is negative
+        let row = if header.biHeight > 0 {
+            height - 1 - y
+        } else {
+            y
+        };
+        let row = pixels.add(row * stride);
+        for x in 0..width {
+            let pixel = row.add(x * bits / 8);
+            let alpha = if bits == 32 { *pixel.add(3) } else { 255 };
+            data.extend([*pixel.add(2), *pixel.add(1), *pixel, alpha]);
+        }
+    }
+    if bits == 32 && data.chunks(4).all(|pixel| pixel[3] == 0) {
+        data.chunks_mut(4).for_each(|pixel| pixel[3] = 255);
+    }
+    Image::from_rgba(size!(width as i32, height as i32), data)
+}
+
+impl Backend for Win32 {
+    fn init(show_console: bool) {
+        unsafe {
+            if !show_console {
+                FreeConsole();
+            }
+            SetProcessDPIAware();
+        }
+    }
+
+    fn event_loop() {
+        let mut msg = MSG {
+            hwnd: null_mut(),
+            message: 0,
+            wParam: 0,
+            lParam: 0,
+            time: 0,
+            pt: POINT { x: 0, y: 0 },
+        };
+        unsafe {
+            loop {
+                if PeekMessageW(&mut msg, null_mut(), 0, 0, PM_REMOVE) != 0 {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                    if msg.message == WM_QUIT {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    fn exit(code: i32) {
+        unsafe {
+            PostQuitMessage(code);
+        }
+    }
+
+    fn dpi_scale() -> f32 {
+        unsafe { 96.0 / GetDpiForSystem() as f32 }
+    }
+
+    fn create_window<T: Ele>(
+        title: &str,
+        rect: Rect,
+        parent: Option<Handle>,
+        widget: &Widget<T>,
+    ) -> Handle {
+        let class_name = type_name::<T>()
+            .encode_utf16()
+            .chain(Some(0))
+            .collect::<Vec<WCHAR>>();
+        register_class(&class_name);
+        let title = title.encode_utf16().chain(Some(0)).collect::<Vec<WCHAR>>();
+        let hwnd = unsafe {
+            CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                title.as_ptr(),
+                if parent.is_none() {
+                    WS_OVERLAPPEDWINDOW
+                } else {
+                    WS_CHILD
+                },
+                rect.pos.x,
+                rect.pos.y,
+                rect.size.width,
+                rect.size.height,
+                parent.map_or(null_mut(), |parent| parent.0 as HWND),
+                null_mut(),
+                null_mut(),
+                widget.addr() as _,
+            )
+        };
+        let mut tme = TRACKMOUSEEVENT {
+            cbSize: size_of::<TRACKMOUSEEVENT>() as u32,
+            dwFlags: TME_HOVER | TME_LEAVE,
+            hwndTrack: hwnd,
+            dwHoverTime: 0,
+        };
+        unsafe { TrackMouseEvent(&mut tme) };
+        Handle(hwnd as _)
+    }
+
+    fn release_window(window: Handle) {
+        unsafe {
+            SetWindowLongPtrW(window.0 as _, GWLP_USERDATA, 0);
+        }
+    }
+
+    fn window_rect(window: Handle) -> Rect {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        unsafe {
+            GetClientRect(window.0 as _, &mut rect as _);
+        }
+        rect_of(rect)
+    }
+
+    fn absolute_rect(window: Handle) -> Rect {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        unsafe {
+            GetWindowRect(window.0 as _, &mut rect as _);
+        }
+        rect_of(rect)
+    }
+
+    fn window_pos(window: Handle) -> Point {
+        let rect = Self::absolute_rect(window);
+        let mut point = POINT {
+            x: rect.pos.x,
+            y: rect.pos.y,
+        };
+        if let Some(parent) = Self::parent_window(window) {
+            unsafe {
+                ScreenToClient(parent.0 as _, &mut point);
+            }
+        }
+        pos!(point.x, point.y)
+    }
+
+    fn parent_window(window: Handle) -> Option<Handle> {
+        unsafe {
+            // the parent of a top-level window is its owner, which does not contain it
+            if GetWindowLongW(window.0 as _, GWL_STYLE) as u32 & WS_CHILD == 0 {
+                return None;
+            }
+            let parent = GetParent(window.0 as _);
+            (!parent.is_null()).then_some(Handle(parent as _))
+        }
+    }
+
+    fn child_windows(window: Handle) -> Vec<Handle> {
+        let mut found: (*mut c_void, Vec<*mut c_void>) = (window.0, Vec::new());
+        unsafe {
+            EnumChildWindows(
+                window.0 as _,
+                Some(collect_children_callback),
+                &mut found as *mut _ as _,
+            );
+        }
+        found.1.into_iter().map(Handle).collect()
+    }
+
+    fn set_window_rect(window: Handle, rect: Rect) {
+        let (x, y, w, h) = rect.into();
+        unsafe {
+            SetWindowPos(
+                window.0 as _,
+                HWND_TOP,
+                x,
+                y,
+                w,
+                h,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+    }
+
+    fn set_window_pos(window: Handle, pos: Point) {
+        let (x, y) = pos.into();
+        unsafe {
+            SetWindowPos(
+                window.0 as _,
+                HWND_TOP,
+                x,
+                y,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+    }
+
+    fn set_window_size(window: Handle, size: Size) {
+        let (w, h) = size.into();
+        unsafe {
+            SetWindowPos(
+                window.0 as _,
+                HWND_TOP,
+                0,
+                0,
+                w,
+                h,
+                SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+    }
+
+    fn window_title(window: Handle) -> String {
+        let len = unsafe { GetWindowTextLengthW(window.0 as _) };
+        let mut title = vec![0u16; len as usize + 1];
+        unsafe {
+            GetWindowTextW(window.0 as _, title.as_mut_ptr(), len + 1);
+        }
+        String::from_utf16_lossy(&title)
+    }
+
+    fn set_window_title(window: Handle, title: &str) {
+        let title = title.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        unsafe {
+            SetWindowTextW(window.0 as _, title.as_ptr());
+        }
+    }
+
+    fn show_window(window: Handle) {
+        unsafe {
+            ShowWindow(window.0 as _, SW_SHOW);
+            UpdateWindow(window.0 as _);
+        }
+    }
+
+    fn set_window_visible(window: Handle, visible: bool) {
+        unsafe {
+            ShowWindow(window.0 as _, if visible { SW_SHOW } else { SW_HIDE });
+        }
+    }
+
+    fn minimize_window(window: Handle) {
+        unsafe {
+            ShowWindow(window.0 as _, SW_MINIMIZE);
+        }
+    }
+
+    fn maximize_window(window: Handle) {
+        unsafe {
+            ShowWindow(window.0 as _, SW_MAXIMIZE);
+        }
+    }
+
+    fn restore_window(window: Handle) {
+        unsafe {
+            ShowWindow(window.0 as _, SW_RESTORE);
+        }
+    }
+
+    fn disable_maximize(window: Handle) {
+        unsafe {
+            let style = GetWindowLongW(window.0 as _, GWL_STYLE) as u32;
+            SetWindowLongW(window.0 as _, GWL_STYLE, (style & !WS_MAXIMIZEBOX) as _);
+        }
+    }
+
+    fn set_window_enabled(window: Handle, enabled: bool) {
+        unsafe {
+            EnableWindow(window.0 as _, enabled as _);
+        }
+    }
+
+    fn window_style(window: Handle) -> WindowStyle {
+        let style = unsafe { GetWindowLongW(window.0 as _, GWL_STYLE) as u32 };
+        let ex_style = unsafe { GetWindowLongW(window.0 as _, GWL_EXSTYLE) as u32 };
+        let mut style_ = WindowStyle::default();
+        if style & WS_BORDER != 0 {
+            style_.border = true;
+        }
+        if style & WS_CAPTION != 0 {
+            style_.caption = true;
+        }
+        if style & WS_CHILD != 0 {
+            style_.child = true;
+        }
+        if style & WS_THICKFRAME != 0 {
+            style_.resize = true;
+        }
+        if style & WS_SYSMENU != 0 {
+            style_.sysmenu = true;
+        }
+        if style & WS_MINIMIZEBOX != 0 {
+            style_.minbox = true;
+        }
+        if style & WS_MAXIMIZEBOX != 0 {
+            style_.maxbox = true;
+        }
+        if ex_style & WS_EX_TOPMOST != 0 {
+            style_.topmost = true;
+        }
+        style_
+    }
+
+    fn set_window_style(window: Handle, style: WindowStyle) {
+        let mut style_ = 0;
+        if style.border {
+            style_ |= WS_BORDER;
+        }
+        if style.caption {
+            style_ |= WS_CAPTION;
+        }
+        if style.child {
+            style_ |= WS_CHILD;
+        }
+        if style.resize {
+            style_ |= WS_THICKFRAME;
+        }
+        if style.sysmenu {
+            style_ |= WS_SYSMENU;
+        }
+        if style.minbox {
+            style_ |= WS_MINIMIZEBOX;
+        }
+        if style.maxbox {
+            style_ |= WS_MAXIMIZEBOX;
+        }
+        let ex_style = 0;
+        if style.topmost {
+            unsafe {
+                SetWindowPos(
+                    window.0 as _,
+                    HWND_TOPMOST,
+                    0,
+                    0,
+                    0,
+                    0,
+                    SWP_NOMOVE | SWP_NOSIZE,
+                );
+            }
+        }
+        unsafe {
+            SetWindowLongW(window.0 as _, GWL_STYLE, style_ as _);
+            SetWindowLongW(window.0 as _, GWL_EXSTYLE, ex_style as _);
+        }
+    }
+
+    fn is_window_visible(window: Handle) -> bool {
+        unsafe { IsWindowVisible(window.0 as _) != 0 }
+    }
+
+    fn is_window_enabled(window: Handle) -> bool {
+        unsafe { IsWindowEnabled(window.0 as _) != 0 }
+    }
+
+    fn set_focus(window: Handle) {
+        unsafe {
+            SetFocus(window.0 as _);
+        }
+    }
+
+    fn has_focus(window: Handle) -> bool {
+        Self::focused_window() == Some(window)
+    }
+
+    fn update_window(window: Handle) {
+        unsafe {
+            RedrawWindow(
+                window.0 as _,
+                null(),
+                null_mut(),
+                RDW_INVALIDATE | RDW_UPDATENOW,
+            );
+        }
+    }
+
+    fn for_each_child(window: Handle, mut f: ChildVisitor) {
+        unsafe {
+            EnumChildWindows(
+                window.0 as _,
+                Some(enum_windows_callback),
+                &mut f as *mut _ as _,
+            );
+        }
+    }
+
+    fn visit_window(window: Handle, f: &mut dyn FnMut(&mut dyn Ele)) {
+        let object =
+            unsafe { GetWindowLongPtrW(window.0 as _, GWLP_USERDATA) } as *mut (Box<dyn Ele>, bool);
+        if let Some((obj, _)) = unsafe { object.as_mut() } {
+            f(obj.as_mut());
+        }
+    }
+
+    fn send_message(window: Handle, msg: Box<dyn Any>) {
+        // the message is boxed again, because a `Box<dyn Any>` is too wide for an `LPARAM`
+        let msg = Box::new(msg);
+        unsafe {
+            SendMessageW(window.0 as _, USER_DEF_MSG, 0, Box::into_raw(msg) as _);
+        }
+    }
+
+    fn send_created(window: Handle) {
+        unsafe {
+            SendMessageW(window.0 as _, WINDOW_CREATED_MSG, 0, 0);
+        }
+    }
+
+    fn register_hotkey(window: Handle, id: i32, key: KeyCode, modifiers: HotKeyFlags) {
+        let vk = key_to_vk(key);
+        if vk == 0 {
+            return;
+        }
+        let modifiers = if modifiers.alt { MOD_ALT } else { 0 }
+            | if modifiers.ctrl { MOD_CONTROL } else { 0 }
+            | if modifiers.shift { MOD_SHIFT } else { 0 }
+            | if modifiers.win { MOD_WIN } else { 0 }
+            | MOD_NOREPEAT;
+        unsafe {
+            RegisterHotKey(window.0 as _, id, modifiers as u32, vk as _);
+        }
+    }
+
+    /// Send the message that the input event comes from to the window,
+    /// so that it goes through `winproc` like real input.
+    fn send_input(window: Handle, event: Event) {
+        let (msg, wparam, lparam): (UINT, WPARAM, LPARAM) = match event {
+            Event::KeyPressed { key, sys, .. } => {
+                let msg = if sys { WM_SYSKEYDOWN } else { WM_KEYDOWN };
+                (msg, key_to_vk(key) as _, 0)
+            }
+            Event::KeyReleased { key, sys, .. } => {
+                let msg = if sys { WM_SYSKEYUP } else { WM_KEYUP };
+                (msg, key_to_vk(key) as _, 0)
+            }
+            Event::Input { ch } => (WM_CHAR, ch as _, 0),
+            Event::MouseMoved { pos, mods } => {
+                (WM_MOUSEMOVE, mods_to_wparam(mods), pos_to_lparam(pos))
+            }
+            Event::MouseButtonPressed { button, pos, mods } => {
+                let (msg, high) = button_msg(button, 0);
+                (msg, high | mods_to_wparam(mods), pos_to_lparam(pos))
+            }
+            Event::MouseButtonReleased { button, pos, mods } => {
+                let (msg, high) = button_msg(button, 1);
+                (msg, high | mods_to_wparam(mods), pos_to_lparam(pos))
+            }
+            Event::MouseDoubleClicked { button, pos, mods } => {
+                let (msg, high) = button_msg(button, 2);
+                (msg, high | mods_to_wparam(mods), pos_to_lparam(pos))
+            }
+            Event::Hover { pos, mods } => (WM_MOUSEHOVER, mods_to_wparam(mods), pos_to_lparam(pos)),
+            Event::Leave => (WM_MOUSELEAVE, 0, 0),
+            _ => {
+                // other events are not input, so they are delivered to the element directly,
+                // and so is the scrolling, whose precise delta in both axes does not fit in the
+                // wheel messages
+                deliver(window, &event);
+                return;
+            }
+        };
+        // the keyboard and the mouse are not in the state the event tells, so winproc reports
+        // the modifiers of the event instead
+        SIMULATED_MODIFIERS.with(|mods| mods.set(event.modifiers()));
+        unsafe {
+            SendMessageW(window.0 as _, msg, wparam, lparam);
+        }
+        SIMULATED_MODIFIERS.with(|mods| mods.set(None));
+    }
+
+    /// Nothing is found if the window under `pos` is disabled or is not created by this library.
+    fn window_at(pos: Point) -> Option<(Handle, Point)> {
+        let mut point = POINT { x: pos.x, y: pos.y };
+        unsafe {
+            let hwnd = WindowFromPoint(point);
+            if hwnd.is_null()
+                || IsWindowEnabled(hwnd) == 0
+                || GetWindowLongPtrW(hwnd, GWLP_USERDATA) == 0
+            {
+                return None;
+            }
+            ScreenToClient(hwnd, &mut point);
+            Some((Handle(hwnd as _), pos!(point.x, point.y)))
+        }
+    }
+
+    fn focused_window() -> Option<Handle> {
+        let hwnd = unsafe { GetFocus() };
+        (!hwnd.is_null()).then_some(Handle(hwnd as _))
+    }
+
+    fn set_timer(window: Handle, id: usize, interval: u32) {
+        unsafe {
+            SetTimer(window.0 as _, id, interval, None);
+        }
+    }
+
+    fn kill_timer(window: Handle, id: usize) {
+        unsafe {
+            KillTimer(window.0 as _, id);
+        }
+    }
+
+    fn clipboard_text() -> Option<String> {
+        unsafe {
+            if OpenClipboard(null_mut()) == 0 {
+                return None;
+            }
+            let data = GetClipboardData(CF_UNICODETEXT);
+            let ptr = if data.is_null() {
+                null()
+            } else {
+                GlobalLock(data) as *const u16
+            };
+            let text = if ptr.is_null() {
+                None
+            } else {
+                let mut len = 0;
+                while *ptr.add(len) != 0 {
+                    len += 1;
+                }
+                let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+                GlobalUnlock(data);
+                Some(text)
+            };
+            CloseClipboard();
+            text
+        }
+    }
+
+    fn set_clipboard_text(text: &str) {
+        let text = text.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        unsafe {
+            if OpenClipboard(null_mut()) == 0 {
+                return;
+            }
+            EmptyClipboard();
+            let mem = GlobalAlloc(GMEM_MOVEABLE, text.len() * size_of::<u16>());
+            if !mem.is_null() {
+                let ptr = GlobalLock(mem) as *mut u16;
+                std::ptr::copy_nonoverlapping(text.as_ptr(), ptr, text.len());
+                GlobalUnlock(mem);
+                SetClipboardData(CF_UNICODETEXT, mem);
+            }
+            CloseClipboard();
+        }
+    }
+
+    fn clipboard_image() -> Option<Image> {
+        unsafe {
+            if OpenClipboard(null_mut()) == 0 {
+                return None;
+            }
+            let data = GetClipboardData(CF_DIB);
+            let ptr = if data.is_null() {
+                null()
+            } else {
+                GlobalLock(data) as *const u8
+            };
+            let image = if ptr.is_null() {
+                None
+            } else {
+                let image = dib_to_image(ptr);
+                GlobalUnlock(data);
+                image
+            };
+            CloseClipboard();
+            image
+        }
+    }
+
+    fn set_clipboard_image(image: &Image) {
+        let size = image.size();
+        let (width, height) = (size.width as usize, size.height as usize);
+        let header_size = size_of::<BITMAPINFOHEADER>();
+        let pixels_size = width * height * 4;
+        if pixels_size == 0 {
+            return;
+        }
+        unsafe {
+            if OpenClipboard(null_mut()) == 0 {
+                return;
+            }
+            EmptyClipboard();
+            let mem = GlobalAlloc(GMEM_MOVEABLE, header_size + pixels_size);
+            if !mem.is_null() {
+                let ptr = GlobalLock(mem) as *mut u8;
+                let mut header: BITMAPINFOHEADER = std::mem::zeroed();
+                header.biSize = header_size as u32;
+                header.biWidth = size.width;
+                header.biHeight = size.height;
+                header.biPlanes = 1;
+                header.biBitCount = 32;
+                header.biCompression = BI_RGB;
+                header.biSizeImage = pixels_size as u32;
+                std::ptr::write_unaligned(ptr as *mut BITMAPINFOHEADER, header);
+                // the rows go from the bottom up, and every pixel is in BGRA order
+                let pixels = ptr.add(header_size);
+                for (y, row) in image.data().chunks(width * 4).enumerate() {
+                    let target = pixels.add((height - 1 - y) * width * 4);
+                    for (x, pixel) in row.chunks(4).enumerate() {
+                        let bgra = [pixel[2], pixel[1], pixel[0], pixel[3]];
+                        std::ptr::copy_nonoverlapping(bgra.as_ptr(), target.add(x * 4), 4);
+                    }
+                }
+                GlobalUnlock(mem);
+                SetClipboardData(CF_DIB, mem);
+            }
+            CloseClipboard();
+        }
+    }
+
+    fn new_pen(style: &PenStyle) -> Handle {
+        let end_cap = match style.cap_style {
+            CapStyle::Flat => PS_ENDCAP_FLAT,
+            CapStyle::Square => PS_ENDCAP_SQUARE,
+            CapStyle::Round => PS_ENDCAP_ROUND,
+        };
+        let join = match style.join_style {
+            JoinStyle::Miter => PS_JOIN_MITER,
+            JoinStyle::Bevel => PS_JOIN_BEVEL,
+            JoinStyle::Round => PS_JOIN_ROUND,
+        };
+        let line = match style.line_style {
+            LineStyle::Solid => PS_SOLID,
+            LineStyle::Dash => PS_DASH,
+            LineStyle::Dot => PS_DOT,
+            LineStyle::DashDot => PS_DASHDOT,
+            LineStyle::DashDotDot => PS_DASHDOTDOT,
+            LineStyle::Null => PS_NULL,
+        };
+        let brush = LOGBRUSH {
+            lbStyle: BS_SOLID,
+            lbColor: RGB(style.color.red, style.color.green, style.color.blue),
+            lbHatch: 0,
+        };
+        let pen = unsafe {
+            ExtCreatePen(
+                line | end_cap | join | PS_GEOMETRIC,
+                style.width,
+                &brush as *const LOGBRUSH as _,
+                0,
+                null_mut(),
+            )
+        };
+        Handle(pen as _)
+    }
+
+    fn new_brush(color: Color) -> Handle {
+        let color = RGB(color.red, color.green, color.blue);
+        Handle(unsafe { CreateSolidBrush(color) } as _)
+    }
+
+    fn new_font(style: &FontStyle) -> Handle {
+        let family = style
+            .font
+            .encode_utf16()
+            .chain(Some(0))
+            .collect::<Vec<u16>>();
+        let font = unsafe {
+            CreateFontW(
+                style.size,
+                0,
+                0,
+                0,
+                style.weight as i32,
+                style.italic as u32,
+                style.underline as u32,
+                style.strikeout as u32,
+                DEFAULT_CHARSET,
+                OUT_DEFAULT_PRECIS,
+                CLIP_DEFAULT_PRECIS,
+                DEFAULT_QUALITY,
+                DEFAULT_PITCH | FF_DONTCARE,
+                family.as_ptr(),
+            )
+        };
+        Handle(font as _)
+    }
+
+    fn text_extent(font: &FontStyle, text: &str) -> Size {
+        // loaded faces are drawn by the software renderer, which measures them too
+        if FontFace::find(font).is_some() {
+            return super::super::software::text_size(font, text);
+        }
+        let text = text.encode_utf16().collect::<Vec<u16>>();
+        let mut size = SIZE { cx: 0, cy: 0 };
+        unsafe {
+            let hdc = GetDC(null_mut());
+            let hfont = Self::new_font(font);
+            let old = SelectObject(hdc, hfont.0 as _);
+            GetTextExtentPoint32W(hdc, text.as_ptr(), text.len() as i32, &mut size);
+            SelectObject(hdc, old);
+            Self::delete_object(hfont);
+            ReleaseDC(null_mut(), hdc);
+        }
+        size!(size.cx, size.cy)
+    }
+
+    fn font_metrics(font: &FontStyle) -> FontMetrics {
+        if FontFace::find(font).is_some() {
+            return super::super::software::font_metrics(font);
+        }
+        let mut metrics: TEXTMETRICW = unsafe { std::mem::zeroed() };
+        unsafe {
+            let hdc = GetDC(null_mut());
+            let hfont = Self::new_font(font);
+            let old = SelectObject(hdc, hfont.0 as _);
+            GetTextMetricsW(hdc, &mut metrics);
+            SelectObject(hdc, old);
+            Self::delete_object(hfont);
+            ReleaseDC(null_mut(), hdc);
+        }
+        FontMetrics {
+            ascent: metrics.tmAscent,
+            descent: metrics.tmDescent,
+            line_height: metrics.tmHeight + metrics.tmExternalLeading,
+        }
+    }
+
+    fn delete_object(obj: Handle) {
+        unsafe {
+            DeleteObject(obj.0 as _);
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use std::{f32::consts::PI, os::raw::c_void, ptr::null_mut};
+use std::{
+    f32::consts::PI,
+    os::raw::c_void,
+    ptr::{null, null_mut},
+};
 
 use winapi::{
     shared::windef::RECT,
@@ -22,12 +26,6 @@ pub fn clear_device(hdc: *mut c_void, rect: Rect, color: Color) {
     }
 }
 
-pub fn delete_object(obj: *mut c_void) {
-    unsafe {
-        DeleteObject(obj as _);
-    }
-}
-
 pub fn select_object(hdc: *mut c_void, obj: *mut c_void) -> *mut c_void {
     unsafe { SelectObject(hdc as _, obj as _) as *mut c_void }
 }
@@ -39,79 +37,9 @@ pub fn set_current_text_color(hdc: *mut c_void, color: Color) -> Color {
     rgb!(GetRValue(ret), GetGValue(ret), GetBValue(ret))
 }
 
-pub fn new_pen_object(pen_style: PenStyle) -> *mut c_void {
-    let end_cap = match pen_style.cap_style {
-        CapStyle::Flat => PS_ENDCAP_FLAT,
-        CapStyle::Square => PS_ENDCAP_SQUARE,
-        CapStyle::Round => PS_ENDCAP_ROUND,
-    };
-    let join = match pen_style.join_style {
-        JoinStyle::Miter => PS_JOIN_MITER,
-        JoinStyle::Bevel => PS_JOIN_BEVEL,
-        JoinStyle::Round => PS_JOIN_ROUND,
-    };
-    let style = match pen_style.line_style {
-        LineStyle::Solid => PS_SOLID,
-        LineStyle::Dash => PS_DASH,
-        LineStyle::Dot => PS_DOT,
-        LineStyle::DashDot => PS_DASHDOT,
-        LineStyle::DashDotDot => PS_DASHDOTDOT,
-        LineStyle::Null => PS_NULL,
-    };
-    let brush = LOGBRUSH {
-        lbStyle: BS_SOLID,
-        lbColor: RGB(pen_style.color.red, pen_style.color.green, pen_style.color.blue),
-        lbHatch: 0,
-    };
-    unsafe {
-        ExtCreatePen(
-            style | end_cap | join | PS_GEOMETRIC,
-            pen_style.width,
-            &brush as *const LOGBRUSH as _,
-            0,
-            null_mut(),
-        ) as *mut c_void
-    }
-}
-
-pub fn new_font_object(style: FontStyle) -> *mut c_void {
-    let family = style
-        .font
-        .to_string()
-        .encode_utf16()
-        .chain(Some(0))
-        .collect::<Vec<u16>>();
-    unsafe {
-        CreateFontW(
-            style.size,
-            0,
-            0,
-            0,
-            style.weight as i32,
-            style.italic as u32,
-            style.underline as u32,
-            style.strikeout as u32,
-            DEFAULT_CHARSET,
-            OUT_DEFAULT_PRECIS,
-            CLIP_DEFAULT_PRECIS,
-            DEFAULT_QUALITY,
-            DEFAULT_PITCH | FF_DONTCARE,
-            family.as_ptr(),
-        ) as _
-    }
-}
-
-pub enum BrushParam {
-    Solid(Color),
-}
-
-pub fn new_brush_object(param: BrushParam) -> *mut c_void {
-    match param {
-        BrushParam::Solid(color) => {
-            let color = RGB(color.red, color.green, color.blue);
-            unsafe { CreateSolidBrush(color) as *mut c_void }
-        }
-    }
+pub fn current_text_color(hdc: *mut c_void) -> Color {
+    let color = unsafe { GetTextColor(hdc as _) };
+    rgb!(GetRValue(color), GetGValue(color), GetBValue(color))
 }
 
 pub fn draw_line(hdc: *mut c_void, x1: i32, y1: i32, x2: i32, y2: i32) {
@@ -313,67 +241,81 @@ pub fn draw_fill_circle(hdc: *mut c_void, pos: Point, radius: i32) {
     draw_fill_ellipse(hdc as _, rect);
 }
 
-pub fn draw_xy_text(hdc: *mut c_void, pos: Point, text: &str, align: TextAlign) {
-    let text = text
-        .to_string()
-        .encode_utf16()
-        .chain(Some(0))
-        .collect::<Vec<u16>>();
-    let (x, y) = (pos.x, pos.y);
-    let align = match align {
-        TextAlign::LeftTop => TA_LEFT | TA_TOP,
-        TextAlign::LeftBottom => TA_LEFT | TA_BOTTOM,
-        TextAlign::CenterTop => TA_CENTER | TA_TOP,
-        TextAlign::CenterBottom => TA_CENTER | TA_BOTTOM,
-        TextAlign::RightTop => TA_RIGHT | TA_TOP,
-        TextAlign::RightBottom => TA_RIGHT | TA_BOTTOM,
-        _ => {
-            panic!("Unsupported text align {:?}", align);
+pub fn draw_text_line(hdc: *mut c_void, pos: Point, text: &str, clip: Option<Rect>) {
+    let text = text.encode_utf16().collect::<Vec<u16>>();
+    let rect = clip.map(|rect| {
+        let (x, y, w, h) = rect.into();
+        RECT {
+            left: x,
+            top: y,
+            right: x + w,
+            bottom: y + h,
         }
+    });
+    // the text is already shaped and in visual order, so GDI must not reorder it
+    let (options, rect) = match &rect {
+        Some(rect) => (ETO_CLIPPED | ETO_IGNORELANGUAGE, rect as *const RECT),
+        None => (ETO_IGNORELANGUAGE, null()),
     };
     unsafe {
-        SetTextAlign(hdc as _, align);
-        TextOutW(
+        SetTextAlign(hdc as _, TA_LEFT | TA_TOP);
+        ExtTextOutW(
             hdc as _,
-            x,
-            y,
-            text.as_ptr() as _,
-            (text.len() - 1).try_into().unwrap(),
+            pos.x,
+            pos.y,
+            options,
+            rect,
+            text.as_ptr(),
+            text.len().try_into().unwrap(),
+            null(),
         );
     }
 }
 
-pub fn draw_rect_text(hdc: *mut c_void, rect: Rect, text: &str, align: TextAlign) {
-    let text = text
-        .to_string()
-        .encode_utf16()
-        .chain(Some(0))
-        .collect::<Vec<u16>>();
-    let (x, y, w, h) = rect.into();
-    let mut rect = RECT {
-        left: x,
-        top: y,
-        right: x + w,
-        bottom: y + h,
-    };
-    let align = match align {
-        TextAlign::LeftTop => DT_LEFT | DT_TOP,
-        TextAlign::LeftMiddle => DT_LEFT | DT_VCENTER,
-        TextAlign::LeftBottom => DT_LEFT | DT_BOTTOM,
-        TextAlign::CenterTop => DT_CENTER | DT_TOP,
-        TextAlign::Center => DT_CENTER | DT_VCENTER,
-        TextAlign::CenterBottom => DT_CENTER | DT_BOTTOM,
-        TextAlign::RightTop => DT_RIGHT | DT_TOP,
-        TextAlign::RightMiddle => DT_RIGHT | DT_VCENTER,
-        TextAlign::RightBottom => DT_RIGHT | DT_BOTTOM,
+/// Blend `image` on the device context with its top-left corner at `pos`.
+pub fn blend_image(hdc: *mut c_void, pos: Point, image: &Image) {
+    let (width, height) = (image.size().width, image.size().height);
+    if width <= 0 || height <= 0 {
+        return;
+    }
+    let mut info: BITMAPINFO = unsafe { std::mem::zeroed() };
+    info.bmiHeader = BITMAPINFOHEADER {
+        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width,
+        // a negative height makes a top-down bitmap, like the image
+        biHeight: -height,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB,
+        ..unsafe { std::mem::zeroed() }
     };
     unsafe {
-        DrawTextW(
-            hdc as _,
-            text.as_ptr() as _,
-            (text.len() - 1).try_into().unwrap(),
-            &mut rect,
-            align | DT_SINGLELINE,
-        );
+        let mem = CreateCompatibleDC(hdc as _);
+        let mut bits = null_mut();
+        let bitmap = CreateDIBSection(mem, &info, DIB_RGB_COLORS, &mut bits, null_mut(), 0);
+        if bitmap.is_null() {
+            DeleteDC(mem);
+            return;
+        }
+        // AlphaBlend wants premultiplied BGRA pixels
+        let pixels = std::slice::from_raw_parts_mut(bits as *mut u8, (width * height * 4) as usize);
+        for (dst, src) in pixels.chunks_exact_mut(4).zip(image.data().chunks_exact(4)) {
+            let alpha = src[3] as u32;
+            dst[0] = (src[2] as u32 * alpha / 255) as u8;
+            dst[1] = (src[1] as u32 * alpha / 255) as u8;
+            dst[2] = (src[0] as u32 * alpha / 255) as u8;
+            dst[3] = alpha as u8;
+        }
+        let old = SelectObject(mem, bitmap as _);
+        let blend = BLENDFUNCTION {
+            BlendOp: AC_SRC_OVER,
+            BlendFlags: 0,
+            SourceConstantAlpha: 255,
+            AlphaFormat: AC_SRC_ALPHA,
+        };
+        GdiAlphaBlend(hdc as _, pos.x, pos.y, width, height, mem, 0, 0, width, height, blend);
+        SelectObject(mem, old);
+        DeleteObject(bitmap as _);
+        DeleteDC(mem);
     }
 }
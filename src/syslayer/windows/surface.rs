@@ -0,0 +1,158 @@
+//! This file contains the `Surface` that draws on a device context with GDI.
+
+use std::cell::RefCell;
+
+use crate::*;
+
+use super::{gdi::*, Win32};
+
+/// The styles currently selected into a device context.
+struct Selected {
+    pen: PenStyle,
+    brush: Color,
+    font: FontStyle,
+}
+
+/// A `Surface` that draws on a device context with GDI.
+pub(crate) struct Device {
+    dc: Handle,
+    selected: RefCell<Selected>,
+}
+
+impl Device {
+    pub fn new(dc: Handle) -> Self {
+        Self {
+            dc,
+            selected: RefCell::new(Selected {
+                pen: PenStyle::default(),
+                brush: Color::WHITE,
+                font: FontStyle::default(),
+            }),
+        }
+    }
+}
+
+impl Surface for Device {
+    fn clear(&self, rect: Rect, color: Color) {
+        clear_device(self.dc.0, rect, color);
+    }
+
+    fn set_pen(&self, pen: &Pen) -> Pen {
+        Pen {
+            hpen: Handle(select_object(self.dc.0, pen.hpen.0)),
+            style: std::mem::replace(&mut self.selected.borrow_mut().pen, pen.style),
+        }
+    }
+
+    fn set_brush(&self, brush: &Brush) -> Brush {
+        Brush {
+            hbrush: Handle(select_object(self.dc.0, brush.hbrush.0)),
+            color: std::mem::replace(&mut self.selected.borrow_mut().brush, brush.color),
+        }
+    }
+
+    fn set_font(&self, font: &Font) -> Font {
+        Font {
+            hfont: Handle(select_object(self.dc.0, font.hfont.0)),
+            style: std::mem::replace(&mut self.selected.borrow_mut().font, font.style.clone()),
+        }
+    }
+
+    fn set_text_color(&self, color: Color) -> Color {
+        set_current_text_color(self.dc.0, color)
+    }
+
+    fn line(&self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        draw_line(self.dc.0, x1, y1, x2, y2);
+    }
+
+    fn rect(&self, rect: Rect) {
+        draw_rect(self.dc.0, rect);
+    }
+
+    fn round_rect(&self, rect: Rect, rx: i32, ry: i32) {
+        draw_round_rect(self.dc.0, rect, rx, ry);
+    }
+
+    fn polyline(&self, points: &[Point]) {
+        draw_polyline(self.dc.0, points);
+    }
+
+    fn polygon(&self, points: &[Point]) {
+        draw_polygon(self.dc.0, points);
+    }
+
+    fn arc(&self, rect: Rect, start: f32, sweep: f32) {
+        draw_arc(self.dc.0, rect, start, sweep);
+    }
+
+    fn pie(&self, rect: Rect, start: f32, sweep: f32) {
+        draw_pie(self.dc.0, rect, start, sweep);
+    }
+
+    fn ellipse(&self, rect: Rect) {
+        draw_ellipse(self.dc.0, rect);
+    }
+
+    fn circle(&self, pos: Point, radius: i32) {
+        draw_circle(self.dc.0, pos, radius);
+    }
+
+    fn fill_rect(&self, rect: Rect) {
+        draw_fill_rect(self.dc.0, rect);
+    }
+
+    fn fill_round_rect(&self, rect: Rect, rx: i32, ry: i32) {
+        draw_fill_round_rect(self.dc.0, rect, rx, ry);
+    }
+
+    fn fill_polygon(&self, points: &[Point]) {
+        draw_fill_polygon(self.dc.0, points);
+    }
+
+    fn fill_pie(&self, rect: Rect, start: f32, sweep: f32) {
+        draw_fill_pie(self.dc.0, rect, start, sweep);
+    }
+
+    fn fill_ellipse(&self, rect: Rect) {
+        draw_fill_ellipse(self.dc.0, rect);
+    }
+
+    fn fill_circle(&self, pos: Point, radius: i32) {
+        draw_fill_circle(self.dc.0, pos, radius);
+    }
+
+    fn text_line(&self, pos: Point, text: &str, clip: Option<Rect>) {
+        let font = self.selected.borrow().font.clone();
+        if FontFace::find(&font).is_none() {
+            draw_text_line(self.dc.0, pos, text, clip);
+            return;
+        }
+        // loaded faces are drawn by the software renderer, so they look the same everywhere
+        let size = self.text_extent(text);
+        // room for the glyphs that reach out of their box, like italic ones
+        let pad = size.height / 2;
+        let rect = rect!(pos.x - pad, pos.y, size.width + 2 * pad, size.height);
+        let Some(rect) = clip.map_or(Some(rect), |clip| rect & clip) else {
+            return;
+        };
+        let raster = Raster::new(rect.size, Color::TRANSPARENT);
+        raster.set_font(&Font {
+            hfont: Handle::NULL,
+            style: font,
+        });
+        raster.set_text_color(current_text_color(self.dc.0));
+        raster.text_line(pos - rect.pos, text, None);
+        if let Some(image) = Box::new(raster).into_image() {
+            blend_image(self.dc.0, rect.pos, &image);
+        }
+    }
+
+    fn text_extent(&self, text: &str) -> Size {
+        Win32::text_extent(&self.selected.borrow().font, text)
+    }
+
+    fn font_metrics(&self) -> FontMetrics {
+        Win32::font_metrics(&self.selected.borrow().font)
+    }
+}
@@ -1,9 +1,9 @@
 use std::any::Any;
-use std::{os::raw::c_void, sync::Mutex};
+use std::{cell::Cell, os::raw::c_void, sync::Mutex};
 use winapi::{
     shared::{
         minwindef::{BOOL, LPARAM, LRESULT, UINT, WPARAM},
-        windef::{HDC, HWND, RECT},
+        windef::{HDC, HWND, POINT, RECT},
     },
     um::{
         wingdi::{SetBkMode, TRANSPARENT},
@@ -13,25 +13,84 @@ use winapi::{
 
 use crate::*;
 
-use super::{get_rect, notifier_exit};
+use super::{keys::vk_to_key, Device, Win32};
 
 static mut WINDOW_COUNT: Mutex<u32> = Mutex::new(0);
 pub const USER_DEF_MSG: UINT = WM_USER + 1; //
 pub const WINDOW_CREATED_MSG: UINT = WM_USER + 2;
 
-macro_rules! wparam_to_mkey {
-    ($wparam:expr) => {
-        match $wparam {
-            MK_LBUTTON => ModifierKey::Mouse(MouseButton::Left),
-            MK_RBUTTON => ModifierKey::Mouse(MouseButton::Right),
-            MK_MBUTTON => ModifierKey::Mouse(MouseButton::Middle),
-            MK_XBUTTON1 => ModifierKey::Mouse(MouseButton::Other(0x01)),
-            MK_XBUTTON2 => ModifierKey::Mouse(MouseButton::Other(0x02)),
-            MK_CONTROL => ModifierKey::Ctrl,
-            MK_SHIFT => ModifierKey::Shift,
-            _ => ModifierKey::None,
+thread_local! {
+    /// The modifiers of the input event sent by `Win32::send_input`, which are reported instead
+    /// of the state of the keyboard and the mouse while it is delivered.
+    pub(super) static SIMULATED_MODIFIERS: Cell<Option<Modifiers>> = const { Cell::new(None) };
+}
+
+/// Check if the key `vk` was held down when the current message was sent.
+unsafe fn is_key_down(vk: i32) -> bool {
+    GetKeyState(vk) < 0
+}
+
+/// Get the modifier keys and the mouse buttons held down from the `MK_*` flags of a mouse
+/// message. Alt and the Windows keys have no flag, so they are read from the keyboard state.
+unsafe fn wparam_to_modifiers(wparam: WPARAM) -> Modifiers {
+    if let Some(mods) = SIMULATED_MODIFIERS.with(Cell::get) {
+        return mods;
+    }
+    let flags = [
+        (MK_SHIFT, Modifiers::SHIFT),
+        (MK_CONTROL, Modifiers::CTRL),
+        (MK_LBUTTON, Modifiers::LEFT_BUTTON),
+        (MK_RBUTTON, Modifiers::RIGHT_BUTTON),
+        (MK_MBUTTON, Modifiers::MIDDLE_BUTTON),
+        (MK_XBUTTON1, Modifiers::X1_BUTTON),
+        (MK_XBUTTON2, Modifiers::X2_BUTTON),
+    ];
+    let mut mods = Modifiers::NONE;
+    for (flag, modifier) in flags {
+        mods.set(modifier, wparam & flag != 0);
+    }
+    mods.set(Modifiers::ALT, is_key_down(VK_MENU));
+    mods.set(Modifiers::META, is_key_down(VK_LWIN) || is_key_down(VK_RWIN));
+    mods
+}
+
+/// Get the number of lines scrolled by a notch of the wheel as set in the system, with
+/// `SPI_GETWHEELSCROLLLINES`, or of chars for the horizontal wheel, with
+/// `SPI_GETWHEELSCROLLCHARS`.
+unsafe fn wheel_lines(action: UINT) -> f32 {
+    let mut lines: UINT = 3;
+    SystemParametersInfoW(action, 0, &mut lines as *mut UINT as _, 0);
+    // scrolling by pages is not told apart, so a notch scrolls the default lines instead
+    match lines {
+        WHEEL_PAGESCROLL => 3.0,
+        lines => lines as f32,
+    }
+}
+
+/// Get the modifier keys and the mouse buttons held down when the current key message was sent.
+unsafe fn key_modifiers() -> Modifiers {
+    if let Some(mods) = SIMULATED_MODIFIERS.with(Cell::get) {
+        return mods;
+    }
+    let keys = [
+        (VK_SHIFT, Modifiers::SHIFT),
+        (VK_CONTROL, Modifiers::CTRL),
+        (VK_MENU, Modifiers::ALT),
+        (VK_LWIN, Modifiers::META),
+        (VK_RWIN, Modifiers::META),
+        (VK_LBUTTON, Modifiers::LEFT_BUTTON),
+        (VK_RBUTTON, Modifiers::RIGHT_BUTTON),
+        (VK_MBUTTON, Modifiers::MIDDLE_BUTTON),
+        (VK_XBUTTON1, Modifiers::X1_BUTTON),
+        (VK_XBUTTON2, Modifiers::X2_BUTTON),
+    ];
+    let mut mods = Modifiers::NONE;
+    for (vk, modifier) in keys {
+        if is_key_down(vk) {
+            mods.insert(modifier);
         }
-    };
+    }
+    mods
 }
 
 macro_rules! lparam_to_pos {
@@ -77,23 +136,26 @@ pub(super) unsafe extern "system" fn winproc(
     }
     // get window object
     let object_ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut (Box<dyn Ele>, bool);
+    if msg == WM_DESTROY {
+        // the window was counted in WM_CREATE even if its widget has been released
+        if let Some((obj, _)) = object_ptr.as_mut() {
+            obj.on_event(&Event::WindowDestroyed);
+        }
+        {
+            let mut window_count = WINDOW_COUNT.lock().unwrap();
+            *window_count -= 1;
+            if *window_count == 0 {
+                Win32::exit(0);
+            }
+        }
+        return 0;
+    }
     let (obj, hover) = if object_ptr.is_null() {
         return DefWindowProcW(hwnd, msg, wparam, lparam);
     } else {
         object_ptr.as_mut().unwrap()
     };
     match msg {
-        WM_DESTROY => {
-            obj.on_event(&Event::WindowDestroyed);
-            {
-                let mut window_count = WINDOW_COUNT.lock().unwrap();
-                *window_count -= 1;
-                if *window_count == 0 {
-                    notifier_exit(0);
-                }
-            }
-            return 0;
-        }
         WM_PAINT => {
             let mut ps = PAINTSTRUCT {
                 hdc: 0 as HDC,
@@ -111,8 +173,8 @@ pub(super) unsafe extern "system" fn winproc(
             let hdc = BeginPaint(hwnd, &mut ps);
             SetBkMode(hdc, TRANSPARENT as _);
             let mut canvas = Canvas {
-                hdc: hdc as _,
-                rect: get_rect(hwnd as _),
+                surface: Box::new(Device::new(Handle(hdc as _))),
+                rect: Win32::window_rect(Handle(hwnd as _)),
             };
             obj.draw(&mut canvas);
             EndPaint(hwnd, &ps);
@@ -121,14 +183,14 @@ pub(super) unsafe extern "system" fn winproc(
         WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN | WM_XBUTTONDOWN | WM_LBUTTONUP
         | WM_RBUTTONUP | WM_MBUTTONUP | WM_XBUTTONUP | WM_LBUTTONDBLCLK | WM_RBUTTONDBLCLK
         | WM_MBUTTONDBLCLK | WM_XBUTTONDBLCLK => {
-            handle_mouse_event(obj, msg, wparam, lparam);
+            handle_mouse_event(hwnd, msg, wparam, lparam);
             return 0;
         }
         WM_MOUSEMOVE => {
             let pos = lparam_to_pos!(lparam);
-            let mk = wparam_to_mkey!(wparam & 0xffff);
-            let event = Event::MouseMoved { pos, mk };
-            obj.on_event(&event);
+            let mods = wparam_to_modifiers(wparam & 0xffff);
+            let event = Event::MouseMoved { pos, mods };
+            deliver(Handle(hwnd as _), &event);
             let mut tme = TRACKMOUSEEVENT {
                 cbSize: size_of::<TRACKMOUSEEVENT>() as u32,
                 dwFlags: TME_HOVER | TME_LEAVE,
@@ -138,20 +200,26 @@ pub(super) unsafe extern "system" fn winproc(
             TrackMouseEvent(&mut tme);
             return 0;
         }
-        WM_MOUSEWHEEL => {
-            let pos = lparam_to_pos!(lparam);
-            let mk = wparam_to_mkey!(wparam & 0xffff);
-            let delta = (wparam >> 16) as i16;
-            let event = Event::MouseWheelScrolled {
-                wheel: if delta > 0 {
-                    MouseWheel::Up
-                } else {
-                    MouseWheel::Down
-                },
-                pos,
-                mk,
+        WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
+            // the wheel messages tell the position on the screen
+            let mut point = POINT {
+                x: (lparam & 0xffff) as i16 as i32,
+                y: ((lparam >> 16) & 0xffff) as i16 as i32,
             };
-            obj.on_event(&event);
+            ScreenToClient(hwnd, &mut point);
+            let pos = pos!(point.x, point.y);
+            let mods = wparam_to_modifiers(wparam & 0xffff);
+            // touchpads and precise wheels send fractions of a notch
+            let notches = (wparam >> 16) as i16 as f32 / WHEEL_DELTA as f32;
+            // the wheel turned away from the user scrolls up, and tilted right scrolls right
+            let delta = match msg {
+                WM_MOUSEWHEEL => {
+                    ScrollDelta::lines(0.0, -notches * wheel_lines(SPI_GETWHEELSCROLLLINES))
+                }
+                _ => ScrollDelta::lines(notches * wheel_lines(SPI_GETWHEELSCROLLCHARS), 0.0),
+            };
+            let event = Event::MouseWheelScrolled { delta, pos, mods };
+            deliver(Handle(hwnd as _), &event);
             return 0;
         }
         WM_SIZE => {
@@ -164,6 +232,9 @@ pub(super) unsafe extern "system" fn winproc(
                 SIZE_MAXSHOW => WindowSize::MaxShow,
                 _ => WindowSize::Resize,
             };
+            if ty != WindowSize::Minimize {
+                obj.as_window().resized(size);
+            }
             let event = Event::WindowResized { size, ty };
             obj.on_event(&event);
             return 0;
@@ -175,13 +246,13 @@ pub(super) unsafe extern "system" fn winproc(
             return 0;
         }
         WM_KEYDOWN | WM_KEYUP | WM_SYSKEYDOWN | WM_SYSKEYUP => {
-            handle_key_event(obj, msg, wparam);
+            handle_key_event(hwnd, msg, wparam);
             return 0;
         }
         WM_CHAR => {
             let ch: char = std::char::from_u32(wparam as u32).unwrap();
             let event = Event::Input { ch };
-            obj.on_event(&event);
+            deliver(Handle(hwnd as _), &event);
             return 0;
         }
         WM_HOTKEY => {
@@ -199,6 +270,14 @@ pub(super) unsafe extern "system" fn winproc(
             obj.on_event(&event);
             return 0;
         }
+        WM_SETFOCUS => {
+            obj.on_event(&Event::FocusIn);
+            return 0;
+        }
+        WM_KILLFOCUS => {
+            obj.on_event(&Event::FocusOut);
+            return 0;
+        }
         WM_ENABLE => {
             let enable = wparam != 0;
             let event = if enable {
@@ -226,8 +305,8 @@ pub(super) unsafe extern "system" fn winproc(
         WM_MOUSEHOVER => {
             if !*hover {
                 let pos = lparam_to_pos!(lparam);
-                let mk = wparam_to_mkey!(wparam);
-                let event = Event::Hover { pos, mk };
+                let mods = wparam_to_modifiers(wparam & 0xffff);
+                let event = Event::Hover { pos, mods };
                 obj.on_event(&event);
                 *hover = true;
             }
@@ -266,7 +345,7 @@ pub(super) unsafe extern "system" fn winproc(
     DefWindowProcW(hwnd, msg, wparam, lparam)
 }
 
-unsafe fn handle_mouse_event(obj: &mut Box<dyn Ele>, msg: UINT, wparam: WPARAM, lparam: LPARAM) {
+unsafe fn handle_mouse_event(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARAM) {
     let button = match msg {
         WM_LBUTTONDOWN | WM_LBUTTONUP | WM_LBUTTONDBLCLK => MouseButton::Left,
         WM_RBUTTONDOWN | WM_RBUTTONUP | WM_RBUTTONDBLCLK => MouseButton::Right,
@@ -283,33 +362,46 @@ unsafe fn handle_mouse_event(obj: &mut Box<dyn Ele>, msg: UINT, wparam: WPARAM,
         _ => return,
     };
     let pos = lparam_to_pos!(lparam);
-    let mk = wparam_to_mkey!(wparam);
+    // the high word of the extension button messages tells the button
+    let mods = wparam_to_modifiers(wparam & 0xffff);
     let event = match msg {
         WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN | WM_XBUTTONDOWN => {
-            Event::MouseButtonPressed { button, pos, mk }
+            Event::MouseButtonPressed { button, pos, mods }
         }
         WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP | WM_XBUTTONUP => {
-            Event::MouseButtonReleased { button, pos, mk }
+            Event::MouseButtonReleased { button, pos, mods }
         }
         WM_LBUTTONDBLCLK | WM_RBUTTONDBLCLK | WM_MBUTTONDBLCLK | WM_XBUTTONDBLCLK => {
-            Event::MouseDoubleClicked { button, pos, mk }
+            Event::MouseDoubleClicked { button, pos, mods }
         }
         _ => return,
     };
-    obj.on_event(&event);
+    deliver(Handle(hwnd as _), &event);
 }
 
-unsafe fn handle_key_event(obj: &mut Box<dyn Ele>, msg: UINT, wparam: WPARAM) {
+unsafe fn handle_key_event(hwnd: HWND, msg: UINT, wparam: WPARAM) {
     let vk = wparam as i32;
     let key = vk_to_key(vk);
+    let mods = key_modifiers();
     let event = match msg {
-        WM_KEYDOWN => Event::KeyPressed { key, sys: false },
-        WM_KEYUP => Event::KeyReleased { key, sys: false },
-        WM_SYSKEYDOWN => Event::KeyPressed { key, sys: true },
-        WM_SYSKEYUP => Event::KeyReleased { key, sys: true },
+        WM_KEYDOWN => Event::KeyPressed { key, sys: false, mods },
+        WM_KEYUP => Event::KeyReleased { key, sys: false, mods },
+        WM_SYSKEYDOWN => Event::KeyPressed { key, sys: true, mods },
+        WM_SYSKEYUP => Event::KeyReleased { key, sys: true, mods },
         _ => return,
     };
-    obj.on_event(&event);
+    deliver(Handle(hwnd as _), &event);
+}
+
+/// Collect the direct children of a window, because `EnumChildWindows` visits all descendants.
+pub unsafe extern "system" fn collect_children_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let (parent, children) = (lparam as *mut (*mut c_void, Vec<*mut c_void>))
+        .as_mut()
+        .unwrap();
+    if GetParent(hwnd) as *mut c_void == *parent {
+        children.push(hwnd as _);
+    }
+    1
 }
 
 pub unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
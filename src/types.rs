@@ -16,6 +16,15 @@ pub struct Rect {
     pub size: Size,
 }
 
+/// The space kept free on each side of a rectangle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Margins {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
 /// A macro to create a `Point` struct.
 #[macro_export]
 macro_rules! pos {
@@ -244,6 +253,33 @@ impl Rect {
     pub fn bottom_right(&self) -> Point {
         pos!(self.pos.x + self.size.width, self.pos.y + self.size.height,)
     }
+
+    /// Return the rectangle left inside after removing the margins.
+    /// The size never becomes negative.
+    pub fn inset(&self, margins: Margins) -> Rect {
+        rect!(
+            self.pos.x + margins.left,
+            self.pos.y + margins.top,
+            (self.size.width - margins.left - margins.right).max(0),
+            (self.size.height - margins.top - margins.bottom).max(0),
+        )
+    }
+}
+
+impl Margins {
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// Create margins that are the same on every side.
+    pub fn uniform(margin: i32) -> Self {
+        Self::new(margin, margin, margin, margin)
+    }
 }
 
 use std::ops::{BitAnd, BitOr};
@@ -309,6 +345,7 @@ impl BitOr for Rect {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<(i32, i32, i32, i32)> for Rect {
     fn into(self) -> (i32, i32, i32, i32) {
         (self.pos.x, self.pos.y, self.size.width, self.size.height)
@@ -321,6 +358,7 @@ impl From<(i32, i32, i32, i32)> for Rect {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<(i32, i32)> for Size {
     fn into(self) -> (i32, i32) {
         (self.width, self.height)
@@ -333,6 +371,7 @@ impl From<(i32, i32)> for Size {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<(i32, i32)> for Point {
     fn into(self) -> (i32, i32) {
         (self.x, self.y)
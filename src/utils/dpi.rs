@@ -1,11 +1,11 @@
 //! This file provides support for DPI.
 
-use crate::get_dpi_scale;
+use crate::{Backend, Platform};
 
 /// Calculate the platform-independent pixel value from the given value.
 /// It will return current screen's scale factor relative to 96 DPI.
 pub fn px(x: f32) -> i32 {
-    (x * get_dpi_scale()) as i32
+    (x * Platform::dpi_scale()) as i32
 }
 
 /// Calculate the platform-independent pixel value from the given em value.
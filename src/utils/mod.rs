@@ -1,5 +1,9 @@
 mod notifier;
 mod dpi;
+mod snapshot;
+mod undo;
 
 pub use notifier::{Notifier, Responder};
-pub use dpi::*;
\ No newline at end of file
+pub use dpi::*;
+pub use snapshot::*;
+pub use undo::{Command, UndoStack};
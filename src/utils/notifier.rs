@@ -10,6 +10,7 @@ use std::{
 /// A wrapper of callback function.
 /// The generic parameter `T` is the type of data passed to the callback function.
 /// It will be passed as an immutable reference to the callback function.
+#[allow(clippy::type_complexity)]
 pub struct Responder<T> {
     f: Rc<RefCell<dyn FnMut(&T)>>,
 }
@@ -28,6 +29,13 @@ impl<T> DerefMut for Responder<T> {
     }
 }
 
+/// The clone calls the same function.
+impl<T> Clone for Responder<T> {
+    fn clone(&self) -> Self {
+        Self { f: self.f.clone() }
+    }
+}
+
 impl<T> Responder<T> {
     /// Create a new `Responder` instance with the given function.
     pub fn new<F: FnMut(&T) + 'static>(f: F) -> Self {
@@ -46,6 +54,7 @@ pub struct Notifier<T> {
 
 impl<T> Notifier<T> {
     /// Create a new `Notifier` instance.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             responders: HashMap::new(),
@@ -0,0 +1,187 @@
+//! This file provides golden-image testing of widgets.
+//! A widget is driven with events, rendered offscreen and compared with a reference PNG.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::*;
+
+/// The environment variable that makes `Snapshot` write the rendered images as the new references.
+pub const BLESS_ENV: &str = "RUSTY_GUI_BLESS";
+
+/// The reason why an image does not match its reference.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// There is no reference image yet. The rendered image is written to `actual`.
+    Missing { reference: PathBuf, actual: PathBuf },
+    /// The image and the reference have different sizes.
+    SizeMismatch { expected: Size, actual: Size },
+    /// Some pixels differ by more than the tolerance. The differences are highlighted in `diff`.
+    Mismatch { pixels: usize, diff: PathBuf },
+    /// The reference could not be read, or the output could not be written.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing { reference, actual } => write!(
+                f,
+                "reference {} does not exist, the rendered image is saved to {} (set {}=1 to accept it)",
+                reference.display(),
+                actual.display(),
+                BLESS_ENV,
+            ),
+            Self::SizeMismatch { expected, actual } => {
+                write!(f, "expected an image of {:?}, but got {:?}", expected, actual)
+            }
+            Self::Mismatch { pixels, diff } => write!(
+                f,
+                "{} pixels differ from the reference, see {}",
+                pixels,
+                diff.display(),
+            ),
+            Self::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Compares rendered images with the reference PNG files stored in a directory.
+///
+/// For a snapshot named `name`, the reference is `<dir>/<name>.png`.
+/// When the comparison fails, the rendered image is saved as `<dir>/<name>.actual.png`,
+/// and the differing pixels are drawn in red in `<dir>/<name>.diff.png`.
+/// If the environment variable `RUSTY_GUI_BLESS` is set, the references are overwritten instead.
+/// # Example
+/// ```no_run
+/// use rusty_gui::*;
+///
+/// let form = Block::create(rect!(100, 100, 400, 300), None);
+/// let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+/// Snapshot::new("tests/snapshots")
+///     .tolerance(2)
+///     .assert_widget("button_pressed", &mut button, &[Event::MouseButtonPressed {
+///         button: MouseButton::Left,
+///         pos: pos!(10, 10),
+///         mods: Modifiers::NONE,
+///     }]);
+/// ```
+pub struct Snapshot {
+    dir: PathBuf,
+    tolerance: u8,
+}
+
+impl Snapshot {
+    /// Create a `Snapshot` that stores references in `dir`.
+    /// The tolerance is 0, so every channel of every pixel must be equal.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            tolerance: 0,
+        }
+    }
+
+    /// Set the largest difference allowed between a channel of a pixel and the reference.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn path(&self, name: &str, suffix: &str) -> PathBuf {
+        self.dir.join(format!("{}{}.png", name, suffix))
+    }
+
+    /// Compare `image` with the reference named `name`.
+    pub fn check(&self, name: &str, image: &Image) -> Result<(), SnapshotError> {
+        let reference = self.path(name, "");
+        let actual = self.path(name, ".actual");
+        let diff = self.path(name, ".diff");
+        if std::env::var_os(BLESS_ENV).is_some() {
+            std::fs::create_dir_all(&self.dir)?;
+            image.save_png(&reference)?;
+            return Ok(());
+        }
+        if !reference.exists() {
+            std::fs::create_dir_all(&self.dir)?;
+            image.save_png(&actual)?;
+            return Err(SnapshotError::Missing { reference, actual });
+        }
+        let expected = Image::load_png(&reference)?;
+        if expected.size() != image.size() {
+            image.save_png(&actual)?;
+            return Err(SnapshotError::SizeMismatch {
+                expected: expected.size(),
+                actual: image.size(),
+            });
+        }
+        let (pixels, diff_image) = self.compare(&expected, image);
+        if pixels > 0 {
+            image.save_png(&actual)?;
+            diff_image.save_png(&diff)?;
+            return Err(SnapshotError::Mismatch { pixels, diff });
+        }
+        // the outputs of an earlier failure are stale now
+        let _ = std::fs::remove_file(actual);
+        let _ = std::fs::remove_file(diff);
+        Ok(())
+    }
+
+    /// Count the pixels that differ by more than the tolerance and draw them in a diff image.
+    /// Matching pixels are drawn as a faded gray copy of the reference.
+    fn compare(&self, expected: &Image, actual: &Image) -> (usize, Image) {
+        let mut diff = Image::new(expected.size(), Color::WHITE);
+        let mut pixels = 0;
+        let size = expected.size();
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let pos = pos!(x, y);
+                let (e, a) = (expected.pixel(pos).unwrap(), actual.pixel(pos).unwrap());
+                let channels = [
+                    (e.red, a.red),
+                    (e.green, a.green),
+                    (e.blue, a.blue),
+                    (e.alpha, a.alpha),
+                ];
+                if channels.iter().any(|(e, a)| e.abs_diff(*a) > self.tolerance) {
+                    pixels += 1;
+                    diff.set_pixel(pos, Color::RED);
+                } else {
+                    let gray = (e.red as u32 + e.green as u32 + e.blue as u32) / 3;
+                    diff.set_pixel(pos, rgb!((gray / 4 + 191) as u8));
+                }
+            }
+        }
+        (pixels, diff)
+    }
+
+    /// Compare `image` with the reference named `name`.
+    /// # Panics
+    /// If the image does not match the reference.
+    pub fn assert(&self, name: &str, image: &Image) {
+        if let Err(err) = self.check(name, image) {
+            panic!("snapshot `{}` failed: {}", name, err);
+        }
+    }
+
+    /// Send `events` to the widget, render it at the size of its window and compare the result
+    /// with the reference named `name`.
+    /// # Panics
+    /// If the image does not match the reference.
+    pub fn assert_widget<T: Ele>(&self, name: &str, widget: &mut Widget<T>, events: &[Event]) {
+        for event in events {
+            widget.on_event(event);
+        }
+        let size = widget.as_window().rect().size;
+        self.assert(name, &widget.render_to_image(size));
+    }
+}
@@ -0,0 +1,193 @@
+//! This file contains the implementation of the `UndoStack` struct and the `Command` trait.
+
+use super::Notifier;
+
+/// An edit that can be undone and done again.
+/// The `Target` is the data the edit changes, such as the text of an editor.
+pub trait Command {
+    type Target: ?Sized;
+
+    /// Revert the edit on `target`.
+    fn undo(&mut self, target: &mut Self::Target);
+
+    /// Apply the edit on `target` again after it was undone.
+    fn redo(&mut self, target: &mut Self::Target);
+
+    /// Absorb `next`, the edit made right after this one, so that both are undone in one step.
+    /// Return false if they can not be merged, which is the default.
+    fn merge(&mut self, next: &Self) -> bool {
+        let _ = next;
+        false
+    }
+}
+
+/// A history of commands that can be undone and redone in order.
+/// Commands are recorded with `push` after they are applied, or applied and recorded with
+/// `execute`. A new command clears the commands that were undone before it, and it is merged
+/// into the last one if `Command::merge` accepts it, until `seal` is called.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// struct Add(i32);
+///
+/// impl Command for Add {
+///     type Target = i32;
+///     fn undo(&mut self, total: &mut i32) {
+///         *total -= self.0;
+///     }
+///     fn redo(&mut self, total: &mut i32) {
+///         *total += self.0;
+///     }
+/// }
+///
+/// let mut total = 0;
+/// let mut stack = UndoStack::new();
+/// stack.execute(Add(3), &mut total);
+/// stack.execute(Add(4), &mut total);
+/// stack.undo(&mut total);
+/// assert_eq!(total, 3);
+/// stack.redo(&mut total);
+/// assert_eq!(total, 7);
+/// ```
+pub struct UndoStack<C: Command> {
+    commands: Vec<C>,
+    /// The number of commands that are applied.
+    index: usize,
+    limit: Option<usize>,
+    sealed: bool,
+    /// Notified with the new value of `can_undo` when it changes.
+    pub can_undo_changed: Notifier<bool>,
+    /// Notified with the new value of `can_redo` when it changes.
+    pub can_redo_changed: Notifier<bool>,
+}
+
+impl<C: Command> Default for UndoStack<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Command> UndoStack<C> {
+    /// Create an empty stack without a limit.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            index: 0,
+            limit: None,
+            sealed: true,
+            can_undo_changed: Notifier::new(),
+            can_redo_changed: Notifier::new(),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.index > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.index < self.commands.len()
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Set the largest number of commands kept. The oldest ones are dropped first.
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        let state = self.state();
+        self.limit = limit;
+        self.trim();
+        self.notify(state);
+    }
+
+    /// Record `command`, which is already applied.
+    pub fn push(&mut self, command: C) {
+        let state = self.state();
+        let redone = self.commands.len() > self.index;
+        self.commands.truncate(self.index);
+        if !self.sealed && !redone {
+            if let Some(last) = self.commands.last_mut() {
+                if last.merge(&command) {
+                    return;
+                }
+            }
+        }
+        self.commands.push(command);
+        self.index = self.commands.len();
+        self.sealed = false;
+        self.trim();
+        self.notify(state);
+    }
+
+    /// Apply `command` to `target` and record it.
+    pub fn execute(&mut self, mut command: C, target: &mut C::Target) {
+        command.redo(target);
+        self.push(command);
+    }
+
+    /// Undo the last applied command and return it.
+    /// Return None if there is nothing to undo.
+    pub fn undo(&mut self, target: &mut C::Target) -> Option<&C> {
+        if !self.can_undo() {
+            return None;
+        }
+        let state = self.state();
+        self.index -= 1;
+        self.sealed = true;
+        self.commands[self.index].undo(target);
+        self.notify(state);
+        Some(&self.commands[self.index])
+    }
+
+    /// Apply the last undone command again and return it.
+    /// Return None if there is nothing to redo.
+    pub fn redo(&mut self, target: &mut C::Target) -> Option<&C> {
+        if !self.can_redo() {
+            return None;
+        }
+        let state = self.state();
+        self.index += 1;
+        self.sealed = true;
+        self.commands[self.index - 1].redo(target);
+        self.notify(state);
+        Some(&self.commands[self.index - 1])
+    }
+
+    /// Keep the next command from being merged into the last one.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Forget all the commands.
+    pub fn clear(&mut self) {
+        let state = self.state();
+        self.commands.clear();
+        self.index = 0;
+        self.sealed = true;
+        self.notify(state);
+    }
+
+    fn trim(&mut self) {
+        if let Some(limit) = self.limit {
+            let extra = self.commands.len().saturating_sub(limit);
+            let extra = extra.min(self.index);
+            self.commands.drain(..extra);
+            self.index -= extra;
+        }
+    }
+
+    fn state(&self) -> (bool, bool) {
+        (self.can_undo(), self.can_redo())
+    }
+
+    fn notify(&mut self, (could_undo, could_redo): (bool, bool)) {
+        let (can_undo, can_redo) = self.state();
+        if can_undo != could_undo {
+            self.can_undo_changed.notify(&can_undo);
+        }
+        if can_redo != could_redo {
+            self.can_redo_changed.notify(&can_redo);
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub struct Block {
 
 impl Block {
     /// Create a new instance of `Block` struct.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             this: Window::default(),
@@ -30,7 +31,8 @@ impl Drawable for Block {
 }
 
 impl EventListener for Block {
-    fn on_event(&mut self, event: &crate::Event) {
+    fn on_event(&mut self, event: &crate::Event) -> EventResult {
         let _ = event;
+        EventResult::Ignored
     }
 }
@@ -0,0 +1,37 @@
+//! This file contains the helpers shared by the button widgets.
+
+use crate::*;
+
+/// The space between the label and the edges of a button.
+const PADDING: Size = size!(12, 6);
+/// The space between the dotted focus frame and the edges of a button.
+const FOCUS_INSET: i32 = 4;
+
+/// Get the font of the label of a button.
+pub(crate) fn label_font() -> FontStyle {
+    FontStyle {
+        size: 24,
+        ..Default::default()
+    }
+}
+
+/// Draw the dotted frame inside `rect` that shows a button has the focus.
+pub(crate) fn draw_focus_frame(canvas: &mut Canvas, rect: Rect) {
+    let focus_pen = Pen::new(PenStyle {
+        line_style: LineStyle::Dot,
+        color: Color::DARK_GRAY,
+        ..Default::default()
+    });
+    canvas.set_pen(&focus_pen);
+    canvas.rect(rect.inset(Margins::uniform(FOCUS_INSET)));
+}
+
+/// Get the size of a button showing `label`: the label with some padding around it.
+pub(crate) fn size_hint(label: &str) -> Size {
+    Platform::text_extent(&label_font(), label) + PADDING + PADDING
+}
+
+/// Get the smallest size of a button showing `label`: the label without padding.
+pub(crate) fn minimum_size_hint(label: &str) -> Size {
+    Platform::text_extent(&label_font(), label)
+}
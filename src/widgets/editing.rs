@@ -0,0 +1,180 @@
+//! This file contains the helpers shared by the text editing widgets.
+
+use crate::*;
+
+/// The color of the frame of a text editing widget that has the focus.
+pub(crate) const FOCUS_COLOR: Color = rgb!(0, 120, 215);
+
+/// Get the position of the char before `pos` in `text`, or 0 at the start of it.
+pub(crate) fn prev_char(text: &str, pos: usize) -> usize {
+    text[..pos].char_indices().next_back().map_or(0, |(i, _)| i)
+}
+
+/// Get the position after the char at `pos` in `text`, or `pos` at the end of it.
+pub(crate) fn next_char(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .chars()
+        .next()
+        .map_or(pos, |c| pos + c.len_utf8())
+}
+
+/// Move `pos` into `text` and back onto a char boundary.
+pub(crate) fn floor_char(text: &str, pos: usize) -> usize {
+    let mut pos = pos.min(text.len());
+    while !text.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    pos
+}
+
+/// A change of the text of an editing widget, kept in its undo history.
+pub(crate) struct TextChange {
+    pub start: usize,
+    pub removed: String,
+    pub inserted: String,
+    /// The anchor and the caret before the change.
+    pub selection: (usize, usize),
+    /// If the change is a char typed by the user, which is merged with the next typed chars.
+    pub typing: bool,
+}
+
+impl TextChange {
+    /// Get where the caret is after the change is applied.
+    pub fn end(&self) -> usize {
+        self.start + self.inserted.len()
+    }
+}
+
+impl Command for TextChange {
+    type Target = String;
+
+    fn undo(&mut self, text: &mut String) {
+        text.replace_range(self.start..self.end(), &self.removed);
+    }
+
+    fn redo(&mut self, text: &mut String) {
+        text.replace_range(self.start..self.start + self.removed.len(), &self.inserted);
+    }
+
+    fn merge(&mut self, next: &Self) -> bool {
+        let typing = self.typing && next.typing && next.removed.is_empty();
+        if typing && next.start == self.end() {
+            self.inserted.push_str(&next.inserted);
+            return true;
+        }
+        false
+    }
+}
+
+/// What the text editing widgets do in their own way, for the editing methods they share.
+pub(crate) trait Editor {
+    /// Check if the user can change the text.
+    fn editable(&self) -> bool;
+
+    /// Check if the selected text can be put on the clipboard.
+    fn copyable(&self) -> bool;
+
+    /// Get the part of the text on the clipboard that is pasted, with the line breaks the widget
+    /// keeps.
+    fn pasted(text: String) -> String;
+
+    /// Tell the widget that its content changed, and select the text from `anchor` to `caret`.
+    fn changed(&mut self, anchor: usize, caret: usize);
+}
+
+/// Add the undo history and clipboard methods to a text editing widget.
+/// The widget implements `Editor`, and has the fields `content: String` and
+/// `history: UndoStack<TextChange>`, the fields `anchor` and `caret` of its selection,
+/// and an `insert` method that puts text in place of the selection.
+macro_rules! editing_methods {
+    ($name:ident) => {
+        impl $name {
+            pub fn can_undo(&self) -> bool {
+                self.history.can_undo()
+            }
+
+            pub fn can_redo(&self) -> bool {
+                self.history.can_redo()
+            }
+
+            /// Get the notifier told whether anything can be undone when it changes.
+            pub fn can_undo_changed(&mut self) -> &mut Notifier<bool> {
+                &mut self.history.can_undo_changed
+            }
+
+            /// Get the notifier told whether anything can be redone when it changes.
+            pub fn can_redo_changed(&mut self) -> &mut Notifier<bool> {
+                &mut self.history.can_redo_changed
+            }
+
+            /// Revert the last change of the text, selecting what it replaced.
+            /// Chars typed one after another are undone together.
+            /// Nothing is undone in read-only mode.
+            pub fn undo(&mut self) {
+                if !self.editable() {
+                    return;
+                }
+                let change = self.history.undo(&mut self.content);
+                if let Some((anchor, caret)) = change.map(|change| change.selection) {
+                    self.changed(anchor, caret);
+                }
+            }
+
+            /// Apply the last undone change of the text again.
+            /// Nothing is redone in read-only mode.
+            pub fn redo(&mut self) {
+                if !self.editable() {
+                    return;
+                }
+                if let Some(caret) = self.history.redo(&mut self.content).map(TextChange::end) {
+                    self.changed(caret, caret);
+                }
+            }
+
+            /// Put the selected text on the clipboard. Nothing is copied in password mode.
+            pub fn copy(&self) {
+                let selected = self.selected_text();
+                if self.copyable() && !selected.is_empty() {
+                    Clipboard::set_text(selected);
+                }
+            }
+
+            /// Move the selected text to the clipboard.
+            /// Nothing is cut in password mode or read-only mode.
+            pub fn cut(&mut self) {
+                let (start, end) = self.selection();
+                if self.copyable() && self.editable() && start < end {
+                    self.copy();
+                    self.replace(start, end, "", false);
+                }
+            }
+
+            /// Put the text on the clipboard in place of the selection.
+            /// A `LineEdit` only takes its first line, as much of it as the maximum length
+            /// allows.
+            pub fn paste(&mut self) {
+                if let Some(text) = Clipboard::get_text() {
+                    self.insert(&Self::pasted(text), false);
+                }
+            }
+
+            /// Put `text` in place of the content from `start` to `end`, and record it in
+            /// the history. `typing` tells if it is typed by the user.
+            fn replace(&mut self, start: usize, end: usize, text: &str, typing: bool) {
+                let change = TextChange {
+                    start,
+                    removed: String::from(&self.content[start..end]),
+                    inserted: String::from(text),
+                    selection: (self.anchor, self.caret),
+                    typing,
+                };
+                self.content.replace_range(start..end, text);
+                let caret = change.end();
+                self.history.push(change);
+                self.changed(caret, caret);
+            }
+        }
+    };
+}
+
+pub(crate) use editing_methods;
@@ -1,19 +1,60 @@
 use crate::*;
 
+use super::editing::{
+    editing_methods, floor_char, next_char, prev_char, Editor, TextChange, FOCUS_COLOR,
+};
+
 const BORDER_WIDTH: i32 = 5;
+/// The char shown in place of every char of a password.
+const MASK: char = '*';
 
-/// This is a simple line edit widget that allows the user to input text.
-/// It without cursor or selection, and user can only append or delete characters at the end of the text.
-/// And it can't scroll even if the text is too long to fit in the widget.
+/// This is a single line edit widget that allows the user to input text.
+/// It has a caret that can be moved with Left, Right, Home and End, or placed with the mouse.
+/// Holding Shift while moving the caret, or dragging the mouse, selects text, Ctrl+A selects
+/// all of it, and typing replaces the selection.
+/// Ctrl+Z and Ctrl+Y undo and redo changes, and Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste.
+/// If the text is too long to fit in the widget, it scrolls to keep the caret in sight.
+/// Right-to-left text is shaped and shown in visual order, and Left and Right move the caret
+/// to the left and to the right on the screen, whichever way the text goes.
+///
+/// Positions in the text, such as the caret and the selection, are byte offsets into
+/// the content, always on a char boundary.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let form = Block::create(rect!(50, 50, 400, 300), None);
+/// let mut password = LineEdit::create("Password", rect!(10, 10, 200, 40), form.as_window());
+/// password.set_password(true);
+/// password.set_max_length(Some(8));
+/// password.set_text("correct horse");
+/// assert_eq!(password.text(), "correct ");
+/// ```
 pub struct LineEdit {
     this: Window,
     content: String,
     placeholder: String,
+    caret: usize,
+    /// The end of the selection which does not move with the caret.
+    anchor: usize,
+    /// How far the text is scrolled to the left, in pixels. It is always at a caret stop.
+    scroll: i32,
+    max_length: Option<usize>,
+    read_only: bool,
+    password: bool,
+    /// The modifier keys held down, as told by the last input event.
+    mods: Modifiers,
+    dragging: bool,
+    history: UndoStack<TextChange>,
     pub content_changed: Notifier<String>,
     pub enter: Notifier<String>,
 
     border_pen: Pen,
     frame_pen: Pen,
+    focus_pen: Pen,
+    caret_pen: Pen,
+    selection_pen: Pen,
+    selection_brush: Brush,
     text_color: Color,
     placeholder_color: Color,
     pub text: Font,
@@ -26,57 +67,114 @@ impl Drawable for LineEdit {
         canvas.clear(Color::WHITE);
         let rect = self.this.rect();
 
-        let text_rect = {
-            let size = rect.size - size!(2 * BORDER_WIDTH, 2 * BORDER_WIDTH);
-            rect.center_rect(size)
-        };
+        let text_rect = self.text_rect();
+        let carets = self.carets();
         canvas.set_font(&self.text);
         if self.content.is_empty() {
             canvas.set_text_color(self.placeholder_color);
             canvas.rect_text(text_rect, &self.placeholder, TextAlign::LeftMiddle);
         } else {
+            let (start, end) = self.selection();
+            // a selection across runs of both directions is shown in several parts
+            for (left, right) in carets.spans(start, end) {
+                let selected = rect!(
+                    text_rect.left() - self.scroll + left,
+                    text_rect.top(),
+                    right - left,
+                    text_rect.size.height
+                );
+                if let Some(selected) = selected & text_rect {
+                    canvas.set_pen(&self.selection_pen);
+                    canvas.set_brush(&self.selection_brush);
+                    canvas.fill_rect(selected);
+                }
+            }
             canvas.set_text_color(self.text_color);
-            canvas.rect_text(text_rect, &self.content, TextAlign::LeftMiddle);
+            canvas.scrolled_text(text_rect, self.scroll, &self.shown());
+        }
+        let focused = self.this.has_focus();
+        if focused {
+            let x = text_rect.left() - self.scroll + carets.x_of(self.caret);
+            canvas.set_pen(&self.caret_pen);
+            canvas.line(x, text_rect.top(), x, text_rect.bottom());
         }
         canvas.set_pen(&self.border_pen);
         canvas.rect(rect);
-        canvas.set_pen(&self.frame_pen);
+        canvas.set_pen(match focused {
+            true => &self.focus_pen,
+            false => &self.frame_pen,
+        });
         canvas.rect(rect);
     }
 }
 
 impl EventListener for LineEdit {
-    fn on_event(&mut self, event: &Event) {
-        if let Event::MouseButtonPressed { button, .. } = event {
-            if *button == MouseButton::Left {
-                self.this.set_focus();
-            }
-            self.this.update();
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        if let Some(mods) = event.modifiers() {
+            self.mods = mods;
         }
-        if let Event::Input { ch: char } = event {
-            match char {
-                '\x08' => {
-                    if !self.content.is_empty() {
-                        self.content.pop();
-                    }
+        let shift = self.mods.shift();
+        // AltGr is told as Ctrl+Alt and types chars such as @ or ą on many layouts, so with it
+        // held down the keys are text rather than shortcuts
+        let ctrl = self.mods.ctrl() && !self.mods.alt();
+        match event {
+            Event::MouseButtonPressed {
+                button: MouseButton::Left,
+                pos,
+                ..
+            } => {
+                self.move_caret(self.pos_at(*pos), shift);
+                self.dragging = true;
+            }
+            Event::MouseMoved { pos, .. } if self.dragging => {
+                self.move_caret(self.pos_at(*pos), true);
+            }
+            Event::MouseButtonReleased {
+                button: MouseButton::Left,
+                ..
+            } => self.dragging = false,
+            Event::KeyPressed { key, .. } => match key {
+                KeyCode::Alpha('A') if ctrl => self.select_all(),
+                KeyCode::Alpha('C') if ctrl => self.copy(),
+                KeyCode::Alpha('X') if ctrl => self.cut(),
+                KeyCode::Alpha('V') if ctrl => self.paste(),
+                KeyCode::Alpha('Z') if ctrl && !shift => self.undo(),
+                KeyCode::Alpha('Z') | KeyCode::Alpha('Y') if ctrl => self.redo(),
+                KeyCode::Left | KeyCode::Right => {
+                    let right = *key == KeyCode::Right;
+                    let carets = self.carets();
+                    let (start, end) = self.selection();
+                    let pos = match shift || start == end {
+                        true => carets.step(self.caret, right),
+                        // the selection collapses to its side in the direction of the key
+                        false => match (carets.x_of(start) <= carets.x_of(end)) == right {
+                            true => end,
+                            false => start,
+                        },
+                    };
+                    self.move_caret(pos, shift);
                 }
+                KeyCode::Home => self.move_caret(0, shift),
+                KeyCode::End => self.move_caret(self.content.len(), shift),
+                KeyCode::Delete => self.delete(true),
+                _ => return EventResult::Ignored,
+            },
+            // with Ctrl held down, the keys are shortcuts rather than text
+            Event::Input { .. } if ctrl => return EventResult::Ignored,
+            Event::Input { ch: char } => match char {
+                '\x08' => self.delete(false),
                 '\r' | '\n' => {
                     let content = self.content.clone();
-                    println!("Enter pressed");
                     self.enter.notify(&content);
-                    return;
-                }
-                '\t' => {
-                    self.content.push(' ');
                 }
-                _ => {
-                    self.content.push(*char);
-                }
-            }
-            let content = self.content.clone();
-            self.content_changed.notify(&content);
-            self.this.update();
+                // Tab moves the focus to the next window
+                ch if ch.is_control() => return EventResult::Ignored,
+                ch => self.insert(ch.encode_utf8(&mut [0; 4]), true),
+            },
+            Event::FocusIn | Event::FocusOut => self.this.update(),
+            _ => return EventResult::Ignored,
         }
+        EventResult::Handled
     }
 }
 
@@ -86,11 +184,21 @@ impl LineEdit {
             width: 2,
             ..Default::default()
         };
+        let focus_pen_style = PenStyle {
+            width: 2,
+            color: FOCUS_COLOR,
+            ..Default::default()
+        };
         let border_pen_style = PenStyle {
             width: BORDER_WIDTH as u32,
             color: Color::WHITE,
             ..Default::default()
         };
+        let selection_color = rgb!(173, 214, 255);
+        let selection_pen_style = PenStyle {
+            color: selection_color,
+            ..Default::default()
+        };
         let text_font = FontStyle {
             size: 24,
             ..Default::default()
@@ -99,17 +207,242 @@ impl LineEdit {
             this: Window::default(),
             content: String::new(),
             placeholder: String::from(placeholder),
+            caret: 0,
+            anchor: 0,
+            scroll: 0,
+            max_length: None,
+            read_only: false,
+            password: false,
+            mods: Modifiers::NONE,
+            dragging: false,
+            history: UndoStack::new(),
             content_changed: Notifier::new(),
             enter: Notifier::new(),
 
             frame_pen: Pen::new(frame_pen_style),
+            focus_pen: Pen::new(focus_pen_style),
             border_pen: Pen::new(border_pen_style),
+            caret_pen: Pen::new(PenStyle::default()),
+            selection_pen: Pen::new(selection_pen_style),
+            selection_brush: Brush::new(selection_color),
             text_color: Color::BLACK,
             placeholder_color: Color::GRAY,
             text: Font::new(text_font),
         }
     }
     pub fn create(placeholder: &str, rect: Rect, parent: &Window) -> Widget<LineEdit> {
-        Widget::new("LineEdit", rect, Some(parent), Self::new(placeholder))
+        let mut widget = Widget::new("LineEdit", rect, Some(parent), Self::new(placeholder));
+        widget.as_window_mut().set_focus_policy(FocusPolicy::Strong);
+        widget
+    }
+
+    pub fn text(&self) -> &str {
+        &self.content
+    }
+
+    /// Replace the content, cut to the maximum length. The caret is put at the end of it,
+    /// and the undo history is cleared. It works in read-only mode too.
+    pub fn set_text(&mut self, text: &str) {
+        self.content = String::from(text);
+        if let Some(max) = self.max_length {
+            let end = self.content.char_indices().nth(max).map(|(i, _)| i);
+            self.content.truncate(end.unwrap_or(self.content.len()));
+        }
+        self.scroll = 0;
+        self.history.clear();
+        self.modified();
+        self.move_caret(self.content.len(), false);
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Move the caret to `pos` and clear the selection.
+    /// A position out of the text is moved to the nearest char boundary before it.
+    pub fn set_caret(&mut self, pos: usize) {
+        self.set_selection(pos, pos);
+    }
+
+    /// Get the start and the end of the selection. They are equal if nothing is selected.
+    pub fn selection(&self) -> (usize, usize) {
+        (self.caret.min(self.anchor), self.caret.max(self.anchor))
+    }
+
+    /// Select the text from `anchor` to `caret`, leaving the caret at `caret`.
+    pub fn set_selection(&mut self, anchor: usize, caret: usize) {
+        self.anchor = floor_char(&self.content, anchor);
+        self.move_caret(floor_char(&self.content, caret), true);
+    }
+
+    pub fn select_all(&mut self) {
+        self.set_selection(0, self.content.len());
+    }
+
+    pub fn selected_text(&self) -> &str {
+        let (start, end) = self.selection();
+        &self.content[start..end]
+    }
+
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Set the largest number of chars the user can type. The current text is cut to it.
+    pub fn set_max_length(&mut self, max: Option<usize>) {
+        self.max_length = max;
+        if max.is_some_and(|max| self.content.chars().count() > max) {
+            let content = self.content.clone();
+            self.set_text(&content);
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Set whether the user can change the text.
+    /// The caret can still be moved and the text selected in read-only mode.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_password(&self) -> bool {
+        self.password
+    }
+
+    /// Set whether every char of the text is shown as `*`.
+    pub fn set_password(&mut self, password: bool) {
+        self.password = password;
+        self.scroll_to_caret();
+        self.this.update();
+    }
+
+    fn text_rect(&self) -> Rect {
+        let rect = self.this.rect();
+        let size = rect.size - size!(2 * BORDER_WIDTH, 2 * BORDER_WIDTH);
+        rect.center_rect(size)
+    }
+
+    /// Get the text shown for the content.
+    fn shown(&self) -> String {
+        match self.password {
+            true => self.content.chars().map(|_| MASK).collect(),
+            false => self.content.clone(),
+        }
+    }
+
+    /// Get where the positions of the content are shown, from the left of the text.
+    /// A password is masked before it is measured, and is never reordered.
+    fn carets(&self) -> CaretMap {
+        CaretMap::new(&self.content, !self.password, |text| {
+            let width = match self.password {
+                true => self.text.measure(&MASK.to_string().repeat(text.chars().count())),
+                false => self.text.measure(text),
+            };
+            width.width
+        })
+    }
+
+    /// Get the text position under the point `pos` of the widget.
+    fn pos_at(&self, pos: Point) -> usize {
+        let x = pos.x - self.text_rect().left() + self.scroll;
+        self.carets().pos_at(x)
+    }
+
+    /// Scroll the text so that the caret is visible, and no more of the widget than needed
+    /// is left empty at the end of it.
+    fn scroll_to_caret(&mut self) {
+        let width = self.text_rect().size.width;
+        let carets = self.carets();
+        let caret = carets.x_of(self.caret);
+        self.scroll = self.scroll.min(caret);
+        if caret - self.scroll > width {
+            self.scroll = carets.edges().find(|x| caret - x <= width).unwrap_or(caret);
+        }
+        let end = carets.width();
+        let fit = carets.edges().find(|x| end - x <= width);
+        self.scroll = self.scroll.min(fit.unwrap_or(0));
+    }
+
+    /// Move the caret, extending the selection if `select` is true.
+    /// Chars typed after it are not merged with the ones typed before in the undo history.
+    fn move_caret(&mut self, pos: usize, select: bool) {
+        self.history.seal();
+        self.place_caret(pos, select);
+    }
+
+    fn place_caret(&mut self, pos: usize, select: bool) {
+        self.caret = pos;
+        if !select {
+            self.anchor = pos;
+        }
+        self.scroll_to_caret();
+        self.this.update();
+    }
+
+    /// Put `text` in place of the selection, as much of it as the maximum length allows.
+    /// `typing` tells if it is typed by the user.
+    fn insert(&mut self, text: &str, typing: bool) {
+        if self.read_only {
+            return;
+        }
+        let (start, end) = self.selection();
+        let text = match self.max_length {
+            Some(max) => {
+                let kept = self.content.chars().count() - self.content[start..end].chars().count();
+                let room = max.saturating_sub(kept);
+                let end = text.char_indices().nth(room).map(|(i, _)| i);
+                &text[..end.unwrap_or(text.len())]
+            }
+            None => text,
+        };
+        if text.is_empty() && start == end {
+            return;
+        }
+        self.replace(start, end, text, typing);
+    }
+
+    /// Delete the selection, or the char before the caret if `forward` is false,
+    /// or the char after it if `forward` is true.
+    fn delete(&mut self, forward: bool) {
+        let (mut start, mut end) = self.selection();
+        if start == end {
+            if forward {
+                end = next_char(&self.content, end);
+            } else {
+                start = prev_char(&self.content, start);
+            }
+        }
+        if !self.read_only && start < end {
+            self.replace(start, end, "", false);
+        }
+    }
+
+    fn modified(&mut self) {
+        let content = self.content.clone();
+        self.content_changed.notify(&content);
+    }
+}
+
+editing_methods!(LineEdit);
+
+impl Editor for LineEdit {
+    fn editable(&self) -> bool {
+        !self.read_only
+    }
+
+    fn copyable(&self) -> bool {
+        !self.password
+    }
+
+    fn pasted(text: String) -> String {
+        String::from(text.lines().next().unwrap_or_default())
+    }
+
+    fn changed(&mut self, anchor: usize, caret: usize) {
+        self.anchor = anchor;
+        self.modified();
+        self.place_caret(caret, true);
     }
 }
@@ -1,9 +1,13 @@
 mod block;
+mod button;
+mod editing;
 mod pushbutton;
 mod switch;
 mod lineedit;
+mod textedit;
 
 pub use block::Block;
 pub use pushbutton::PushButton;
 pub use switch::Switch;
-pub use lineedit::LineEdit;
\ No newline at end of file
+pub use lineedit::LineEdit;
+pub use textedit::TextEdit;
\ No newline at end of file
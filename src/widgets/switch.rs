@@ -1,6 +1,9 @@
 use crate::*;
 
+use super::button::{self, label_font};
+
 /// demo widget
+/// It is toggled with the mouse, or with Space when it has the focus.
 pub struct Switch {
     this: Window,
     label: String,
@@ -20,7 +23,11 @@ impl Switch {
         }
     }
     pub fn create(label: &str, rect: Rect, parent: &Window) -> Widget<Self> {
-        Widget::new("Switch", rect, Some(parent), Self::new(label))
+        let mut widget = Widget::new("Switch", rect, Some(parent), Self::new(label));
+        let this = widget.as_window_mut();
+        this.set_size_policy(SizePolicy::Preferred, SizePolicy::Fixed);
+        this.set_focus_policy(FocusPolicy::Strong);
+        widget
     }
 }
 
@@ -34,11 +41,7 @@ impl Drawable for Switch {
         } else {
             self.backcolor
         });
-        let fs = FontStyle {
-            size: 24,
-            ..Default::default()
-        };
-        let fo = Font::new(fs);
+        let fo = Font::new(label_font());
         canvas.set_font(&fo);
         let text_rect = if self.status {
             Rect {
@@ -49,6 +52,9 @@ impl Drawable for Switch {
             rect
         };
         canvas.rect_text(text_rect, &self.label, TextAlign::Center);
+        if self.this.has_focus() {
+            button::draw_focus_frame(canvas, rect);
+        }
         if self.status {
             return;
         }
@@ -61,23 +67,40 @@ impl Drawable for Switch {
         canvas.line(rect.right(), rect.top(), rect.right(), rect.bottom());
         canvas.line(rect.left(), rect.bottom(), rect.right(), rect.bottom());
     }
+
+    fn size_hint(&self) -> Option<Size> {
+        Some(button::size_hint(&self.label))
+    }
+
+    fn minimum_size_hint(&self) -> Option<Size> {
+        Some(button::minimum_size_hint(&self.label))
+    }
 }
 
 impl EventListener for Switch {
-    fn on_event(&mut self, event: &Event) {
+    fn on_event(&mut self, event: &Event) -> EventResult {
         match event {
             Event::MouseButtonPressed {
                 button,
                 pos: _,
-                mk: _,
+                mods: _,
             } => {
-                if *button == MouseButton::Left {
-                    self.status = !self.status;
-                    self.state_changed.notify(&self.status);
-                    self.this.update();
+                if *button != MouseButton::Left {
+                    return EventResult::Ignored;
                 }
+                self.status = !self.status;
+                self.state_changed.notify(&self.status);
+                self.this.update();
+            }
+            Event::KeyPressed {
+                key: KeyCode::Space,
+                ..
+            } => {
+                self.status = !self.status;
+                self.state_changed.notify(&self.status);
+                self.this.update();
             }
-            Event::Hover { pos: _, mk: _ } => {
+            Event::Hover { pos: _, mods: _ } => {
                 self.backcolor = rgb!(215);
                 self.this.update();
             }
@@ -85,7 +108,11 @@ impl EventListener for Switch {
                 self.backcolor = rgb!(235);
                 self.this.update();
             }
-            _ => {}
+            Event::FocusIn | Event::FocusOut => self.this.update(),
+            // the space has been handled as a key
+            Event::Input { ch: ' ' } => {}
+            _ => return EventResult::Ignored,
         }
+        EventResult::Handled
     }
 }
@@ -0,0 +1,545 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::*;
+
+use super::editing::{
+    editing_methods, floor_char, next_char, prev_char, Editor, TextChange, FOCUS_COLOR,
+};
+
+/// The space between the frame and the text.
+const PADDING: i32 = 5;
+
+/// The lines of the content, with the wrapping width and the font they were made for.
+type WrappedLines = (Option<i32>, FontStyle, Rc<[(usize, usize)]>);
+
+/// A multi-line text editor.
+/// It has a caret that can be moved with the arrow keys, Home, End, PageUp and PageDown,
+/// or placed with the mouse. Holding Shift while moving the caret, or dragging the mouse,
+/// selects text, and typing replaces the selection.
+/// Ctrl+Z and Ctrl+Y undo and redo changes, and Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste.
+/// Long lines are wrapped at the edge of the widget unless word wrap is turned off,
+/// and the content scrolls vertically to keep the caret in sight.
+/// Tab types a space, so Ctrl+Tab and Ctrl+Shift+Tab move the focus out of the editor.
+/// Left and Right move the caret to the left and to the right on the screen, also in
+/// right-to-left text, and go on to the next or the previous line at the ends of a line.
+///
+/// Positions in the text, such as the caret and the selection, are byte offsets into
+/// the content, always on a char boundary.
+/// # Example
+/// ```
+/// use rusty_gui::*;
+///
+/// let form = Block::create(rect!(50, 50, 400, 300), None);
+/// let mut edit = TextEdit::create(rect!(10, 10, 380, 280), form.as_window());
+/// edit.set_text("Hello\nworld");
+/// edit.set_selection(6, 11);
+/// assert_eq!(edit.selected_text(), "world");
+/// ```
+pub struct TextEdit {
+    this: Window,
+    content: String,
+    caret: usize,
+    /// The end of the selection which does not move with the caret.
+    anchor: usize,
+    /// The x position kept by the caret while it moves up and down.
+    goal_x: Option<i32>,
+    /// The first visible line.
+    scroll: usize,
+    /// The fraction of a line scrolled by the wheel and not shown yet.
+    wheel: f32,
+    word_wrap: bool,
+    /// The lines of the content, made again when the content changes or when they are asked
+    /// for another width or font.
+    wrapped: RefCell<Option<WrappedLines>>,
+    /// The modifier keys held down, as told by the last input event.
+    mods: Modifiers,
+    dragging: bool,
+    history: UndoStack<TextChange>,
+    pub text_changed: Notifier<String>,
+    /// Notified with the start and the end of the selection when it or the caret moves.
+    pub selection_changed: Notifier<(usize, usize)>,
+
+    frame_pen: Pen,
+    focus_pen: Pen,
+    caret_pen: Pen,
+    selection_pen: Pen,
+    selection_brush: Brush,
+    text_color: Color,
+    pub font: Font,
+}
+
+default_as_window!(TextEdit);
+
+impl TextEdit {
+    pub fn new() -> Self {
+        let frame_pen_style = PenStyle {
+            width: 2,
+            ..Default::default()
+        };
+        let focus_pen_style = PenStyle {
+            width: 2,
+            color: FOCUS_COLOR,
+            ..Default::default()
+        };
+        let selection_color = rgb!(173, 214, 255);
+        let selection_pen_style = PenStyle {
+            color: selection_color,
+            ..Default::default()
+        };
+        let text_font = FontStyle {
+            size: 24,
+            ..Default::default()
+        };
+        Self {
+            this: Window::default(),
+            content: String::new(),
+            caret: 0,
+            anchor: 0,
+            goal_x: None,
+            scroll: 0,
+            wheel: 0.0,
+            word_wrap: true,
+            wrapped: RefCell::new(None),
+            mods: Modifiers::NONE,
+            dragging: false,
+            history: UndoStack::new(),
+            text_changed: Notifier::new(),
+            selection_changed: Notifier::new(),
+
+            frame_pen: Pen::new(frame_pen_style),
+            focus_pen: Pen::new(focus_pen_style),
+            caret_pen: Pen::new(PenStyle::default()),
+            selection_pen: Pen::new(selection_pen_style),
+            selection_brush: Brush::new(selection_color),
+            text_color: Color::BLACK,
+            font: Font::new(text_font),
+        }
+    }
+
+    pub fn create(rect: Rect, parent: &Window) -> Widget<TextEdit> {
+        let mut widget = Widget::new("TextEdit", rect, Some(parent), Self::new());
+        let this = widget.as_window_mut();
+        this.set_size_policy(SizePolicy::Expanding, SizePolicy::Expanding);
+        this.set_focus_policy(FocusPolicy::Strong);
+        widget
+    }
+
+    pub fn text(&self) -> &str {
+        &self.content
+    }
+
+    /// Replace the content. The caret is put at the end of it, and the undo history is cleared.
+    pub fn set_text(&mut self, text: &str) {
+        let selection = self.selection();
+        self.content = String::from(text);
+        self.history.clear();
+        self.caret = self.content.len();
+        self.anchor = self.caret;
+        self.text_modified();
+        self.caret_moved(selection);
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// Move the caret to `pos` and clear the selection.
+    /// A position out of the text is moved to the nearest char boundary before it.
+    pub fn set_caret(&mut self, pos: usize) {
+        self.set_selection(pos, pos);
+    }
+
+    /// Get the start and the end of the selection. They are equal if nothing is selected.
+    pub fn selection(&self) -> (usize, usize) {
+        (self.caret.min(self.anchor), self.caret.max(self.anchor))
+    }
+
+    /// Select the text from `anchor` to `caret`, leaving the caret at `caret`.
+    pub fn set_selection(&mut self, anchor: usize, caret: usize) {
+        let selection = self.selection();
+        self.history.seal();
+        self.anchor = floor_char(&self.content, anchor);
+        self.caret = floor_char(&self.content, caret);
+        self.caret_moved(selection);
+    }
+
+    pub fn selected_text(&self) -> &str {
+        let (start, end) = self.selection();
+        &self.content[start..end]
+    }
+
+    pub fn word_wrap(&self) -> bool {
+        self.word_wrap
+    }
+
+    /// Set whether lines longer than the widget are wrapped.
+    /// Without word wrap, the part of a line beyond the right edge is not shown.
+    pub fn set_word_wrap(&mut self, wrap: bool) {
+        self.word_wrap = wrap;
+        self.scroll_to_caret();
+        self.this.update();
+    }
+
+    /// Get the number of lines, after wrapping.
+    pub fn line_count(&self) -> usize {
+        self.lines().len()
+    }
+
+    /// Get the index of the first visible line.
+    pub fn first_visible_line(&self) -> usize {
+        self.scroll
+    }
+
+    /// Scroll the content so that `line` is the first visible line, as far as possible.
+    pub fn scroll_to_line(&mut self, line: usize) {
+        self.scroll = line.min(self.max_scroll());
+        self.this.update();
+    }
+
+    fn text_rect(&self) -> Rect {
+        self.this.rect().inset(Margins::uniform(PADDING))
+    }
+
+    fn width_of(&self, text: &str) -> i32 {
+        self.font.measure(text).width
+    }
+
+    fn line_height(&self) -> i32 {
+        self.font.metrics().line_height.max(1)
+    }
+
+    /// Get the number of lines that fit in the widget.
+    fn visible_lines(&self) -> usize {
+        (self.text_rect().size.height / self.line_height()).max(1) as usize
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.line_count().saturating_sub(self.visible_lines())
+    }
+
+    /// Split the content into the lines shown on the screen.
+    /// Each line is a range of the content without its line break. A line wrapped at a space
+    /// leaves the space out, so that the caret before it is at the end of the line.
+    fn lines(&self) -> Rc<[(usize, usize)]> {
+        let width = self.word_wrap.then(|| self.text_rect().size.width);
+        let mut wrapped = self.wrapped.borrow_mut();
+        match &*wrapped {
+            Some((made_for, font, lines)) if *made_for == width && font == self.font.style() => {
+                lines.clone()
+            }
+            _ => {
+                let lines: Rc<[_]> = wrap_lines(&self.content, width, |start, end| {
+                    self.width_of(&self.content[start..end])
+                })
+                .into();
+                *wrapped = Some((width, self.font.style().clone(), lines.clone()));
+                lines
+            }
+        }
+    }
+
+    /// Get the line showing the text position `pos`.
+    fn line_of(lines: &[(usize, usize)], pos: usize) -> usize {
+        lines
+            .iter()
+            .rposition(|&(start, _)| start <= pos)
+            .unwrap_or(0)
+    }
+
+    /// Get where the positions of `line` are shown, from the left of the line.
+    /// The positions of the map start at the start of the line.
+    fn carets(&self, line: (usize, usize)) -> CaretMap {
+        let (start, end) = line;
+        CaretMap::new(&self.content[start..end], true, |text| self.width_of(text))
+    }
+
+    /// Get the text position in `line` closest to `x` pixels from the left of the line.
+    fn pos_in_line(&self, line: (usize, usize), x: i32) -> usize {
+        line.0 + self.carets(line).pos_at(x)
+    }
+
+    /// Get the text position under the point `pos` of the widget.
+    fn pos_at(&self, pos: Point) -> usize {
+        let rect = self.text_rect();
+        let lines = self.lines();
+        let row = (pos.y - rect.top()).max(0) / self.line_height();
+        let line = (self.scroll + row as usize).min(lines.len() - 1);
+        self.pos_in_line(lines[line], pos.x - rect.left())
+    }
+
+    /// Get the line showing the text position `pos`, and the x position of the caret before it.
+    fn place_of(&self, lines: &[(usize, usize)], pos: usize) -> (usize, i32) {
+        let line = Self::line_of(lines, pos);
+        (line, self.carets(lines[line]).x_of(pos - lines[line].0))
+    }
+
+    /// Get the position one step to the left of the caret, or to the right if `right` is true.
+    /// The caret moves in the order the line is shown, and past the end or the start of the
+    /// line it goes on to the next or the previous line.
+    fn step(&self, right: bool) -> usize {
+        let lines = self.lines();
+        let (start, end) = lines[Self::line_of(&lines, self.caret)];
+        let pos = start + self.carets((start, end)).step(self.caret - start, right);
+        match pos == self.caret {
+            true if self.caret == end => next_char(&self.content, self.caret),
+            true if self.caret == start => prev_char(&self.content, self.caret),
+            _ => pos,
+        }
+    }
+
+    fn scroll_to_caret(&mut self) {
+        let lines = self.lines();
+        let (line, _) = self.place_of(&lines, self.caret);
+        let visible = self.visible_lines();
+        if line < self.scroll {
+            self.scroll = line;
+        } else if line >= self.scroll + visible {
+            self.scroll = line + 1 - visible;
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Move the caret, extending the selection if `select` is true.
+    fn move_caret(&mut self, pos: usize, select: bool) {
+        let selection = self.selection();
+        self.history.seal();
+        self.caret = pos;
+        if !select {
+            self.anchor = pos;
+        }
+        self.caret_moved(selection);
+    }
+
+    /// Move the caret `count` lines up or down, keeping its x position.
+    fn move_lines(&mut self, count: isize, select: bool) {
+        let lines = self.lines();
+        let (line, x) = self.place_of(&lines, self.caret);
+        let x = *self.goal_x.get_or_insert(x);
+        let target = line.saturating_add_signed(count).min(lines.len() - 1);
+        let pos = if count < 0 && line == 0 {
+            0
+        } else if count > 0 && line == lines.len() - 1 {
+            self.content.len()
+        } else {
+            self.pos_in_line(lines[target], x)
+        };
+        self.move_caret(pos, select);
+        self.goal_x = Some(x);
+    }
+
+    /// Put `text` in place of the selection. `typing` tells if it is typed by the user.
+    fn insert(&mut self, text: &str, typing: bool) {
+        let (start, end) = self.selection();
+        self.replace(start, end, text, typing);
+    }
+
+    /// Delete the selection, or the char before the caret if `forward` is false,
+    /// or the char after it if `forward` is true.
+    fn delete(&mut self, forward: bool) {
+        let (mut start, mut end) = self.selection();
+        if start == end {
+            if forward {
+                end = next_char(&self.content, end);
+            } else {
+                start = prev_char(&self.content, start);
+            }
+        }
+        if start < end {
+            self.replace(start, end, "", false);
+        }
+    }
+
+    fn text_modified(&mut self) {
+        self.wrapped.take();
+        let content = self.content.clone();
+        self.text_changed.notify(&content);
+    }
+
+    fn caret_moved(&mut self, old_selection: (usize, usize)) {
+        self.goal_x = None;
+        self.scroll_to_caret();
+        let selection = self.selection();
+        if selection != old_selection {
+            self.selection_changed.notify(&selection);
+        }
+        self.this.update();
+    }
+}
+
+editing_methods!(TextEdit);
+
+impl Editor for TextEdit {
+    fn editable(&self) -> bool {
+        true
+    }
+
+    fn copyable(&self) -> bool {
+        true
+    }
+
+    fn pasted(text: String) -> String {
+        text.replace("\r\n", "\n")
+    }
+
+    fn changed(&mut self, anchor: usize, caret: usize) {
+        let selection = self.selection();
+        (self.anchor, self.caret) = (anchor, caret);
+        self.text_modified();
+        self.caret_moved(selection);
+    }
+}
+
+impl Default for TextEdit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drawable for TextEdit {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.clear(Color::WHITE);
+        let rect = self.this.rect();
+        let text_rect = self.text_rect();
+        let line_height = self.line_height();
+        let lines = self.lines();
+        let (sel_start, sel_end) = self.selection();
+        let caret_line = Self::line_of(&lines, self.caret);
+        let focused = self.this.has_focus();
+
+        canvas.set_font(&self.font);
+        canvas.set_text_color(self.text_color);
+        for (row, &(start, end)) in lines.iter().enumerate().skip(self.scroll) {
+            let top = text_rect.top() + (row - self.scroll) as i32 * line_height;
+            if top >= text_rect.bottom() {
+                break;
+            }
+            let height = line_height.min(text_rect.bottom() - top);
+            let line_rect = rect!(text_rect.left(), top, text_rect.size.width, height);
+            let carets = self.carets((start, end));
+            let from = sel_start.max(start);
+            let to = sel_end.min(end).max(from);
+            // a selection across runs of both directions is shown in several parts
+            for (left, right) in carets.spans(from - start, to - start) {
+                let selected = rect!(line_rect.left() + left, top, right - left, height);
+                if let Some(selected) = selected & line_rect {
+                    canvas.set_pen(&self.selection_pen);
+                    canvas.set_brush(&self.selection_brush);
+                    canvas.fill_rect(selected);
+                }
+            }
+            canvas.rect_text(line_rect, &self.content[start..end], TextAlign::LeftTop);
+            if row == caret_line && focused {
+                let x = line_rect.left() + carets.x_of(self.caret - start);
+                canvas.set_pen(&self.caret_pen);
+                canvas.line(x, top, x, top + height);
+            }
+        }
+        canvas.set_pen(match focused {
+            true => &self.focus_pen,
+            false => &self.frame_pen,
+        });
+        canvas.rect(rect);
+    }
+}
+
+impl EventListener for TextEdit {
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        if let Some(mods) = event.modifiers() {
+            self.mods = mods;
+        }
+        let shift = self.mods.shift();
+        // AltGr is told as Ctrl+Alt and types chars such as @ or ą on many layouts, so with it
+        // held down the keys are text rather than shortcuts
+        let ctrl = self.mods.ctrl() && !self.mods.alt();
+        match event {
+            Event::KeyPressed { key, .. } => match key {
+                KeyCode::Alpha('A') if ctrl => {
+                    self.set_selection(0, self.content.len());
+                }
+                KeyCode::Alpha('C') if ctrl => self.copy(),
+                KeyCode::Alpha('X') if ctrl => self.cut(),
+                KeyCode::Alpha('V') if ctrl => self.paste(),
+                KeyCode::Alpha('Z') if ctrl && !shift => self.undo(),
+                KeyCode::Alpha('Z') | KeyCode::Alpha('Y') if ctrl => self.redo(),
+                KeyCode::Left | KeyCode::Right => {
+                    let right = *key == KeyCode::Right;
+                    let (start, end) = self.selection();
+                    let pos = match shift || start == end {
+                        true => self.step(right),
+                        // the selection collapses to its side in the direction of the key
+                        false => {
+                            let lines = self.lines();
+                            let before = self.place_of(&lines, start) <= self.place_of(&lines, end);
+                            match before == right {
+                                true => end,
+                                false => start,
+                            }
+                        }
+                    };
+                    self.move_caret(pos, shift);
+                }
+                KeyCode::Up => self.move_lines(-1, shift),
+                KeyCode::Down => self.move_lines(1, shift),
+                KeyCode::PageUp => self.move_lines(-(self.visible_lines() as isize), shift),
+                KeyCode::PageDown => self.move_lines(self.visible_lines() as isize, shift),
+                KeyCode::Home => {
+                    let lines = self.lines();
+                    let line = Self::line_of(&lines, self.caret);
+                    self.move_caret(lines[line].0, shift);
+                }
+                KeyCode::End => {
+                    let lines = self.lines();
+                    let line = Self::line_of(&lines, self.caret);
+                    self.move_caret(lines[line].1, shift);
+                }
+                KeyCode::Delete => self.delete(true),
+                // the space is typed with the char, and Ctrl+Tab moves the focus
+                KeyCode::Tab if !ctrl => {}
+                _ => return EventResult::Ignored,
+            },
+            // with Ctrl held down, the keys are shortcuts rather than text
+            Event::Input { .. } if ctrl => return EventResult::Ignored,
+            Event::Input { ch } => match ch {
+                '\x08' => self.delete(false),
+                '\r' | '\n' => self.insert("\n", true),
+                '\t' => self.insert(" ", true),
+                ch if ch.is_control() => return EventResult::Ignored,
+                ch => self.insert(ch.encode_utf8(&mut [0; 4]), true),
+            },
+            Event::MouseButtonPressed {
+                button: MouseButton::Left,
+                pos,
+                ..
+            } => {
+                self.move_caret(self.pos_at(*pos), shift);
+                self.dragging = true;
+            }
+            Event::MouseMoved { pos, .. } if self.dragging => {
+                self.move_caret(self.pos_at(*pos), true);
+            }
+            Event::MouseButtonReleased {
+                button: MouseButton::Left,
+                ..
+            } => self.dragging = false,
+            Event::MouseWheelScrolled { delta, .. } => {
+                // at the top or the bottom, the parent scrolls instead
+                let end = match delta.lines_y < 0.0 {
+                    true => 0,
+                    false => self.max_scroll(),
+                };
+                if delta.lines_y == 0.0 || self.scroll == end {
+                    self.wheel = 0.0;
+                    return EventResult::Ignored;
+                }
+                // the fractions of a line scrolled by touchpads add up
+                self.wheel += delta.lines_y;
+                let lines = self.wheel.trunc();
+                self.wheel -= lines;
+                self.scroll_to_line(self.scroll.saturating_add_signed(lines as isize));
+            }
+            Event::FocusIn | Event::FocusOut => self.this.update(),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+}
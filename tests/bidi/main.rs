@@ -0,0 +1,108 @@
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::form;
+
+const FONT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fonts/DejaVuSans-Subset.ttf"
+);
+
+fn font() -> Font {
+    Font::new(FontFace::load(FONT).unwrap().style(20))
+}
+
+/// Draw `text` in black on white with the test font.
+fn draw(text: &str) -> Image {
+    let canvas = Canvas::offscreen(size!(200, 40));
+    canvas.clear(Color::WHITE);
+    canvas.set_font(&font());
+    canvas.xy_text(pos!(4, 4), text, TextAlign::LeftTop);
+    canvas.into_image().unwrap()
+}
+
+#[test]
+fn arabic_letters_are_joined() {
+    let canvas = Canvas::offscreen(size!(10, 10));
+    canvas.set_font(&font());
+    let width = |text| {
+        canvas
+            .formatted_text_extent(text, None, &TextFormat::default())
+            .width
+    };
+    // beh takes its initial, medial and final forms
+    let forms = canvas.text_extent("\u{FE91}\u{FE92}\u{FE90}").width;
+    assert_eq!(width("ببب"), forms);
+    // lam and alef make a single glyph
+    assert_eq!(width("لا"), canvas.text_extent("\u{FEFB}").width);
+    assert_ne!(width("لا"), canvas.text_extent("\u{FEDD}\u{FE8E}").width);
+    // the forms are drawn in place of the letters, from right to left
+    assert_eq!(draw("بلا"), draw("\u{202D}\u{FEFC}\u{FE91}"));
+}
+
+#[test]
+fn right_to_left_runs_are_reversed() {
+    // a left-to-right override shows the chars as they are written
+    assert_eq!(draw("שלום"), draw("\u{202D}םולש"));
+    // the number after the Hebrew word belongs to its run
+    assert_eq!(draw("abc שלום 12"), draw("\u{202D}abc 12 םולש"));
+    // numbers keep their order, and brackets face the text
+    assert_eq!(draw("שלום (12)"), draw("\u{202D}(12) םולש"));
+}
+
+#[test]
+fn ellipsis_ends_right_to_left_lines_on_the_left() {
+    // the first word and the ellipsis before it, as they are shown
+    let shown = "\u{202D}…םולש";
+    let measure = Canvas::offscreen(size!(10, 10));
+    measure.set_font(&font());
+    let width = measure.text_extent(shown).width + 2;
+
+    let format = TextFormat {
+        align: TextAlign::RightTop,
+        ellipsis: true,
+        ..Default::default()
+    };
+    let canvas = Canvas::offscreen(size!(width, 40));
+    canvas.clear(Color::WHITE);
+    canvas.set_font(&font());
+    canvas.formatted_text(rect!(0, 0, width, 40), "שלום עולם ומלואו", &format);
+    let expected = Canvas::offscreen(size!(width, 40));
+    expected.clear(Color::WHITE);
+    expected.set_font(&font());
+    expected.xy_text(pos!(width, 0), shown, TextAlign::RightTop);
+    assert_eq!(canvas.into_image(), expected.into_image());
+}
+
+#[test]
+fn caret_moves_in_visual_order() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    edit.as_window().show();
+    // "ab " then the Hebrew word, shown reversed; every char is 12 pixels wide
+    edit.set_text("ab אב");
+    app.click(pos!(125, 140));
+    assert_eq!(edit.caret(), 0);
+
+    let mut carets = Vec::new();
+    for _ in 0..5 {
+        app.press_key(KeyCode::Right);
+        carets.push(edit.caret());
+    }
+    assert_eq!(carets, [1, 2, 3, 5, 7]);
+    app.press_key(KeyCode::Left);
+    app.press_key(KeyCode::Left);
+    assert_eq!(edit.caret(), 3);
+
+    // the right of the Hebrew word is the end of the text
+    app.click(pos!(125 + 59, 140));
+    assert_eq!(edit.caret(), 7);
+    app.click(pos!(125 + 49, 140));
+    assert_eq!(edit.caret(), 5);
+    // typing there puts the char between the two Hebrew letters
+    app.type_text("ג");
+    assert_eq!(edit.text(), "ab אגב");
+}
@@ -0,0 +1,68 @@
+//! This file contains the fixtures shared by the test crates.
+//! Each crate uses only some of them.
+#![allow(dead_code)]
+
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+/// The events logged by recorders, with the names of the recorders that got them.
+pub type Log = Rc<RefCell<Vec<(&'static str, Event)>>>;
+
+/// Get the events in `log`, without the names of the recorders.
+pub fn events(log: &Log) -> Vec<Event> {
+    log.borrow().iter().map(|(_, event)| *event).collect()
+}
+
+/// Create a top-level form at (100, 100), 400 by 300 pixels, and show it.
+pub fn form() -> Widget<Block> {
+    let form = Block::create(rect!(100, 100, 400, 300), None);
+    form.as_window().show();
+    form
+}
+
+/// Logs the events `records` accepts under its name, and handles them.
+/// It ignores the other events.
+pub struct Recorder {
+    this: Window,
+    name: &'static str,
+    log: Log,
+    records: fn(&Event) -> bool,
+}
+
+default_as_window!(Recorder);
+
+impl Drawable for Recorder {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.clear(Color::WHITE);
+    }
+}
+
+impl EventListener for Recorder {
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        if !(self.records)(event) {
+            return EventResult::Ignored;
+        }
+        self.log.borrow_mut().push((self.name, *event));
+        EventResult::Handled
+    }
+}
+
+impl Recorder {
+    /// Create a recorder in `parent` that logs to `log`. It is hidden.
+    pub fn create(
+        name: &'static str,
+        rect: Rect,
+        parent: &Window,
+        log: &Log,
+        records: fn(&Event) -> bool,
+    ) -> Widget<Self> {
+        let recorder = Self {
+            this: Window::default(),
+            name,
+            log: log.clone(),
+            records,
+        };
+        Widget::new(name, rect, Some(parent), recorder)
+    }
+}
@@ -0,0 +1,205 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{form, Log, Recorder};
+
+/// Create a recorder of the focus events named `name` at `x` in `parent`, and show it.
+/// It takes the focus when it is clicked and with Tab.
+fn field(name: &'static str, x: i32, parent: &Window, log: &Log) -> Widget<Recorder> {
+    let records = |event: &Event| matches!(event, Event::FocusIn | Event::FocusOut);
+    let mut field = Recorder::create(name, rect!(x, 0, 50, 50), parent, log, records);
+    field.as_window_mut().set_focus_policy(FocusPolicy::Strong);
+    field.as_window().show();
+    field
+}
+
+/// Press Tab, with Shift held down if `backwards` is true.
+/// The Shift key goes to `form`, because only the held modifiers matter.
+fn tab(app: &Application, form: &Widget<Block>, backwards: bool) {
+    let (window, key, sys) = (form.as_window().get_id(), KeyCode::Shift, false);
+    if backwards {
+        app.simulate(window, Event::KeyPressed { key, sys, mods: Modifiers::SHIFT });
+    }
+    app.press_key(KeyCode::Tab);
+    if backwards {
+        app.simulate(window, Event::KeyReleased { key, sys, mods: Modifiers::NONE });
+    }
+}
+
+#[test]
+fn tab_follows_creation_order() {
+    let app = Application::new(true);
+    let form = form();
+    let log = Log::default();
+    let a = field("a", 0, form.as_window(), &log);
+    let b = field("b", 60, form.as_window(), &log);
+    let c = field("c", 120, form.as_window(), &log);
+
+    a.as_window().set_focus();
+    tab(&app, &form, false);
+    assert!(b.as_window().has_focus());
+    tab(&app, &form, false);
+    assert!(c.as_window().has_focus());
+    // the order wraps around at the end
+    tab(&app, &form, false);
+    assert!(a.as_window().has_focus());
+    tab(&app, &form, true);
+    assert!(c.as_window().has_focus());
+    tab(&app, &form, true);
+    assert!(b.as_window().has_focus());
+}
+
+#[test]
+fn tab_indices_come_first() {
+    let app = Application::new(true);
+    let form = form();
+    let log = Log::default();
+    let a = field("a", 0, form.as_window(), &log);
+    let mut b = field("b", 60, form.as_window(), &log);
+    let mut c = field("c", 120, form.as_window(), &log);
+    c.as_window_mut().set_tab_index(Some(0));
+    b.as_window_mut().set_tab_index(Some(1));
+    assert_eq!(c.as_window().tab_index(), Some(0));
+
+    c.as_window().set_focus();
+    tab(&app, &form, false);
+    assert!(b.as_window().has_focus());
+    tab(&app, &form, false);
+    assert!(a.as_window().has_focus());
+    tab(&app, &form, false);
+    assert!(c.as_window().has_focus());
+
+    b.as_window_mut().set_tab_index(None);
+    tab(&app, &form, false);
+    assert!(a.as_window().has_focus());
+}
+
+#[test]
+fn tab_skips_windows_without_focus() {
+    let app = Application::new(true);
+    let form = form();
+    let log = Log::default();
+    let a = field("a", 0, form.as_window(), &log);
+    let b = field("b", 60, form.as_window(), &log);
+    let mut c = field("c", 120, form.as_window(), &log);
+    let d = field("d", 180, form.as_window(), &log);
+    let e = field("e", 240, form.as_window(), &log);
+    b.as_window().hide();
+    c.as_window_mut().set_focus_policy(FocusPolicy::Click);
+    d.as_window().disable();
+    // the windows in a hidden window are skipped too
+    let panel = Block::create(rect!(0, 60, 200, 100), Some(form.as_window()));
+    let inner = field("inner", 0, panel.as_window(), &log);
+
+    a.as_window().set_focus();
+    tab(&app, &form, false);
+    assert!(e.as_window().has_focus());
+    panel.as_window().show();
+    tab(&app, &form, false);
+    assert!(inner.as_window().has_focus());
+}
+
+#[test]
+fn focus_events_tell_the_change() {
+    let app = Application::new(true);
+    let form = form();
+    let log = Log::default();
+    let a = field("a", 0, form.as_window(), &log);
+    let _b = field("b", 60, form.as_window(), &log);
+
+    a.as_window().set_focus();
+    // giving the focus to the window which has it changes nothing
+    a.as_window().set_focus();
+    tab(&app, &form, false);
+    app.click(pos!(110, 110));
+    assert_eq!(
+        *log.borrow(),
+        vec![
+            ("a", Event::FocusIn),
+            ("a", Event::FocusOut),
+            ("b", Event::FocusIn),
+            ("b", Event::FocusOut),
+            ("a", Event::FocusIn),
+        ]
+    );
+}
+
+#[test]
+fn clicks_follow_the_policy() {
+    let app = Application::new(true);
+    let form = form();
+    let log = Log::default();
+    let mut a = field("a", 0, form.as_window(), &log);
+    let b = field("b", 60, form.as_window(), &log);
+    a.as_window_mut().set_focus_policy(FocusPolicy::Tab);
+    assert!(!FocusPolicy::Tab.accepts_click());
+
+    b.as_window().set_focus();
+    app.click(pos!(110, 110));
+    assert!(b.as_window().has_focus());
+    app.click(pos!(170, 110));
+    assert!(b.as_window().has_focus());
+}
+
+#[test]
+fn tab_in_line_edit_moves_the_focus() {
+    let app = Application::new(true);
+    let form = form();
+    let name = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    let mail = LineEdit::create("Mail", rect!(20, 80, 200, 40), form.as_window());
+    name.as_window().show();
+    mail.as_window().show();
+    assert_eq!(name.as_window().focus_policy(), FocusPolicy::Strong);
+
+    app.click(pos!(130, 140));
+    app.type_text("Ada");
+    tab(&app, &form, false);
+    app.type_text("ada@example.com");
+    assert_eq!(name.text(), "Ada");
+    assert_eq!(mail.text(), "ada@example.com");
+}
+
+#[test]
+fn ctrl_tab_leaves_text_edit() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = TextEdit::create(rect!(20, 20, 200, 100), form.as_window());
+    let button = PushButton::create("OK", rect!(20, 140, 120, 40), form.as_window());
+    edit.as_window().show();
+    button.as_window().show();
+
+    edit.as_window().set_focus();
+    app.press_key(KeyCode::Tab);
+    assert!(edit.as_window().has_focus());
+    assert_eq!(edit.text(), " ");
+
+    let window = edit.as_window().get_id();
+    let (key, sys) = (KeyCode::Ctrl, false);
+    app.simulate(window, Event::KeyPressed { key, sys, mods: Modifiers::CTRL });
+    app.press_key(KeyCode::Tab);
+    app.simulate(window, Event::KeyReleased { key, sys, mods: Modifiers::NONE });
+    assert!(button.as_window().has_focus());
+    assert_eq!(edit.text(), " ");
+}
+
+#[test]
+fn space_presses_focused_button() {
+    let app = Application::new(true);
+    let form = form();
+    let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+    button.as_window().show();
+    let presses = Rc::new(RefCell::new(Vec::new()));
+    let sink = presses.clone();
+    button.press.add(
+        "test",
+        Responder::new(move |down: &bool| sink.borrow_mut().push(*down)),
+    );
+
+    button.as_window().set_focus();
+    app.press_key(KeyCode::Space);
+    assert_eq!(*presses.borrow(), vec![true, false]);
+}
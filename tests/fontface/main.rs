@@ -0,0 +1,97 @@
+use rusty_gui::*;
+
+const FONT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fonts/DejaVuSans-Subset.ttf"
+);
+
+fn face() -> FontFace {
+    FontFace::load(FONT).unwrap()
+}
+
+/// Draw `text` in black on white with `style`.
+fn draw(style: FontStyle, text: &str) -> Image {
+    let canvas = Canvas::offscreen(size!(80, 40));
+    canvas.clear(Color::WHITE);
+    canvas.set_font(&Font::new(style));
+    canvas.xy_text(pos!(4, 4), text, TextAlign::LeftTop);
+    canvas.into_image().unwrap()
+}
+
+/// Sum how dark the pixels of `image` are.
+fn ink(image: &Image) -> u32 {
+    image
+        .data()
+        .chunks(4)
+        .map(|pixel| 255 - pixel[0] as u32)
+        .sum()
+}
+
+#[test]
+fn faces_load_from_files_and_bytes() {
+    let face = face();
+    assert_eq!(face.family(), "DejaVu Sans");
+    assert!(!face.is_bold() && !face.is_italic());
+    assert!(face.has_char('A') && face.has_char('א'));
+    assert!(!face.has_char('中'));
+
+    let bytes = std::fs::read(FONT).unwrap();
+    assert_eq!(
+        FontFace::from_bytes(&bytes).unwrap().family(),
+        "DejaVu Sans"
+    );
+    let err = FontFace::from_bytes(b"not a font").unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(FontFace::load("missing.ttf").is_err());
+}
+
+#[test]
+fn text_is_measured_with_the_face() {
+    let font = Font::new(face().style(32));
+    let metrics = font.metrics();
+    assert_eq!(metrics.line_height, 32);
+    assert_eq!(metrics.ascent + metrics.descent, 32);
+    assert!(metrics.ascent > metrics.descent);
+    // the face is proportional, unlike the built-in font
+    assert!(font.advance('i') < font.advance('W'));
+    assert_eq!(
+        font.measure("iW").width,
+        font.measure("i").width + font.measure("W").width
+    );
+    assert_eq!(font.measure("iW").height, 32);
+}
+
+#[test]
+fn glyphs_are_antialiased_and_cached() {
+    let style = face().style(32);
+    let image = draw(style.clone(), "O");
+    let pixels: Vec<&[u8]> = image.data().chunks(4).collect();
+    assert!(pixels.iter().any(|pixel| pixel[0] == 0));
+    assert!(pixels.iter().any(|pixel| pixel[0] > 0 && pixel[0] < 255));
+    // drawing again with the cached glyphs gives the same pixels
+    assert_eq!(draw(style, "O"), image);
+}
+
+#[test]
+fn bold_and_italic_are_synthesized() {
+    let regular = face().style(32);
+    let bold = FontStyle {
+        weight: FontWeight::Bold,
+        ..regular.clone()
+    };
+    assert!(ink(&draw(bold, "l")) > ink(&draw(regular.clone(), "l")));
+
+    let italic = FontStyle {
+        italic: true,
+        ..regular.clone()
+    };
+    let (upright, slanted) = (draw(regular, "l"), draw(italic, "l"));
+    let dark = |image: &Image, y: i32| {
+        (0..80)
+            .filter(|&x| image.pixel(pos!(x, y)).unwrap().red < 128)
+            .min()
+    };
+    // the top of the italic "l" leans right of its bottom
+    assert_eq!(dark(&upright, 10), dark(&upright, 28));
+    assert!(dark(&slanted, 10) > dark(&slanted, 28));
+}
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+struct Counter {
+    this: Window,
+    ticks: usize,
+    draws: usize,
+    resized: Option<Size>,
+}
+
+default_as_window!(Counter);
+
+impl Drawable for Counter {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.clear(Color::WHITE);
+        self.draws += 1;
+    }
+}
+
+impl EventListener for Counter {
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        match event {
+            Event::Timer { id } => {
+                self.ticks += 1;
+                if self.ticks == 3 {
+                    self.this.kill_timer(*id);
+                }
+            }
+            Event::WindowResized { size, .. } => self.resized = Some(*size),
+            _ => {}
+        }
+        EventResult::Ignored
+    }
+}
+
+impl Counter {
+    fn create(rect: Rect, parent: Option<&Window>) -> Widget<Self> {
+        Widget::new(
+            "Counter",
+            rect,
+            parent,
+            Self {
+                this: Window::default(),
+                ticks: 0,
+                draws: 0,
+                resized: None,
+            },
+        )
+    }
+}
+
+#[test]
+fn timers_fire_until_killed() {
+    let app = Application::new(true);
+    let counter = Counter::create(rect!(0, 0, 100, 100), None);
+    counter.as_window().set_timer(1, 500);
+    app.exec();
+    assert_eq!(counter.ticks, 3);
+}
+
+#[test]
+fn shown_windows_are_painted() {
+    let app = Application::new(true);
+    let parent = Counter::create(rect!(0, 0, 400, 300), None);
+    let child = Counter::create(rect!(10, 10, 50, 50), Some(parent.as_window()));
+    let hidden = Counter::create(rect!(10, 10, 50, 50), None);
+    parent.as_window().show();
+    child.as_window().show();
+    app.exec();
+    assert_eq!(parent.draws, 1);
+    assert_eq!(child.draws, 1);
+    assert_eq!(hidden.draws, 0);
+}
+
+#[test]
+fn window_geometry() {
+    let parent = Counter::create(rect!(100, 100, 400, 300), None);
+    let child = Counter::create(rect!(10, 20, 50, 60), Some(parent.as_window()));
+    assert_eq!(child.as_window().rect(), rect!(0, 0, 50, 60));
+    assert_eq!(child.as_window().absrect(), rect!(110, 120, 50, 60));
+
+    child.as_window().set_size(size!(80, 90));
+    assert_eq!(child.resized, Some(size!(80, 90)));
+    assert_eq!(child.as_window().rect(), rect!(0, 0, 80, 90));
+
+    parent.as_window().set_title("Parent");
+    assert_eq!(parent.as_window().title(), "Parent");
+}
+
+#[test]
+fn focus_and_children() {
+    let parent = Counter::create(rect!(0, 0, 400, 300), None);
+    let mut child = Counter::create(rect!(0, 0, 50, 50), Some(parent.as_window()));
+    child.as_window_mut().write_data(42);
+
+    child.as_window().set_focus();
+    assert!(child.as_window().has_focus());
+    assert!(!parent.as_window().has_focus());
+
+    let mut found = Vec::new();
+    parent.as_window().foreach(move |w: &mut dyn Ele| {
+        found.push(w.as_window().read_data::<i32>());
+        assert_eq!(found, vec![Some(42)]);
+    });
+}
+
+#[test]
+fn dropped_windows_leave_the_tree() {
+    let parent = Counter::create(rect!(0, 0, 400, 300), None);
+    let mut kept = Counter::create(rect!(0, 0, 50, 50), Some(parent.as_window()));
+    let mut dropped = Counter::create(rect!(60, 0, 50, 50), Some(parent.as_window()));
+    kept.as_window_mut().write_data(1);
+    dropped.as_window_mut().write_data(2);
+    dropped.as_window().set_focus();
+    drop(dropped);
+    assert!(!parent.as_window().has_focus());
+
+    let found = Rc::new(RefCell::new(Vec::new()));
+    let sink = found.clone();
+    parent.as_window().foreach(move |w: &mut dyn Ele| {
+        sink.borrow_mut().push(w.as_window().read_data::<i32>());
+    });
+    assert_eq!(*found.borrow(), vec![Some(1)]);
+    // the child windows go with their parent, and can still be dropped afterwards
+    drop(parent);
+    drop(kept);
+}
+
+#[test]
+fn builtin_widgets_can_be_created() {
+    let app = Application::new(true);
+    let block = Block::create(rect!(50, 50, 800, 600), None);
+    let button = PushButton::create("Button", rect!(50, 50, 100, 50), block.as_window());
+    let switch = Switch::create("Switch", rect!(50, 150, 100, 50), block.as_window());
+    let edit = LineEdit::create("Input", rect!(50, 250, 200, 50), block.as_window());
+    for window in [
+        block.as_window(),
+        button.as_window(),
+        switch.as_window(),
+        edit.as_window(),
+    ] {
+        window.show();
+    }
+    app.exec();
+}
+
+#[test]
+fn clipboard_holds_text_or_image() {
+    let _app = Application::new(true);
+    Clipboard::set_text("text");
+    assert_eq!(Clipboard::get_text().as_deref(), Some("text"));
+    assert_eq!(Clipboard::get_image(), None);
+
+    let image = Image::new(size!(2, 2), Color::RED);
+    Clipboard::set_image(&image);
+    assert_eq!(Clipboard::get_image(), Some(image));
+    assert_eq!(Clipboard::get_text(), None);
+}
@@ -0,0 +1,449 @@
+use rusty_gui::*;
+
+fn form(size: Size) -> Widget<Block> {
+    Block::create(rect!(100, 100, size.width, size.height), None)
+}
+
+fn child(parent: &Widget<Block>, size: Size) -> Widget<Block> {
+    Block::create(
+        rect!(0, 0, size.width, size.height),
+        Some(parent.as_window()),
+    )
+}
+
+/// Get the rect of `child` relative to the client area of `parent`.
+fn placed(parent: &Widget<Block>, child: &Widget<Block>) -> Rect {
+    let origin = parent.as_window().absrect().pos;
+    let rect = child.as_window().absrect();
+    Rect {
+        pos: rect.pos - origin,
+        size: rect.size,
+    }
+}
+
+#[test]
+fn hbox_shares_free_space_by_stretch() {
+    let _app = Application::new(true);
+    let mut form = form(size!(400, 100));
+    let a = child(&form, size!(50, 10));
+    let b = child(&form, size!(50, 10));
+    let c = child(&form, size!(50, 10));
+
+    let mut layout = HBoxLayout::new();
+    layout.set_margins(Margins::new(10, 5, 10, 5));
+    layout.set_spacing(6);
+    layout.add_widget(a.as_window(), 0);
+    layout.add_widget(b.as_window(), 1);
+    layout.add_widget(c.as_window(), 2);
+    form.as_window_mut().set_layout(layout);
+
+    // 380 wide inside the margins: 50 for `a`, 12 of spacing, and 318 shared 1:2
+    assert_eq!(placed(&form, &a), rect!(10, 5, 50, 90));
+    assert_eq!(placed(&form, &b), rect!(66, 5, 106, 90));
+    assert_eq!(placed(&form, &c), rect!(178, 5, 212, 90));
+}
+
+#[test]
+fn vbox_follows_parent_resize() {
+    let _app = Application::new(true);
+    let mut form = form(size!(200, 200));
+    let top = child(&form, size!(10, 30));
+    let rest = child(&form, size!(10, 30));
+
+    let mut layout = VBoxLayout::new();
+    layout.set_spacing(10);
+    layout.add_widget(top.as_window(), 0);
+    layout.add_widget(rest.as_window(), 1);
+    form.as_window_mut().set_layout(layout);
+    assert_eq!(placed(&form, &top), rect!(0, 0, 200, 30));
+    assert_eq!(placed(&form, &rest), rect!(0, 40, 200, 160));
+
+    form.as_window().set_size(size!(300, 120));
+    assert_eq!(placed(&form, &top), rect!(0, 0, 300, 30));
+    assert_eq!(placed(&form, &rest), rect!(0, 40, 300, 80));
+}
+
+#[test]
+fn stretch_and_spacing_items() {
+    let _app = Application::new(true);
+    let mut form = form(size!(300, 40));
+    let ok = child(&form, size!(80, 20));
+    let cancel = child(&form, size!(60, 20));
+
+    let mut layout = HBoxLayout::new();
+    layout.add_stretch(1);
+    layout.add_widget(ok.as_window(), 0);
+    layout.add_spacing(20);
+    layout.add_widget(cancel.as_window(), 0);
+    form.as_window_mut().set_layout(layout);
+
+    // the buttons are pushed to the right edge
+    assert_eq!(placed(&form, &ok), rect!(140, 0, 80, 40));
+    assert_eq!(placed(&form, &cancel), rect!(240, 0, 60, 40));
+}
+
+#[test]
+fn set_stretch_and_relayout() {
+    let _app = Application::new(true);
+    let mut form = form(size!(100, 100));
+    let a = child(&form, size!(10, 10));
+    let b = child(&form, size!(10, 10));
+
+    let mut layout = HBoxLayout::new();
+    layout.add_widget(a.as_window(), 1);
+    layout.add_widget(b.as_window(), 1);
+    assert!(layout.set_stretch(b.as_window(), 3));
+    assert!(!layout.set_stretch(form.as_window(), 3));
+    form.as_window_mut().set_layout(layout);
+    assert_eq!(placed(&form, &a).size, size!(25, 100));
+    assert_eq!(placed(&form, &b), rect!(25, 0, 75, 100));
+
+    // without a layout, resizing leaves the children alone
+    form.as_window_mut().clear_layout();
+    form.as_window().set_size(size!(200, 50));
+    assert_eq!(placed(&form, &b), rect!(25, 0, 75, 100));
+}
+
+#[test]
+fn grid_rows_and_columns() {
+    let _app = Application::new(true);
+    let mut form = form(size!(300, 200));
+    let title = child(&form, size!(10, 10));
+    let label = child(&form, size!(10, 10));
+    let edit = child(&form, size!(10, 10));
+    let body = child(&form, size!(10, 10));
+
+    let mut grid = GridLayout::new();
+    grid.set_spacing(10);
+    grid.add_widget(title.as_window(), 0, 0, 1, 2);
+    grid.add_widget(label.as_window(), 1, 0, 1, 1);
+    grid.add_widget(edit.as_window(), 1, 1, 1, 1);
+    grid.add_widget(body.as_window(), 2, 0, 1, 2);
+    grid.set_column_minimum_width(0, 50);
+    grid.set_column_stretch(1, 1);
+    grid.set_row_preferred_height(0, 30);
+    grid.set_row_preferred_height(1, 30);
+    grid.set_row_stretch(2, 1);
+    assert_eq!((grid.row_count(), grid.column_count()), (3, 2));
+    form.as_window_mut().set_layout(grid);
+
+    assert_eq!(placed(&form, &title), rect!(0, 0, 300, 30));
+    assert_eq!(placed(&form, &label), rect!(0, 40, 50, 30));
+    assert_eq!(placed(&form, &edit), rect!(60, 40, 240, 30));
+    assert_eq!(placed(&form, &body), rect!(0, 80, 300, 120));
+
+    // the stretching column and row take the change
+    form.as_window().set_size(size!(200, 150));
+    assert_eq!(placed(&form, &edit), rect!(60, 40, 140, 30));
+    assert_eq!(placed(&form, &body), rect!(0, 80, 200, 70));
+}
+
+#[test]
+fn grid_limits_and_alignment() {
+    let _app = Application::new(true);
+    let mut form = form(size!(200, 100));
+    let mut wide = child(&form, size!(10, 10));
+    let mut narrow = child(&form, size!(10, 10));
+    let small = child(&form, size!(20, 10));
+    wide.as_window_mut().set_min_width(150);
+    narrow.as_window_mut().set_max_width(30);
+
+    let mut grid = GridLayout::new();
+    grid.add_widget(wide.as_window(), 0, 0, 1, 1);
+    grid.add_widget(narrow.as_window(), 0, 1, 1, 1);
+    grid.add_widget(small.as_window(), 1, 1, 1, 1);
+    assert!(grid.set_alignment(small.as_window(), Alignment::CENTER));
+    for i in 0..2 {
+        grid.set_row_stretch(i, 1);
+        grid.set_column_stretch(i, 1);
+    }
+    form.as_window_mut().set_layout(grid);
+
+    // the first column is widened for the minimum width of `wide`
+    assert_eq!(placed(&form, &wide), rect!(0, 0, 150, 50));
+    // `narrow` fills its cell up to its maximum width
+    assert_eq!(placed(&form, &narrow), rect!(150, 0, 30, 50));
+    // `small` keeps its size in the middle of its cell
+    assert_eq!(placed(&form, &small), rect!(165, 70, 20, 10));
+}
+
+#[test]
+fn push_button_hints_follow_label() {
+    let _app = Application::new(true);
+    let form = form(size!(300, 100));
+    let ok = PushButton::create("OK", rect!(0, 0, 10, 10), form.as_window());
+    let cancel = PushButton::create("Cancel", rect!(0, 0, 10, 10), form.as_window());
+    let hint = |b: &Widget<PushButton>| b.size_hint().unwrap();
+    assert!(hint(&cancel).width > hint(&ok).width);
+    assert!(ok.minimum_size_hint().unwrap().width < hint(&ok).width);
+    assert_eq!(
+        ok.as_window().size_policy(),
+        (SizePolicy::Preferred, SizePolicy::Fixed)
+    );
+}
+
+#[test]
+fn hbox_uses_size_hints() {
+    let _app = Application::new(true);
+    let mut form = form(size!(300, 100));
+    let ok = PushButton::create("OK", rect!(0, 0, 10, 10), form.as_window());
+    let cancel = PushButton::create("Cancel", rect!(0, 0, 10, 10), form.as_window());
+    let (ok_hint, cancel_hint) = (ok.size_hint().unwrap(), cancel.size_hint().unwrap());
+
+    let mut layout = HBoxLayout::new();
+    layout.add_stretch(1);
+    layout.add_widget(ok.as_window(), 0);
+    layout.add_widget(cancel.as_window(), 0);
+    form.as_window_mut().set_layout(layout);
+
+    // the buttons take the size of their labels, and are centered vertically
+    let origin = form.as_window().absrect().pos;
+    let ok_rect = ok.as_window().absrect();
+    let cancel_rect = cancel.as_window().absrect();
+    assert_eq!(ok_rect.size, ok_hint);
+    assert_eq!(cancel_rect.size, cancel_hint);
+    assert_eq!(cancel_rect.right() - origin.x, 300);
+    assert_eq!(ok_rect.right(), cancel_rect.left());
+    assert_eq!(cancel_rect.top() - origin.y, (100 - cancel_hint.height) / 2);
+
+    // without enough space, they shrink towards their minimum size hints
+    let (ok_min, cancel_min) = (
+        ok.minimum_size_hint().unwrap(),
+        cancel.minimum_size_hint().unwrap(),
+    );
+    form.as_window()
+        .set_size(size!(ok_min.width + cancel_min.width, 100));
+    assert_eq!(ok.as_window().absrect().size.width, ok_min.width);
+    assert_eq!(cancel.as_window().absrect().size.width, cancel_min.width);
+}
+
+#[test]
+fn expanding_and_fixed_policies() {
+    let _app = Application::new(true);
+    let mut form = form(size!(300, 100));
+    let mut fixed = child(&form, size!(40, 20));
+    let mut expanding = child(&form, size!(40, 20));
+    let preferred = child(&form, size!(40, 20));
+    fixed
+        .as_window_mut()
+        .set_size_policy(SizePolicy::Fixed, SizePolicy::Fixed);
+    expanding
+        .as_window_mut()
+        .set_size_policy(SizePolicy::Expanding, SizePolicy::Preferred);
+
+    let mut layout = HBoxLayout::new();
+    layout.add_widget(fixed.as_window(), 0);
+    layout.add_widget(expanding.as_window(), 0);
+    layout.add_widget(preferred.as_window(), 0);
+    form.as_window_mut().set_layout(layout);
+
+    // without stretch factors, the expanding window takes the free space
+    assert_eq!(placed(&form, &fixed), rect!(0, 40, 40, 20));
+    assert_eq!(placed(&form, &expanding), rect!(40, 0, 220, 100));
+    assert_eq!(placed(&form, &preferred), rect!(260, 0, 40, 100));
+}
+
+fn flex_item(grow: f32, shrink: f32) -> FlexItem {
+    FlexItem {
+        grow,
+        shrink,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn flex_grow_and_basis() {
+    let layout = FlexLayout::new(FlexDirection::Row);
+    let basis = FlexItem {
+        basis: Some(50),
+        ..flex_item(1.0, 1.0)
+    };
+    let rects = layout.compute(
+        rect!(0, 0, 300, 50),
+        &[
+            FlexBox::new(basis, size!(10, 10)),
+            FlexBox::new(flex_item(2.0, 1.0), size!(50, 10)),
+            FlexBox::new(flex_item(0.0, 1.0), size!(50, 10)),
+        ],
+    );
+    assert_eq!(
+        rects,
+        vec![
+            rect!(0, 0, 100, 50),
+            rect!(100, 0, 150, 50),
+            rect!(250, 0, 50, 50),
+        ]
+    );
+}
+
+#[test]
+fn flex_shrink_within_limits() {
+    let layout = FlexLayout::new(FlexDirection::Row);
+    let mut boxes = [
+        FlexBox::new(flex_item(0.0, 1.0), size!(100, 10)),
+        FlexBox::new(flex_item(0.0, 3.0), size!(100, 10)),
+    ];
+    // the overflow of 100 is taken 1:3
+    let rects = layout.compute(rect!(0, 0, 100, 10), &boxes);
+    assert_eq!(rects[0].size.width, 75);
+    assert_eq!(rects[1], rect!(75, 0, 25, 10));
+
+    boxes[1].min = size!(40, 0);
+    let rects = layout.compute(rect!(0, 0, 100, 10), &boxes);
+    assert_eq!(rects[0].size.width, 60);
+    assert_eq!(rects[1], rect!(60, 0, 40, 10));
+}
+
+#[test]
+fn flex_wrap_and_justify() {
+    let mut layout = FlexLayout::new(FlexDirection::Row);
+    layout.set_wrap(FlexWrap::Wrap);
+    layout.set_gap(10);
+    layout.set_justify_content(JustifyContent::Center);
+    layout.set_align_items(Align::Start);
+    layout.set_margins(Margins::uniform(5));
+    let item = FlexItem::default();
+    let rects = layout.compute(
+        rect!(0, 0, 210, 110),
+        &[
+            FlexBox::new(item, size!(80, 20)),
+            FlexBox::new(item, size!(80, 30)),
+            FlexBox::new(item, size!(80, 20)),
+        ],
+    );
+    assert_eq!(
+        rects,
+        vec![
+            rect!(20, 5, 80, 20),
+            rect!(110, 5, 80, 30),
+            rect!(65, 45, 80, 20),
+        ]
+    );
+}
+
+#[test]
+fn flex_reverse_and_alignment() {
+    let mut layout = FlexLayout::new(FlexDirection::RowReverse);
+    layout.set_justify_content(JustifyContent::SpaceBetween);
+    layout.set_align_items(Align::Center);
+    let end = FlexItem {
+        align_self: Some(Align::End),
+        ..Default::default()
+    };
+    let rects = layout.compute(
+        rect!(0, 0, 200, 60),
+        &[
+            FlexBox::new(FlexItem::default(), size!(40, 20)),
+            FlexBox::new(FlexItem::default(), size!(60, 40)),
+            FlexBox::new(end, size!(20, 20)),
+        ],
+    );
+    assert_eq!(
+        rects,
+        vec![
+            rect!(160, 20, 40, 20),
+            rect!(60, 10, 60, 40),
+            rect!(0, 40, 20, 20),
+        ]
+    );
+
+    let mut layout = FlexLayout::new(FlexDirection::Column);
+    layout.set_wrap(FlexWrap::WrapReverse);
+    layout.set_justify_content(JustifyContent::SpaceEvenly);
+    let item = FlexItem::default();
+    let rects = layout.compute(
+        rect!(0, 0, 100, 200),
+        &[
+            FlexBox::new(item, size!(30, 50)),
+            FlexBox::new(item, size!(30, 50)),
+        ],
+    );
+    assert_eq!(rects, vec![rect!(70, 33, 30, 50), rect!(70, 117, 30, 50)]);
+}
+
+#[test]
+fn flex_layout_follows_parent_resize() {
+    let _app = Application::new(true);
+    let mut form = form(size!(200, 100));
+    let side = child(&form, size!(50, 10));
+    let main = child(&form, size!(10, 10));
+
+    let mut layout = FlexLayout::new(FlexDirection::Row);
+    layout.set_gap(10);
+    layout.add_widget(side.as_window(), FlexItem::default());
+    layout.add_widget(main.as_window(), flex_item(1.0, 1.0));
+    form.as_window_mut().set_layout(layout);
+    assert_eq!(placed(&form, &side), rect!(0, 0, 50, 100));
+    assert_eq!(placed(&form, &main), rect!(60, 0, 140, 100));
+
+    form.as_window().set_size(size!(400, 50));
+    assert_eq!(placed(&form, &side), rect!(0, 0, 50, 50));
+    assert_eq!(placed(&form, &main), rect!(60, 0, 340, 50));
+}
+
+#[test]
+fn anchors_place() {
+    let rect = rect!(5, 5, 40, 20);
+    let parent = size!(200, 100);
+    let margins = Margins::new(10, 20, 30, 40);
+    assert_eq!(
+        Anchors::FILL.place(margins, parent, rect),
+        rect!(10, 20, 160, 40)
+    );
+    assert_eq!(
+        Anchors::CENTER.place(margins, parent, rect),
+        rect!(60, 20, 40, 20)
+    );
+    let corner = Anchors {
+        right: true,
+        bottom: true,
+        ..Default::default()
+    };
+    assert_eq!(corner.place(margins, parent, rect), rect!(130, 40, 40, 20));
+    // no anchor on an axis keeps the window there
+    let top = Anchors {
+        top: true,
+        ..Default::default()
+    };
+    assert_eq!(top.place(margins, parent, rect), rect!(5, 20, 40, 20));
+}
+
+#[test]
+fn anchored_children_follow_parent() {
+    let _app = Application::new(true);
+    let form = form(size!(300, 200));
+    let mut toolbar = child(&form, size!(10, 30));
+    let mut ok = child(&form, size!(80, 30));
+    let free = child(&form, size!(20, 20));
+    free.as_window().set_pos(pos!(7, 9));
+    toolbar.as_window_mut().set_anchors(
+        Anchors {
+            left: true,
+            top: true,
+            right: true,
+            ..Default::default()
+        },
+        Margins::uniform(5),
+    );
+    ok.as_window_mut().set_anchors(
+        Anchors {
+            right: true,
+            bottom: true,
+            ..Default::default()
+        },
+        Margins::uniform(10),
+    );
+    assert_eq!(placed(&form, &toolbar), rect!(5, 5, 290, 30));
+    assert_eq!(placed(&form, &ok), rect!(210, 160, 80, 30));
+
+    form.as_window().set_size(size!(500, 400));
+    assert_eq!(placed(&form, &toolbar), rect!(5, 5, 490, 30));
+    assert_eq!(placed(&form, &ok), rect!(410, 360, 80, 30));
+    assert_eq!(placed(&form, &free), rect!(7, 9, 20, 20));
+
+    ok.as_window_mut().clear_anchors();
+    form.as_window().set_size(size!(300, 200));
+    assert_eq!(placed(&form, &ok), rect!(410, 360, 80, 30));
+    assert_eq!(placed(&form, &toolbar), rect!(5, 5, 290, 30));
+    assert!(free.as_window().anchors().is_none());
+}
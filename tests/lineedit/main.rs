@@ -0,0 +1,238 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::form;
+
+/// A line edit at (20, 20) in a form at (100, 100).
+/// The font is 12 pixels wide and the text starts 5 pixels in, so 15 chars fit in it.
+fn line_edit(form: &Widget<Block>) -> Widget<LineEdit> {
+    let edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    edit.as_window().show();
+    edit
+}
+
+/// Get the position on the screen of the visible char at `column`.
+fn char_pos(column: i32) -> Point {
+    pos!(125 + column * 12 + 2, 140)
+}
+
+/// Press `key` in `window`, which has the focus, while holding `modifier`.
+fn chord(app: &Application, window: WindowID, modifier: KeyCode, key: KeyCode) {
+    app.simulate(
+        window,
+        Event::KeyPressed {
+            key: modifier,
+            sys: false,
+            mods: Modifiers::from_key(modifier),
+        },
+    );
+    app.press_key(key);
+    app.simulate(
+        window,
+        Event::KeyReleased {
+            key: modifier,
+            sys: false,
+            mods: Modifiers::NONE,
+        },
+    );
+}
+
+#[test]
+fn fix_typo_in_the_middle() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = line_edit(&form);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let sink = changes.clone();
+    edit.content_changed.add(
+        "test",
+        Responder::new(move |text: &String| sink.borrow_mut().push(text.clone())),
+    );
+
+    app.click(char_pos(0));
+    app.type_text("helo");
+    app.press_key(KeyCode::Left);
+    app.type_text("l");
+    assert_eq!(edit.text(), "hello");
+    assert_eq!(edit.caret(), 4);
+
+    app.press_key(KeyCode::Home);
+    app.press_key(KeyCode::Delete);
+    assert_eq!(edit.text(), "ello");
+    app.press_key(KeyCode::End);
+    let window = edit.as_window().get_id();
+    chord(&app, window, KeyCode::Shift, KeyCode::Left);
+    chord(&app, window, KeyCode::Shift, KeyCode::Left);
+    assert_eq!(edit.selected_text(), "lo");
+    app.press_key(KeyCode::Backspace);
+    assert_eq!(edit.text(), "el");
+    assert_eq!(changes.borrow().last().map(String::as_str), Some("el"));
+}
+
+#[test]
+fn select_all_and_replace() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = line_edit(&form);
+    app.click(char_pos(0));
+    app.type_text("draft");
+    let window = edit.as_window().get_id();
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('A'));
+    assert_eq!(edit.text(), "draft");
+    assert_eq!(edit.selection(), (0, 5));
+    app.type_text("final");
+    assert_eq!(edit.text(), "final");
+}
+
+#[test]
+fn horizontal_scrolling() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = line_edit(&form);
+    app.click(char_pos(0));
+    app.type_text("abcdefghijklmnopqrst");
+
+    // the first 5 chars are scrolled out of sight
+    app.click(char_pos(0));
+    assert_eq!(edit.caret(), 5);
+    app.press_key(KeyCode::Home);
+    app.click(char_pos(3));
+    assert_eq!(edit.caret(), 3);
+}
+
+#[test]
+fn max_length_and_read_only() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = line_edit(&form);
+    edit.set_max_length(Some(4));
+    app.click(char_pos(0));
+    app.type_text("123456");
+    assert_eq!(edit.text(), "1234");
+
+    edit.set_read_only(true);
+    app.press_key(KeyCode::Backspace);
+    app.type_text("5");
+    assert_eq!(edit.text(), "1234");
+    app.press_key(KeyCode::Home);
+    assert_eq!(edit.caret(), 0);
+}
+
+#[test]
+fn password_masking() {
+    let _app = Application::new(true);
+    let form = form();
+    let mut password = line_edit(&form);
+    password.set_password(true);
+    password.set_text("abc");
+    let mut masked = line_edit(&form);
+    masked.set_text("***");
+    let size = size!(200, 40);
+    assert_eq!(password.text(), "abc");
+    assert!(password.render_to_image(size) == masked.render_to_image(size));
+}
+
+#[test]
+fn undo_and_redo() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = line_edit(&form);
+    let available = Rc::new(RefCell::new(Vec::new()));
+    let sink = available.clone();
+    edit.can_undo_changed().add(
+        "test",
+        Responder::new(move |can: &bool| sink.borrow_mut().push(*can)),
+    );
+    let window = edit.as_window().get_id();
+
+    app.click(char_pos(0));
+    app.type_text("hello");
+    app.press_key(KeyCode::Left);
+    app.type_text("wor");
+    app.press_key(KeyCode::Backspace);
+    assert_eq!(edit.text(), "hellwoo");
+
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('Z'));
+    assert_eq!(edit.text(), "hellworo");
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('Z'));
+    assert_eq!(edit.text(), "hello");
+    assert_eq!(edit.caret(), 4);
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('Z'));
+    assert_eq!(edit.text(), "");
+    assert!(!edit.can_undo());
+    assert_eq!(*available.borrow(), [true, false]);
+
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('Y'));
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('Y'));
+    assert_eq!(edit.text(), "hellworo");
+    assert!(edit.can_redo());
+}
+
+#[test]
+fn copy_cut_and_paste() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = line_edit(&form);
+    let window = edit.as_window().get_id();
+    app.click(char_pos(0));
+    app.type_text("copy me");
+    edit.set_selection(0, 4);
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('C'));
+    assert_eq!(Clipboard::get_text().as_deref(), Some("copy"));
+
+    app.press_key(KeyCode::End);
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('V'));
+    assert_eq!(edit.text(), "copy mecopy");
+    edit.set_selection(4, 7);
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('X'));
+    assert_eq!(edit.text(), "copycopy");
+    assert_eq!(Clipboard::get_text().as_deref(), Some(" me"));
+
+    // only the first line is pasted, and a password is never copied
+    Clipboard::set_text("one\ntwo");
+    edit.set_password(true);
+    edit.select_all();
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('V'));
+    assert_eq!(edit.text(), "one");
+    edit.select_all();
+    chord(&app, window, KeyCode::Ctrl, KeyCode::Alpha('C'));
+    assert_eq!(Clipboard::get_text().as_deref(), Some("one\ntwo"));
+}
+
+#[test]
+fn altgr_types_chars() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = line_edit(&form);
+    app.click(char_pos(0));
+    let window = edit.as_window().get_id();
+    // AltGr is told as Ctrl+Alt
+    let altgr = Modifiers::CTRL | Modifiers::ALT;
+    app.simulate(window, Event::KeyPressed { key: KeyCode::RightAlt, sys: false, mods: altgr });
+    app.type_text("a@b");
+    let mods = Modifiers::NONE;
+    app.simulate(window, Event::KeyReleased { key: KeyCode::RightAlt, sys: false, mods });
+    assert_eq!(edit.text(), "a@b");
+}
+
+#[test]
+fn altgr_letters_are_not_shortcuts() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = line_edit(&form);
+    edit.set_text("xy");
+    app.click(char_pos(2));
+    let window = edit.as_window().get_id();
+    // AltGr+A types ą on a Polish layout, and is told as Ctrl+Alt+A
+    let key = KeyCode::Alpha('A');
+    let mods = Modifiers::CTRL | Modifiers::ALT;
+    app.simulate(window, Event::KeyPressed { key, sys: false, mods });
+    app.simulate(window, Event::Input { ch: 'ą' });
+    app.simulate(window, Event::KeyReleased { key, sys: false, mods });
+    assert_eq!(edit.selected_text(), "");
+    assert_eq!(edit.text(), "xyą");
+}
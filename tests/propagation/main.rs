@@ -0,0 +1,183 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+type Log = Rc<RefCell<Vec<(&'static str, Event)>>>;
+
+/// A container that logs the input events it sees, and handles the ones `handles` accepts.
+struct Panel {
+    this: Window,
+    name: &'static str,
+    log: Log,
+    captures: fn(&Event) -> bool,
+    handles: fn(&Event) -> bool,
+}
+
+default_as_window!(Panel);
+
+impl Drawable for Panel {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.clear(Color::WHITE);
+    }
+}
+
+impl EventListener for Panel {
+    fn on_capture(&mut self, event: &Event) -> EventResult {
+        if !(self.captures)(event) {
+            return EventResult::Ignored;
+        }
+        self.log.borrow_mut().push((self.name, *event));
+        EventResult::Handled
+    }
+
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        if !event.is_input() {
+            return EventResult::Ignored;
+        }
+        self.log.borrow_mut().push((self.name, *event));
+        match (self.handles)(event) {
+            true => EventResult::Handled,
+            false => EventResult::Ignored,
+        }
+    }
+}
+
+impl Panel {
+    fn create(name: &'static str, rect: Rect, parent: Option<&Window>, log: &Log) -> Widget<Self> {
+        let panel = Widget::new(
+            "Panel",
+            rect,
+            parent,
+            Self {
+                this: Window::default(),
+                name,
+                log: log.clone(),
+                captures: |_| false,
+                handles: |_| false,
+            },
+        );
+        panel.as_window().show();
+        panel
+    }
+}
+
+/// An outer panel at (100, 100), an inner panel at (20, 20) in it, and a line edit at
+/// (10, 10) in the inner panel.
+fn tree(log: &Log) -> (Widget<Panel>, Widget<Panel>, Widget<LineEdit>) {
+    let outer = Panel::create("outer", rect!(100, 100, 400, 300), None, log);
+    let inner = Panel::create(
+        "inner",
+        rect!(20, 20, 300, 200),
+        Some(outer.as_window()),
+        log,
+    );
+    let edit = LineEdit::create("Name", rect!(10, 10, 200, 40), inner.as_window());
+    edit.as_window().show();
+    (outer, inner, edit)
+}
+
+fn names(log: &Log) -> Vec<&'static str> {
+    log.borrow().iter().map(|(name, _)| *name).collect()
+}
+
+#[test]
+fn ignored_events_bubble_to_the_parents() {
+    let app = Application::new(true);
+    let log = Log::default();
+    let (_outer, _inner, edit) = tree(&log);
+    let wheel = |pos| Event::MouseWheelScrolled {
+        delta: ScrollDelta::lines(0.0, 3.0),
+        pos,
+        mods: Modifiers::NONE,
+    };
+    app.simulate(edit.as_window().get_id(), wheel(pos!(20, 20)));
+    // the position is translated to each parent
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("inner", wheel(pos!(30, 30))),
+            ("outer", wheel(pos!(50, 50)))
+        ]
+    );
+}
+
+#[test]
+fn handled_events_stop_at_the_target() {
+    let app = Application::new(true);
+    let log = Log::default();
+    let (_outer, mut inner, edit) = tree(&log);
+    inner.handles = |event| matches!(event, Event::KeyPressed { .. });
+    app.click(pos!(140, 140));
+    log.borrow_mut().clear();
+
+    // the line edit takes the text, and does not use F5
+    app.type_text("ab");
+    app.press_key(KeyCode::F(5));
+    assert_eq!(edit.text(), "ab");
+    // the inner panel handles the key press, so the outer one never sees it
+    let key = Event::KeyPressed {
+        key: KeyCode::F(5),
+        sys: false,
+        mods: Modifiers::NONE,
+    };
+    assert_eq!(log.borrow()[0], ("inner", key));
+    assert!(!log.borrow().contains(&("outer", key)));
+}
+
+#[test]
+fn parents_capture_events_before_the_target() {
+    let app = Application::new(true);
+    let log = Log::default();
+    let (mut outer, mut inner, edit) = tree(&log);
+    outer.captures = |event| matches!(event, Event::Input { ch: 'x' });
+    inner.captures = |event| matches!(event, Event::Input { .. });
+    app.click(pos!(140, 140));
+    log.borrow_mut().clear();
+
+    app.type_text("x");
+    assert_eq!(names(&log), ["outer"]);
+    app.type_text("y");
+    assert_eq!(names(&log), ["outer", "inner"]);
+    assert_eq!(edit.text(), "");
+}
+
+#[test]
+fn clicked_buttons_stop_the_press() {
+    let app = Application::new(true);
+    let log = Log::default();
+    let outer = Panel::create("outer", rect!(100, 100, 400, 300), None, &log);
+    let button = PushButton::create("OK", rect!(20, 20, 120, 40), outer.as_window());
+    button.as_window().show();
+    app.click(pos!(130, 130));
+    let pressed = log
+        .borrow()
+        .iter()
+        .any(|(_, event)| matches!(event, Event::MouseButtonPressed { .. }));
+    assert!(!pressed);
+}
+
+#[test]
+fn right_clicks_on_buttons_bubble() {
+    let app = Application::new(true);
+    let log = Log::default();
+    let outer = Panel::create("outer", rect!(100, 100, 400, 300), None, &log);
+    let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), outer.as_window());
+    button.as_window().show();
+    let presses = Rc::new(RefCell::new(0));
+    let sink = presses.clone();
+    button.press.add("test", Responder::new(move |_| *sink.borrow_mut() += 1));
+
+    let window = button.as_window().get_id();
+    let (pos, mods) = (pos!(10, 10), Modifiers::RIGHT_BUTTON);
+    let right = MouseButton::Right;
+    app.simulate(window, Event::MouseButtonPressed { button: right, pos, mods });
+    let mods = Modifiers::NONE;
+    app.simulate(window, Event::MouseButtonReleased { button: right, pos, mods });
+    // the button does not use the right button, so the panel gets both events
+    let (pos, mods) = (pos!(30, 30), Modifiers::RIGHT_BUTTON);
+    let pressed = Event::MouseButtonPressed { button: right, pos, mods };
+    let mods = Modifiers::NONE;
+    let released = Event::MouseButtonReleased { button: right, pos, mods };
+    assert_eq!(*log.borrow(), [("outer", pressed), ("outer", released)]);
+    assert_eq!(*presses.borrow(), 0);
+}
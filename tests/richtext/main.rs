@@ -0,0 +1,115 @@
+use rusty_gui::*;
+
+fn colored(color: Color) -> TextStyle {
+    TextStyle {
+        color,
+        ..Default::default()
+    }
+}
+
+/// Get the smallest rectangle holding the pixels of `color`.
+fn bounds(image: &Image, color: Color) -> Rect {
+    let size = image.size();
+    let points: Vec<Point> = (0..size.height)
+        .flat_map(|y| (0..size.width).map(move |x| pos!(x, y)))
+        .filter(|p| image.pixel(*p) == Some(color))
+        .collect();
+    let left = points.iter().map(|p| p.x).min().unwrap();
+    let top = points.iter().map(|p| p.y).min().unwrap();
+    let right = points.iter().map(|p| p.x).max().unwrap();
+    let bottom = points.iter().map(|p| p.y).max().unwrap();
+    rect!(left, top, right - left + 1, bottom - top + 1)
+}
+
+#[test]
+fn styles_split_and_merge_runs() {
+    let mut text = RichText::from("hello world");
+    assert_eq!(text.runs().count(), 1);
+
+    let red = colored(Color::RED);
+    text.set_style(0..5, &red);
+    text.set_style(6..11, &red);
+    let runs: Vec<_> = text.runs().map(|(range, text, _)| (range, text)).collect();
+    assert_eq!(runs, vec![(0..5, "hello"), (5..6, " "), (6..11, "world")]);
+    assert_eq!(text.style_at(5), Some(&TextStyle::default()));
+    assert_eq!(text.style_at(11), None);
+
+    text.set_style(5..6, &red);
+    assert_eq!(text.runs().count(), 1);
+    assert_eq!(text.style_at(0), Some(&red));
+
+    text.push("!", &red).push("?", &TextStyle::default());
+    assert_eq!(text.text(), "hello world!?");
+    assert_eq!(text.runs().count(), 2);
+}
+
+#[test]
+fn runs_are_measured_with_their_fonts() {
+    let big = TextStyle {
+        font: FontStyle {
+            size: 32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut text = RichText::new();
+    text.push("BIG", &big).push("small", &TextStyle::default());
+    // 3 chars of 16 pixels and 5 chars of 8 pixels, on a line as tall as the big font
+    assert_eq!(text.measure(None, TextAlign::LeftTop), size!(88, 32));
+
+    text.push("\nsmall", &TextStyle::default());
+    assert_eq!(text.measure(None, TextAlign::LeftTop), size!(88, 48));
+    let wrapped = TextFormat {
+        wrap: true,
+        ..Default::default()
+    };
+    // "BIGsmall" has no space, so it is broken after "BIGs"
+    assert_eq!(text.measure(Some(60), wrapped), size!(56, 64));
+    let truncated = TextFormat {
+        ellipsis: true,
+        ..Default::default()
+    };
+    // "BIGs…" with the ellipsis in the small font
+    assert_eq!(text.measure(Some(70), truncated), size!(64, 48));
+}
+
+#[test]
+fn runs_share_a_baseline() {
+    let big = TextStyle {
+        font: FontStyle {
+            size: 32,
+            ..Default::default()
+        },
+        ..colored(Color::RED)
+    };
+    let mut text = RichText::new();
+    text.push("BIG", &big).push("small", &colored(Color::BLUE));
+
+    let canvas = Canvas::offscreen(size!(100, 40));
+    canvas.clear(Color::WHITE);
+    canvas.rich_text(rect!(0, 0, 100, 40), &text, TextAlign::LeftTop);
+    let image = canvas.into_image().unwrap();
+    let (big, small) = (bounds(&image, Color::RED), bounds(&image, Color::BLUE));
+    assert_eq!(big.bottom(), small.bottom());
+    assert!(big.right() <= 48 && small.left() >= 48);
+}
+
+#[test]
+fn background_highlights_the_run() {
+    let highlight = TextStyle {
+        background: Some(Color::YELLOW),
+        ..Default::default()
+    };
+    let mut text = RichText::new();
+    text.push("ab", &TextStyle::default())
+        .push("cd", &highlight);
+
+    let canvas = Canvas::offscreen(size!(40, 20));
+    canvas.clear(Color::WHITE);
+    let pen = canvas.set_pen(&Pen::new(PenStyle::default()));
+    canvas.rich_text(rect!(0, 0, 40, 20), &text, TextAlign::LeftTop);
+    // the pen of the canvas is given back
+    assert_eq!(canvas.set_pen(&pen).style(), PenStyle::default());
+    let image = canvas.into_image().unwrap();
+    assert_eq!(bounds(&image, Color::YELLOW), rect!(16, 0, 16, 16));
+}
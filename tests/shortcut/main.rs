@@ -0,0 +1,263 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::form;
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+/// Logs the keys pressed while it has the focus.
+struct Recorder {
+    this: Window,
+    log: Log,
+}
+
+default_as_window!(Recorder);
+
+impl Drawable for Recorder {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        canvas.clear(Color::WHITE);
+    }
+}
+
+impl EventListener for Recorder {
+    fn on_event(&mut self, event: &Event) -> EventResult {
+        if let Event::KeyPressed { key, mods, .. } = event {
+            let chord = KeyChord::new(*mods, *key);
+            self.log.borrow_mut().push(chord.to_string());
+            return EventResult::Handled;
+        }
+        EventResult::Ignored
+    }
+}
+
+fn recorder(parent: &Window) -> (Widget<Recorder>, Log) {
+    let log = Log::default();
+    let recorder = Recorder {
+        this: Window::default(),
+        log: log.clone(),
+    };
+    let widget = Widget::new("Recorder", rect!(0, 0, 50, 50), Some(parent), recorder);
+    widget.as_window().show();
+    widget.as_window().set_focus();
+    (widget, log)
+}
+
+/// A responder that logs the key sequences under `name`.
+fn logger(log: &Log, name: &'static str) -> Responder<KeySequence> {
+    let log = log.clone();
+    Responder::new(move |keys: &KeySequence| log.borrow_mut().push(format!("{} {}", name, keys)))
+}
+
+/// Press `key` while holding Ctrl.
+fn ctrl(app: &Application, window: &Window, key: KeyCode) {
+    let (id, sys) = (window.get_id(), false);
+    let mods = Modifiers::CTRL;
+    app.simulate(
+        id,
+        Event::KeyPressed {
+            key: KeyCode::Ctrl,
+            sys,
+            mods,
+        },
+    );
+    app.press_key(key);
+    let mods = Modifiers::NONE;
+    app.simulate(
+        id,
+        Event::KeyReleased {
+            key: KeyCode::Ctrl,
+            sys,
+            mods,
+        },
+    );
+}
+
+#[test]
+fn parse_and_display() {
+    let parse = |text: &str| text.parse::<KeySequence>();
+    let keys = parse("control + shift + s").unwrap();
+    let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+    assert_eq!(
+        keys.chords(),
+        [KeyChord::new(ctrl_shift, KeyCode::Alpha('S'))]
+    );
+    assert_eq!(keys.to_string(), "Ctrl+Shift+S");
+    assert_eq!(parse("Shift+Alt+F12").unwrap().to_string(), "Alt+Shift+F12");
+    assert_eq!(parse("Meta+Escape").unwrap().to_string(), "Meta+Esc");
+    assert_eq!(parse("Num5").unwrap().chords()[0].key, KeyCode::Num(5));
+    assert_eq!(parse("Ctrl+K, Ctrl+C").unwrap().chords().len(), 2);
+    // a comma after a plus is the key
+    let comma = parse("Ctrl+,, PgDown").unwrap();
+    assert_eq!(comma.chords()[0].key, KeyCode::Symbol(','));
+    assert_eq!(comma.to_string(), "Ctrl+,, PgDown");
+
+    assert_eq!(parse(""), Err(ShortcutError::Empty));
+    assert_eq!(parse("Ctrl+K,"), Err(ShortcutError::Empty));
+    assert_eq!(
+        parse("Ctrl+Shift"),
+        Err(ShortcutError::NoKey("Ctrl+Shift".into()))
+    );
+    assert_eq!(
+        parse("Ctrl+Foo"),
+        Err(ShortcutError::UnknownKey("Foo".into()))
+    );
+    assert_eq!(parse("A+B"), Err(ShortcutError::SeveralKeys("A+B".into())));
+    assert_eq!(
+        parse("S+Ctrl"),
+        Err(ShortcutError::SeveralKeys("S+Ctrl".into()))
+    );
+}
+
+#[test]
+fn shortcut_takes_the_key() {
+    let app = Application::new(true);
+    let form = form();
+    let (_recorder, log) = recorder(form.as_window());
+    let _save = Shortcut::new("Ctrl+S", form.as_window(), logger(&log, "save")).unwrap();
+    let _rename = Shortcut::new("F2", form.as_window(), logger(&log, "rename")).unwrap();
+
+    ctrl(&app, form.as_window(), KeyCode::Alpha('S'));
+    app.press_key(KeyCode::F(2));
+    app.press_key(KeyCode::Alpha('S'));
+    assert_eq!(*log.borrow(), ["save Ctrl+S", "rename F2", "S"]);
+}
+
+#[test]
+fn win_keys_hold_meta() {
+    let app = Application::new(true);
+    let form = form();
+    let (recorder, log) = recorder(form.as_window());
+    let _find = Shortcut::new("Meta+K", form.as_window(), logger(&log, "find")).unwrap();
+    assert_eq!(Modifiers::from_key(KeyCode::RightWin), Modifiers::META);
+
+    let (id, key, sys) = (recorder.as_window().get_id(), KeyCode::LeftWin, false);
+    app.simulate(id, Event::KeyPressed { key, sys, mods: Modifiers::META });
+    log.borrow_mut().clear();
+    app.press_key(KeyCode::Alpha('K'));
+    app.simulate(id, Event::KeyReleased { key, sys, mods: Modifiers::NONE });
+    assert_eq!(*log.borrow(), ["find Meta+K"]);
+}
+
+#[test]
+fn shortcut_keeps_the_char_from_line_edit() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    edit.as_window().show();
+    edit.as_window().set_focus();
+    let log = Log::default();
+    let _next = Shortcut::new("N", form.as_window(), logger(&log, "next")).unwrap();
+
+    app.type_text("ab");
+    app.press_key(KeyCode::Alpha('N'));
+    assert_eq!(edit.text(), "ab");
+    assert_eq!(*log.borrow(), ["next N"]);
+}
+
+#[test]
+fn keys_without_a_char_keep_the_next_one() {
+    let app = Application::new(true);
+    let form = form();
+    let edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    edit.as_window().show();
+    edit.as_window().set_focus();
+    let log = Log::default();
+    let _reload = Shortcut::new("F5", form.as_window(), logger(&log, "reload")).unwrap();
+    let _next = Shortcut::new("N", form.as_window(), logger(&log, "next")).unwrap();
+
+    app.press_key(KeyCode::F(5));
+    app.type_text("abc");
+    assert_eq!(edit.text(), "abc");
+    // the char of a key taken by a shortcut goes no further than its release
+    let id = edit.as_window().get_id();
+    let (key, sys, mods) = (KeyCode::Alpha('N'), false, Modifiers::NONE);
+    app.simulate(id, Event::KeyPressed { key, sys, mods });
+    app.simulate(id, Event::KeyReleased { key, sys, mods });
+    app.type_text("d");
+    assert_eq!(edit.text(), "abcd");
+    assert_eq!(*log.borrow(), ["reload F5", "next N"]);
+}
+
+#[test]
+fn window_context_follows_the_focus() {
+    let app = Application::new(true);
+    let form = form();
+    let other = common::form();
+    let (_recorder, log) = recorder(other.as_window());
+    let mut save = Shortcut::new("Ctrl+S", form.as_window(), logger(&log, "save")).unwrap();
+    assert_eq!(save.context(), ShortcutContext::Window);
+
+    // the focus is in the other form
+    ctrl(&app, other.as_window(), KeyCode::Alpha('S'));
+    assert_eq!(*log.borrow(), ["Ctrl+S"]);
+    save.set_context(ShortcutContext::Application).unwrap();
+    ctrl(&app, other.as_window(), KeyCode::Alpha('S'));
+    assert_eq!(*log.borrow(), ["Ctrl+S", "save Ctrl+S"]);
+
+    // a hidden window has no active shortcuts
+    form.as_window().hide();
+    ctrl(&app, other.as_window(), KeyCode::Alpha('S'));
+    assert_eq!(log.borrow().last().unwrap(), "Ctrl+S");
+}
+
+#[test]
+fn sequences_of_chords() {
+    let app = Application::new(true);
+    let form = form();
+    let (_recorder, log) = recorder(form.as_window());
+    let keys = "Ctrl+K, Ctrl+C";
+    let _comment = Shortcut::new(keys, form.as_window(), logger(&log, "comment")).unwrap();
+    let _save = Shortcut::new("Ctrl+S", form.as_window(), logger(&log, "save")).unwrap();
+
+    ctrl(&app, form.as_window(), KeyCode::Alpha('K'));
+    assert!(log.borrow().is_empty());
+    ctrl(&app, form.as_window(), KeyCode::Alpha('C'));
+    assert_eq!(*log.borrow(), ["comment Ctrl+K, Ctrl+C"]);
+
+    // the second chord alone is not the shortcut
+    log.borrow_mut().clear();
+    ctrl(&app, form.as_window(), KeyCode::Alpha('C'));
+    assert_eq!(*log.borrow(), ["Ctrl+C"]);
+
+    // a chord that breaks the sequence goes on as usual, or starts another shortcut
+    log.borrow_mut().clear();
+    ctrl(&app, form.as_window(), KeyCode::Alpha('K'));
+    app.press_key(KeyCode::Alpha('X'));
+    ctrl(&app, form.as_window(), KeyCode::Alpha('K'));
+    ctrl(&app, form.as_window(), KeyCode::Alpha('S'));
+    assert_eq!(*log.borrow(), ["X", "save Ctrl+S"]);
+}
+
+#[test]
+fn conflicts_are_refused() {
+    let _app = Application::new(true);
+    let form = form();
+    let panel = Block::create(rect!(0, 0, 200, 100), Some(form.as_window()));
+    let other = common::form();
+    let log = Log::default();
+    let save = Shortcut::new("Ctrl+S", form.as_window(), logger(&log, "save")).unwrap();
+    let conflict = Err(ShortcutError::Conflict(save.keys().clone()));
+
+    // the same keys anywhere in the top-level window
+    let again = Shortcut::new("ctrl+s", panel.as_window(), logger(&log, "again"));
+    assert_eq!(again.err(), conflict.clone().err());
+    // a sequence that starts with the keys
+    let longer = Shortcut::new("Ctrl+S, A", form.as_window(), logger(&log, "longer"));
+    assert_eq!(longer.err(), conflict.clone().err());
+
+    // another top-level window may use them, but not with the application context
+    let mut elsewhere = Shortcut::new("Ctrl+S", other.as_window(), logger(&log, "other")).unwrap();
+    assert_eq!(
+        elsewhere.set_context(ShortcutContext::Application),
+        conflict
+    );
+    assert_eq!(elsewhere.context(), ShortcutContext::Window);
+
+    // dropping a shortcut frees its keys
+    drop(save);
+    assert_eq!(elsewhere.set_context(ShortcutContext::Application), Ok(()));
+}
@@ -0,0 +1,192 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{events, form, Log, Recorder};
+
+/// Create a recorder of the events other than the focus events at `rect` in `parent`.
+/// It takes the focus when it is clicked. It is hidden.
+fn recorder(rect: Rect, parent: &Window) -> (Widget<Recorder>, Log) {
+    let log = Log::default();
+    let records = |event: &Event| !matches!(event, Event::FocusIn | Event::FocusOut);
+    let mut widget = Recorder::create("Recorder", rect, parent, &log, records);
+    widget.as_window_mut().set_focus_policy(FocusPolicy::Click);
+    log.borrow_mut().clear();
+    (widget, log)
+}
+
+#[test]
+fn line_edit_enter() {
+    let app = Application::new(true);
+    let form = form();
+    let mut edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    edit.as_window().show();
+    let entered = Rc::new(RefCell::new(None));
+    let sink = entered.clone();
+    edit.enter.add(
+        "test",
+        Responder::new(move |text: &String| *sink.borrow_mut() = Some(text.clone())),
+    );
+
+    app.click(pos!(130, 140));
+    assert!(edit.as_window().has_focus());
+    app.type_text("Ada");
+    app.press_key(KeyCode::Backspace);
+    app.press_key(KeyCode::Alpha('A'));
+    app.press_key(KeyCode::Enter);
+    assert_eq!(entered.borrow().as_deref(), Some("Ada"));
+}
+
+#[test]
+fn push_button_press() {
+    let app = Application::new(true);
+    let form = form();
+    let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+    button.as_window().show();
+    let presses = Rc::new(RefCell::new(Vec::new()));
+    let sink = presses.clone();
+    button.press.add(
+        "test",
+        Responder::new(move |down: &bool| sink.borrow_mut().push(*down)),
+    );
+
+    app.click(pos!(130, 130));
+    assert_eq!(*presses.borrow(), vec![true, false]);
+    // the click misses the button
+    app.click(pos!(300, 300));
+    assert_eq!(*presses.borrow(), vec![true, false]);
+}
+
+#[test]
+fn hover_and_leave() {
+    let app = Application::new(true);
+    let form = form();
+    let (a, log_a) = recorder(rect!(0, 0, 50, 50), form.as_window());
+    let (b, log_b) = recorder(rect!(60, 0, 50, 50), form.as_window());
+    a.as_window().show();
+    b.as_window().show();
+
+    app.move_mouse(pos!(110, 110));
+    app.move_mouse(pos!(120, 120));
+    app.move_mouse(pos!(170, 110));
+    let mods = Modifiers::NONE;
+    assert_eq!(
+        events(&log_a),
+        vec![
+            Event::Hover { pos: pos!(10, 10), mods },
+            Event::MouseMoved { pos: pos!(10, 10), mods },
+            Event::MouseMoved { pos: pos!(20, 20), mods },
+            Event::Leave,
+        ]
+    );
+    assert_eq!(
+        events(&log_b),
+        vec![
+            Event::Hover { pos: pos!(10, 10), mods },
+            Event::MouseMoved { pos: pos!(10, 10), mods },
+        ]
+    );
+
+    // a simulated mouse event moves the hover too
+    let press = Event::MouseButtonPressed {
+        button: MouseButton::Right,
+        pos: pos!(1, 2),
+        mods,
+    };
+    app.simulate(a.as_window().get_id(), press);
+    assert_eq!(events(&log_b).last(), Some(&Event::Leave));
+    assert_eq!(
+        events(&log_a)[4..],
+        [Event::Hover { pos: pos!(1, 2), mods }, press]
+    );
+}
+
+#[test]
+fn keys_go_to_focus() {
+    let app = Application::new(true);
+    let form = form();
+    let (a, log_a) = recorder(rect!(0, 0, 50, 50), form.as_window());
+    let (b, log_b) = recorder(rect!(60, 0, 50, 50), form.as_window());
+    a.as_window().show();
+    b.as_window().show();
+
+    b.as_window().set_focus();
+    let mods = Modifiers::NONE;
+    app.press_key(KeyCode::Alpha('Q'));
+    app.press_key(KeyCode::Left);
+    assert!(log_a.borrow().is_empty());
+    assert_eq!(
+        events(&log_b),
+        vec![
+            Event::KeyPressed { key: KeyCode::Alpha('Q'), sys: false, mods },
+            Event::Input { ch: 'q' },
+            Event::KeyReleased { key: KeyCode::Alpha('Q'), sys: false, mods },
+            Event::KeyPressed { key: KeyCode::Left, sys: false, mods },
+            Event::KeyReleased { key: KeyCode::Left, sys: false, mods },
+        ]
+    );
+
+    // clicking `a` moves the focus to it
+    app.click(pos!(110, 110));
+    app.type_text("x");
+    assert_eq!(events(&log_a).last(), Some(&Event::Input { ch: 'x' }));
+}
+
+#[test]
+fn held_modifiers_combine() {
+    let app = Application::new(true);
+    let form = form();
+    let (a, log) = recorder(rect!(0, 0, 50, 50), form.as_window());
+    a.as_window().show();
+    a.as_window().set_focus();
+    let window = a.as_window().get_id();
+    let hold = |key, mods| {
+        let sys = false;
+        app.simulate(window, Event::KeyPressed { key, sys, mods });
+    };
+    hold(KeyCode::LeftCtrl, Modifiers::CTRL);
+    hold(KeyCode::Shift, Modifiers::CTRL | Modifiers::SHIFT);
+    log.borrow_mut().clear();
+
+    // both keys are told with the key and with the click
+    app.press_key(KeyCode::Alpha('S'));
+    app.click(pos!(110, 110));
+    let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+    let mods = |pressed: fn(&Event) -> bool| {
+        let events = events(&log);
+        events.iter().find(|event| pressed(event)).and_then(Event::modifiers)
+    };
+    let key = mods(|event| matches!(event, Event::KeyPressed { .. }));
+    let click = mods(|event| matches!(event, Event::MouseButtonPressed { .. }));
+    assert_eq!(key, Some(ctrl_shift));
+    assert_eq!(click, Some(ctrl_shift | Modifiers::LEFT_BUTTON));
+    let click = click.unwrap();
+    assert!(click.ctrl() && click.shift() && !click.alt());
+    assert_eq!(click.keys(), ctrl_shift);
+    assert_eq!(format!("{:?}", click), "SHIFT | CTRL | LEFT_BUTTON");
+
+    let sys = false;
+    let mods = Modifiers::SHIFT;
+    app.simulate(window, Event::KeyReleased { key: KeyCode::LeftCtrl, sys, mods });
+    app.press_key(KeyCode::Alpha('S'));
+    assert_eq!(events(&log).last().and_then(Event::modifiers), Some(Modifiers::SHIFT));
+}
+
+#[test]
+fn disabled_and_hidden_windows_get_no_clicks() {
+    let app = Application::new(true);
+    let form = form();
+    let (a, log_a) = recorder(rect!(0, 0, 50, 50), form.as_window());
+    let (_b, log_b) = recorder(rect!(60, 0, 50, 50), form.as_window());
+    a.as_window().show();
+    a.as_window().disable();
+    log_a.borrow_mut().clear();
+
+    app.click(pos!(110, 110));
+    app.click(pos!(170, 110));
+    assert!(log_a.borrow().is_empty());
+    assert!(log_b.borrow().is_empty());
+}
@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use rusty_gui::*;
+
+fn references() -> Snapshot {
+    Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshot/references"))
+}
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rusty_gui_snapshot_{}", name));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn left_click() -> Event {
+    Event::MouseButtonPressed {
+        button: MouseButton::Left,
+        pos: pos!(10, 10),
+        mods: Modifiers::NONE,
+    }
+}
+
+#[test]
+fn push_button() {
+    let form = Block::create(rect!(100, 100, 400, 300), None);
+    let mut button = PushButton::create("OK", rect!(20, 20, 120, 40), form.as_window());
+    references().assert_widget("push_button", &mut button, &[]);
+    references().assert_widget("push_button_pressed", &mut button, &[left_click()]);
+    let release = Event::MouseButtonReleased {
+        button: MouseButton::Left,
+        pos: pos!(10, 10),
+        mods: Modifiers::NONE,
+    };
+    button.as_window().set_focus();
+    references().assert_widget("push_button_focused", &mut button, &[release]);
+}
+
+#[test]
+fn switch() {
+    let form = Block::create(rect!(100, 100, 400, 300), None);
+    let mut switch = Switch::create("Wi-Fi", rect!(20, 20, 120, 40), form.as_window());
+    let hover = Event::Hover {
+        pos: pos!(10, 10),
+        mods: Modifiers::NONE,
+    };
+    references().assert_widget("switch_hover", &mut switch, &[hover]);
+    references().assert_widget("switch_on", &mut switch, &[left_click()]);
+}
+
+#[test]
+fn line_edit() {
+    let form = Block::create(rect!(100, 100, 400, 300), None);
+    let mut edit = LineEdit::create("Name", rect!(20, 20, 200, 40), form.as_window());
+    references().assert_widget("line_edit_placeholder", &mut edit, &[]);
+    let typed: Vec<Event> = "Ada".chars().map(|ch| Event::Input { ch }).collect();
+    references().assert_widget("line_edit_typed", &mut edit, &typed);
+    edit.as_window().set_focus();
+    references().assert_widget("line_edit_focused", &mut edit, &[]);
+}
+
+#[test]
+fn missing_reference_saves_actual() {
+    let dir = scratch("missing");
+    let image = Image::new(size!(4, 4), Color::RED);
+    let err = Snapshot::new(&dir).check("red", &image).unwrap_err();
+    assert!(matches!(err, SnapshotError::Missing { .. }));
+    assert_eq!(Image::load_png(dir.join("red.actual.png")).unwrap(), image);
+}
+
+#[test]
+fn tolerance_and_diff_image() {
+    let dir = scratch("tolerance");
+    std::fs::create_dir_all(&dir).unwrap();
+    Image::new(size!(4, 4), rgb!(100)).save_png(dir.join("gray.png")).unwrap();
+
+    let mut image = Image::new(size!(4, 4), rgb!(100));
+    image.set_pixel(pos!(1, 2), rgb!(103));
+    let err = Snapshot::new(&dir).check("gray", &image).unwrap_err();
+    assert!(matches!(err, SnapshotError::Mismatch { pixels: 1, .. }));
+    let diff = Image::load_png(dir.join("gray.diff.png")).unwrap();
+    assert_eq!(diff.pixel(pos!(1, 2)), Some(Color::RED));
+    assert_ne!(diff.pixel(pos!(0, 0)), Some(Color::RED));
+
+    Snapshot::new(&dir).tolerance(3).assert("gray", &image);
+    assert!(!dir.join("gray.diff.png").exists());
+}
+
+#[test]
+fn size_mismatch() {
+    let dir = scratch("size");
+    std::fs::create_dir_all(&dir).unwrap();
+    Image::new(size!(4, 4), Color::WHITE).save_png(dir.join("white.png")).unwrap();
+    let err = Snapshot::new(&dir)
+        .check("white", &Image::new(size!(4, 5), Color::WHITE))
+        .unwrap_err();
+    assert!(matches!(err, SnapshotError::SizeMismatch { .. }));
+}
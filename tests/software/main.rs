@@ -0,0 +1,378 @@
+use rusty_gui::*;
+
+fn canvas() -> Canvas {
+    let canvas = Canvas::offscreen(size!(40, 40));
+    canvas.clear(Color::WHITE);
+    canvas
+}
+
+fn count(image: &Image, color: Color) -> usize {
+    let size = image.size();
+    (0..size.height)
+        .flat_map(|y| (0..size.width).map(move |x| pos!(x, y)))
+        .filter(|p| image.pixel(*p) == Some(color))
+        .count()
+}
+
+fn pen(style: PenStyle) -> Pen {
+    Pen::new(PenStyle {
+        color: Color::RED,
+        ..style
+    })
+}
+
+#[test]
+fn offscreen_starts_transparent() {
+    let image = Canvas::offscreen(size!(3, 2)).into_image().unwrap();
+    assert_eq!(image.size(), size!(3, 2));
+    assert!(image.data().iter().all(|b| *b == 0));
+}
+
+#[test]
+fn line_excludes_last_pixel() {
+    let canvas = canvas();
+    canvas.set_pen(&pen(PenStyle::default()));
+    canvas.line(5, 10, 15, 10);
+    let image = canvas.into_image().unwrap();
+    assert_eq!(image.pixel(pos!(5, 10)), Some(Color::RED));
+    assert_eq!(image.pixel(pos!(14, 10)), Some(Color::RED));
+    assert_eq!(image.pixel(pos!(15, 10)), Some(Color::WHITE));
+    assert_eq!(count(&image, Color::RED), 10);
+}
+
+#[test]
+fn fill_rect_uses_brush_and_pen() {
+    let canvas = canvas();
+    canvas.set_pen(&pen(PenStyle::default()));
+    canvas.set_brush(&Brush::new(Color::BLUE));
+    canvas.fill_rect(rect!(10, 10, 10, 10));
+    let image = canvas.into_image().unwrap();
+    assert_eq!(image.pixel(pos!(10, 10)), Some(Color::RED));
+    assert_eq!(image.pixel(pos!(19, 19)), Some(Color::RED));
+    assert_eq!(image.pixel(pos!(15, 15)), Some(Color::BLUE));
+    assert_eq!(image.pixel(pos!(20, 15)), Some(Color::WHITE));
+    assert_eq!(count(&image, Color::RED), 36);
+    assert_eq!(count(&image, Color::BLUE), 64);
+}
+
+#[test]
+fn null_pen_draws_nothing() {
+    let canvas = canvas();
+    canvas.set_pen(&pen(PenStyle {
+        line_style: LineStyle::Null,
+        ..PenStyle::default()
+    }));
+    canvas.rect(rect!(5, 5, 20, 20));
+    canvas.ellipse(rect!(5, 5, 20, 20));
+    let image = canvas.into_image().unwrap();
+    assert_eq!(count(&image, Color::WHITE), 1600);
+}
+
+#[test]
+fn pen_width_and_caps() {
+    let wide = |cap_style| {
+        let canvas = canvas();
+        canvas.set_pen(&pen(PenStyle {
+            width: 4,
+            cap_style,
+            ..PenStyle::default()
+        }));
+        canvas.line(10, 20, 30, 20);
+        count(&canvas.into_image().unwrap(), Color::RED)
+    };
+    assert_eq!(wide(CapStyle::Flat), 20 * 4);
+    assert_eq!(wide(CapStyle::Square), 24 * 4);
+    let round = wide(CapStyle::Round);
+    assert!(round > 20 * 4 && round < 24 * 4);
+}
+
+#[test]
+fn dashes_leave_gaps() {
+    let canvas = canvas();
+    canvas.set_pen(&pen(PenStyle {
+        line_style: LineStyle::Dash,
+        width: 2,
+        ..PenStyle::default()
+    }));
+    canvas.line(0, 20, 32, 20);
+    let image = canvas.into_image().unwrap();
+    // dashes are 6 pixels long and gaps are 2 pixels long
+    let row: Vec<bool> = (0..32)
+        .map(|x| image.pixel(pos!(x, 20)) == Some(Color::RED))
+        .collect();
+    let expected: Vec<bool> = (0..32).map(|x| x % 8 < 6).collect();
+    assert_eq!(row, expected);
+}
+
+#[test]
+fn miter_and_bevel_joins() {
+    let corner = |join_style| {
+        let canvas = canvas();
+        canvas.set_pen(&pen(PenStyle {
+            width: 6,
+            join_style,
+            ..PenStyle::default()
+        }));
+        canvas.polyline(&[pos!(5, 20), pos!(20, 20), pos!(20, 35)]);
+        canvas.into_image().unwrap()
+    };
+    let miter = corner(JoinStyle::Miter);
+    let bevel = corner(JoinStyle::Bevel);
+    assert_eq!(miter.pixel(pos!(22, 17)), Some(Color::RED));
+    assert_eq!(bevel.pixel(pos!(22, 17)), Some(Color::WHITE));
+    assert!(count(&miter, Color::RED) > count(&bevel, Color::RED));
+}
+
+#[test]
+fn fill_circle_is_symmetric() {
+    let canvas = canvas();
+    canvas.set_pen(&pen(PenStyle {
+        line_style: LineStyle::Null,
+        ..PenStyle::default()
+    }));
+    canvas.set_brush(&Brush::new(Color::GREEN));
+    canvas.fill_circle(pos!(20, 20), 10);
+    let image = canvas.into_image().unwrap();
+    assert_eq!(image.pixel(pos!(20, 20)), Some(Color::GREEN));
+    assert_eq!(image.pixel(pos!(11, 11)), Some(Color::WHITE));
+    for y in 0..40 {
+        for x in 0..40 {
+            assert_eq!(image.pixel(pos!(x, y)), image.pixel(pos!(39 - x, y)));
+            assert_eq!(image.pixel(pos!(x, y)), image.pixel(pos!(x, 39 - y)));
+        }
+    }
+}
+
+#[test]
+fn fill_polygon_alternates() {
+    let canvas = canvas();
+    canvas.set_brush(&Brush::new(Color::BLUE));
+    canvas.set_pen(&pen(PenStyle {
+        line_style: LineStyle::Null,
+        ..PenStyle::default()
+    }));
+    // a pentagram: the center is filled twice, so it stays empty
+    let star = [
+        pos!(20, 2),
+        pos!(31, 36),
+        pos!(2, 14),
+        pos!(38, 14),
+        pos!(9, 36),
+    ];
+    canvas.fill_polygon(&star);
+    let image = canvas.into_image().unwrap();
+    assert_eq!(image.pixel(pos!(20, 20)), Some(Color::WHITE));
+    assert_eq!(image.pixel(pos!(20, 8)), Some(Color::BLUE));
+}
+
+#[test]
+fn set_pen_returns_previous() {
+    let canvas = canvas();
+    let red = pen(PenStyle::default());
+    let old = canvas.set_pen(&red);
+    assert_eq!(old.style(), PenStyle::default());
+    assert_eq!(canvas.set_pen(&old).style().color, Color::RED);
+}
+
+#[test]
+fn text_is_drawn_and_aligned() {
+    let canvas = canvas();
+    canvas.set_text_color(Color::BLUE);
+    canvas.rect_text(rect!(0, 0, 40, 40), "Hi", TextAlign::Center);
+    let image = canvas.into_image().unwrap();
+    let blue: Vec<Point> = (0..40)
+        .flat_map(|y| (0..40).map(move |x| pos!(x, y)))
+        .filter(|p| image.pixel(*p) == Some(Color::BLUE))
+        .collect();
+    assert!(!blue.is_empty());
+    // "Hi" takes 16x16 pixels in the default font
+    assert!(blue.iter().all(|p| p.within(&rect!(12, 12, 16, 16))));
+}
+
+#[test]
+fn text_is_clipped_to_rect() {
+    let canvas = canvas();
+    canvas.set_text_color(Color::BLUE);
+    canvas.rect_text(rect!(0, 0, 8, 40), "MMMM", TextAlign::LeftTop);
+    let image = canvas.into_image().unwrap();
+    for y in 0..40 {
+        for x in 8..40 {
+            assert_eq!(image.pixel(pos!(x, y)), Some(Color::WHITE));
+        }
+    }
+    assert!(count(&image, Color::BLUE) > 0);
+}
+
+/// Get the smallest rectangle holding the pixels of `color`.
+fn bounds(image: &Image, color: Color) -> Rect {
+    let size = image.size();
+    let points: Vec<Point> = (0..size.height)
+        .flat_map(|y| (0..size.width).map(move |x| pos!(x, y)))
+        .filter(|p| image.pixel(*p) == Some(color))
+        .collect();
+    let left = points.iter().map(|p| p.x).min().unwrap();
+    let top = points.iter().map(|p| p.y).min().unwrap();
+    let right = points.iter().map(|p| p.x).max().unwrap();
+    let bottom = points.iter().map(|p| p.y).max().unwrap();
+    rect!(left, top, right - left + 1, bottom - top + 1)
+}
+
+#[test]
+fn point_text_is_aligned_around_pos() {
+    let aligns = [
+        (TextAlign::LeftTop, 0, 0),
+        (TextAlign::LeftMiddle, 0, 8),
+        (TextAlign::LeftBottom, 0, 16),
+        (TextAlign::CenterTop, 8, 0),
+        (TextAlign::Center, 8, 8),
+        (TextAlign::CenterBottom, 8, 16),
+        (TextAlign::RightTop, 16, 0),
+        (TextAlign::RightMiddle, 16, 8),
+        (TextAlign::RightBottom, 16, 16),
+    ];
+    for (align, dx, dy) in aligns {
+        let canvas = canvas();
+        canvas.set_text_color(Color::BLUE);
+        canvas.xy_text(pos!(20, 20), "Hi", align);
+        let image = canvas.into_image().unwrap();
+        // "Hi" takes 16x16 pixels in the default font
+        let expected = rect!(20 - dx, 20 - dy, 16, 16);
+        let drawn = bounds(&image, Color::BLUE);
+        assert!((drawn & expected) == Some(drawn), "{:?}: {:?}", align, drawn);
+    }
+}
+
+#[test]
+fn text_is_broken_into_lines() {
+    let canvas = canvas();
+    let plain = TextFormat::default();
+    assert_eq!(canvas.formatted_text_extent("ab\ncd", None, &plain), size!(16, 32));
+    assert_eq!(canvas.formatted_text_extent("aaa bbb", Some(40), &plain), size!(56, 16));
+
+    let wrapped = TextFormat {
+        wrap: true,
+        ..plain
+    };
+    assert_eq!(canvas.formatted_text_extent("aaa bbb", Some(40), &wrapped), size!(24, 32));
+    // a word wider than the line is broken
+    assert_eq!(canvas.formatted_text_extent("aaaaaaa", Some(40), &wrapped), size!(40, 32));
+    let spaced = TextFormat {
+        line_spacing: 4,
+        ..wrapped
+    };
+    assert_eq!(canvas.formatted_text_extent("aaa bbb ccc", Some(40), &spaced), size!(24, 56));
+
+    canvas.set_text_color(Color::BLUE);
+    canvas.xy_text(pos!(20, 20), "Hi\nHi", TextAlign::Center);
+    let image = canvas.into_image().unwrap();
+    let drawn = bounds(&image, Color::BLUE);
+    assert!(drawn.top() >= 4 && drawn.bottom() <= 36, "{:?}", drawn);
+    assert!(drawn.bottom() > 24);
+}
+
+#[test]
+fn long_text_ends_with_ellipsis() {
+    let canvas = canvas();
+    let format = TextFormat {
+        ellipsis: true,
+        ..TextFormat::default()
+    };
+    // "Long label" is 80 pixels wide, "Long…" fits in 40
+    assert_eq!(canvas.formatted_text_extent("Long label", Some(40), &format), size!(40, 16));
+    assert_eq!(canvas.formatted_text_extent("Long", Some(40), &format), size!(32, 16));
+
+    // the lines below the rectangle are cut, and the last shown line gets the ellipsis
+    canvas.set_text_color(Color::BLUE);
+    let format = TextFormat {
+        wrap: true,
+        ..format
+    };
+    canvas.formatted_text(rect!(0, 0, 40, 20), "aaa bbb ccc", &format);
+    let image = canvas.into_image().unwrap();
+    let drawn = bounds(&image, Color::BLUE);
+    assert!(drawn.bottom() <= 16, "{:?}", drawn);
+    let dots = (24..32).filter(|&x| image.pixel(pos!(x, 10)) == Some(Color::BLUE));
+    assert_eq!(dots.count(), 3);
+}
+
+struct Badge {
+    this: Window,
+}
+
+default_as_window!(Badge);
+
+impl Drawable for Badge {
+    fn draw(&mut self, canvas: &mut Canvas) {
+        let rect = self.this.rect();
+        canvas.clear(Color::WHITE);
+        canvas.set_brush(&Brush::new(Color::BLUE));
+        canvas.fill_rect(rect!(0, 0, rect.size.width / 2, rect.size.height));
+    }
+}
+
+impl EventListener for Badge {
+    fn on_event(&mut self, _event: &Event) -> EventResult {
+        EventResult::Ignored
+    }
+}
+
+#[test]
+fn widget_renders_offscreen_at_size() {
+    let mut badge = Widget::new(
+        "badge",
+        rect!(0, 0, 10, 10),
+        None,
+        Badge {
+            this: Window::default(),
+        },
+    );
+    let image = badge.render_to_image(size!(40, 20));
+    assert_eq!(image.size(), size!(40, 20));
+    assert_eq!(badge.as_window().rect().size, size!(40, 20));
+    assert_eq!(image.pixel(pos!(10, 10)), Some(Color::BLUE));
+    assert_eq!(image.pixel(pos!(30, 10)), Some(Color::WHITE));
+}
+
+#[test]
+fn image_encodes_as_png() {
+    let mut image = Image::new(size!(3, 2), Color::WHITE);
+    image.set_pixel(pos!(2, 1), rgb!(10, 20, 30, 40));
+    let mut bytes = Vec::new();
+    image.write_png(&mut bytes).unwrap();
+    assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+    let decoder = png::Decoder::new(bytes.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height), (3, 2));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(&data[..info.buffer_size()], image.data());
+}
+
+#[test]
+fn text_is_measured_with_the_font() {
+    let font = Font::new(FontStyle {
+        size: 32,
+        ..Default::default()
+    });
+    let metrics = font.metrics();
+    assert_eq!(metrics.line_height, 32);
+    assert_eq!(metrics.ascent + metrics.descent, 32);
+    assert_eq!(font.advance('W'), 16);
+    assert_eq!(font.measure("Hello"), size!(80, 32));
+
+    let canvas = canvas();
+    canvas.set_font(&font);
+    assert_eq!(canvas.text_extent("Hi"), font.measure("Hi"));
+}
+
+#[test]
+fn underscore_is_a_bar_under_the_letters() {
+    let canvas = canvas();
+    canvas.set_text_color(Color::BLUE);
+    canvas.rect_text(rect!(0, 0, 40, 40), "__", TextAlign::LeftTop);
+    let image = canvas.into_image().unwrap();
+    // a row below the baseline, across both chars
+    assert_eq!(bounds(&image, Color::BLUE), rect!(0, 13, 16, 1));
+}
@@ -0,0 +1,260 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::form;
+
+/// A text edit at (20, 20) in a form at (100, 100).
+/// The font is 12 pixels wide and 24 pixels high, and the text starts 5 pixels in,
+/// so 15 chars fit in a line and 5 lines fit in the widget.
+fn text_edit() -> (Widget<Block>, Widget<TextEdit>) {
+    let form = form();
+    let edit = TextEdit::create(rect!(20, 20, 190, 130), form.as_window());
+    edit.as_window().show();
+    (form, edit)
+}
+
+/// Get the position on the screen of the char at `column` in the visible `row`.
+fn char_pos(row: i32, column: i32) -> Point {
+    pos!(125 + column * 12 + 2, 125 + row * 24 + 12)
+}
+
+/// Press `key` in `window`, which has the focus, while holding Shift.
+fn shifted(app: &Application, window: WindowID, key: KeyCode) {
+    let shift = KeyCode::Shift;
+    app.simulate(
+        window,
+        Event::KeyPressed {
+            key: shift,
+            sys: false,
+            mods: Modifiers::SHIFT,
+        },
+    );
+    app.press_key(key);
+    app.simulate(
+        window,
+        Event::KeyReleased {
+            key: shift,
+            sys: false,
+            mods: Modifiers::NONE,
+        },
+    );
+}
+
+#[test]
+fn typing_and_caret_movement() {
+    let app = Application::new(true);
+    let (_form, edit) = text_edit();
+    app.click(char_pos(0, 0));
+    app.type_text("hello\nworld");
+    assert_eq!(edit.text(), "hello\nworld");
+    assert_eq!(edit.caret(), 11);
+
+    app.press_key(KeyCode::Up);
+    assert_eq!(edit.caret(), 5);
+    app.press_key(KeyCode::Left);
+    app.press_key(KeyCode::Left);
+    app.press_key(KeyCode::Down);
+    assert_eq!(edit.caret(), 9);
+    app.press_key(KeyCode::Home);
+    assert_eq!(edit.caret(), 6);
+    app.press_key(KeyCode::End);
+    assert_eq!(edit.caret(), 11);
+
+    app.press_key(KeyCode::Backspace);
+    app.press_key(KeyCode::Home);
+    app.press_key(KeyCode::Delete);
+    assert_eq!(edit.text(), "hello\norl");
+}
+
+#[test]
+fn shift_selection() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    edit.set_text("hello\nworld");
+    edit.as_window().set_focus();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let sink = changes.clone();
+    edit.selection_changed.add(
+        "test",
+        Responder::new(move |selection: &(usize, usize)| sink.borrow_mut().push(*selection)),
+    );
+
+    let window = edit.as_window().get_id();
+    shifted(&app, window, KeyCode::Home);
+    assert_eq!(edit.selected_text(), "world");
+    shifted(&app, window, KeyCode::Up);
+    assert_eq!(edit.selection(), (0, 11));
+    assert_eq!(*changes.borrow(), vec![(6, 11), (0, 11)]);
+
+    // typing replaces the selection, and an arrow key collapses it
+    app.type_text("hi");
+    assert_eq!(edit.text(), "hi");
+    edit.set_selection(0, 1);
+    app.press_key(KeyCode::Right);
+    assert_eq!(edit.selection(), (1, 1));
+}
+
+#[test]
+fn mouse_places_caret_and_selects() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    edit.set_text("hello\nworld");
+    app.click(char_pos(1, 3));
+    assert!(edit.as_window().has_focus());
+    assert_eq!(edit.caret(), 9);
+
+    let window = edit.as_window().get_id();
+    let button = MouseButton::Left;
+    let (pos, mods) = (pos!(5 + 12, 5 + 12), Modifiers::SHIFT);
+    app.simulate(window, Event::MouseButtonPressed { button, pos, mods });
+    app.simulate(window, Event::MouseButtonReleased { button, pos, mods });
+    assert_eq!(edit.selected_text(), "ello\nwor");
+
+    // dragging selects from where the button was pressed
+    let mods = Modifiers::NONE;
+    app.simulate(window, Event::MouseButtonPressed { button, pos, mods });
+    let pos = pos!(5 + 36, 5 + 12);
+    app.simulate(window, Event::MouseMoved { pos, mods });
+    app.simulate(window, Event::MouseButtonReleased { button, pos, mods });
+    assert_eq!(edit.selected_text(), "el");
+}
+
+#[test]
+fn caret_moves_in_visual_order() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    // "ab " then the Hebrew word, shown reversed
+    edit.set_text("ab אבג\nxy");
+    app.click(char_pos(0, 0));
+    let mut carets = Vec::new();
+    for _ in 0..7 {
+        app.press_key(KeyCode::Right);
+        carets.push(edit.caret());
+    }
+    // the right of the Hebrew word is the end of the line, and the next step is the next line
+    assert_eq!(carets, [1, 2, 3, 7, 5, 9, 10]);
+
+    app.click(char_pos(0, 4));
+    assert_eq!(edit.caret(), 7);
+    shifted(&app, edit.as_window().get_id(), KeyCode::Left);
+    assert_eq!(edit.selected_text(), "אב");
+    // typing between the first two Hebrew letters
+    app.click(char_pos(0, 5));
+    app.type_text("ד");
+    assert_eq!(edit.text(), "ab אדבג\nxy");
+}
+
+#[test]
+fn touchpad_scrolling() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    let text: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+    edit.set_text(&text.join("\n"));
+    edit.scroll_to_line(0);
+
+    // the fractions of a line add up
+    let pos = char_pos(0, 0);
+    app.scroll(pos, ScrollDelta::pixels(0.0, 12.0));
+    assert_eq!(edit.first_visible_line(), 0);
+    app.scroll(pos, ScrollDelta::pixels(0.0, 12.0));
+    assert_eq!(edit.first_visible_line(), 1);
+    app.scroll(pos, ScrollDelta::lines(0.0, -0.5));
+    app.scroll(pos, ScrollDelta::lines(0.0, -0.75));
+    assert_eq!(edit.first_visible_line(), 0);
+    // scrolling sideways does not move the lines
+    app.scroll(pos, ScrollDelta::lines(2.0, 0.0));
+    assert_eq!(edit.first_visible_line(), 0);
+}
+
+#[test]
+fn word_wrap() {
+    let _app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    edit.set_text("aaaa bbbb cccc dddd eeeeeeeeeeeeeeeeeeee");
+    assert_eq!(edit.line_count(), 4);
+    edit.set_caret(14);
+    edit.set_word_wrap(false);
+    assert_eq!(edit.line_count(), 1);
+    edit.set_word_wrap(true);
+    assert_eq!(edit.line_count(), 4);
+
+    // the lines follow the width of the widget and the content
+    edit.as_window().set_size(size!(370, 130));
+    assert_eq!(edit.line_count(), 2);
+    edit.set_text("aaaa");
+    assert_eq!(edit.line_count(), 1);
+}
+
+#[test]
+fn vertical_scrolling() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    let text: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+    edit.set_text(&text.join("\n"));
+    assert_eq!(edit.first_visible_line(), 5);
+
+    edit.as_window().set_focus();
+    app.press_key(KeyCode::PageUp);
+    assert_eq!(edit.caret(), "line 0\nline 1\nline 2\nline 3\nline 4".len());
+    assert_eq!(edit.first_visible_line(), 4);
+    app.press_key(KeyCode::PageUp);
+    assert_eq!(edit.caret(), "line 0".len());
+    assert_eq!(edit.first_visible_line(), 0);
+    app.press_key(KeyCode::PageUp);
+    assert_eq!(edit.caret(), 0);
+
+    let pos = char_pos(0, 0);
+    app.scroll(pos, ScrollDelta::lines(0.0, 3.0));
+    assert_eq!(edit.first_visible_line(), 3);
+    app.scroll(pos, ScrollDelta::lines(0.0, 3.0));
+    assert_eq!(edit.first_visible_line(), 5);
+
+    // clicking a scrolled line places the caret in it
+    app.click(char_pos(0, 5));
+    assert_eq!(
+        edit.caret(),
+        "line 0\nline 1\nline 2\nline 3\nline 4\nline ".len()
+    );
+}
+
+#[test]
+fn undo_typing() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    app.click(char_pos(0, 0));
+    app.type_text("one\ntwo");
+    edit.set_selection(0, 3);
+    app.type_text("1");
+    assert_eq!(edit.text(), "1\ntwo");
+
+    edit.undo();
+    assert_eq!(edit.text(), "one\ntwo");
+    assert_eq!(edit.selected_text(), "one");
+    edit.undo();
+    assert_eq!(edit.text(), "");
+    assert!(!edit.can_undo());
+    edit.redo();
+    edit.redo();
+    assert_eq!(edit.text(), "1\ntwo");
+}
+
+#[test]
+fn altgr_letters_are_not_shortcuts() {
+    let app = Application::new(true);
+    let (_form, mut edit) = text_edit();
+    edit.set_text("xy");
+    app.click(char_pos(0, 2));
+    let window = edit.as_window().get_id();
+    // AltGr+A types ą on a Polish layout, and is told as Ctrl+Alt+A
+    let key = KeyCode::Alpha('A');
+    let mods = Modifiers::CTRL | Modifiers::ALT;
+    app.simulate(window, Event::KeyPressed { key, sys: false, mods });
+    app.simulate(window, Event::Input { ch: 'ą' });
+    app.simulate(window, Event::KeyReleased { key, sys: false, mods });
+    assert_eq!(edit.selected_text(), "");
+    assert_eq!(edit.text(), "xyą");
+}
@@ -0,0 +1,92 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rusty_gui::*;
+
+/// Appends chars to a list, merging the chars appended one after another.
+struct Append(Vec<char>);
+
+impl Command for Append {
+    type Target = Vec<char>;
+
+    fn undo(&mut self, list: &mut Vec<char>) {
+        list.truncate(list.len() - self.0.len());
+    }
+
+    fn redo(&mut self, list: &mut Vec<char>) {
+        list.extend(&self.0);
+    }
+
+    fn merge(&mut self, next: &Self) -> bool {
+        self.0.extend(&next.0);
+        true
+    }
+}
+
+fn append(stack: &mut UndoStack<Append>, list: &mut Vec<char>, ch: char) {
+    stack.execute(Append(vec![ch]), list);
+}
+
+#[test]
+fn merge_until_sealed() {
+    let mut list = Vec::new();
+    let mut stack = UndoStack::new();
+    append(&mut stack, &mut list, 'a');
+    append(&mut stack, &mut list, 'b');
+    stack.seal();
+    append(&mut stack, &mut list, 'c');
+    assert_eq!(list, ['a', 'b', 'c']);
+
+    assert!(stack.undo(&mut list).is_some());
+    assert_eq!(list, ['a', 'b']);
+    assert!(stack.undo(&mut list).is_some());
+    assert!(list.is_empty());
+    assert!(stack.undo(&mut list).is_none());
+    assert!(stack.redo(&mut list).is_some());
+    assert_eq!(list, ['a', 'b']);
+
+    // a new command drops the undone ones, and is not merged into the last one
+    append(&mut stack, &mut list, 'd');
+    assert!(!stack.can_redo());
+    stack.undo(&mut list);
+    assert_eq!(list, ['a', 'b']);
+}
+
+#[test]
+fn notifiers_and_limit() {
+    let mut list = Vec::new();
+    let mut stack = UndoStack::new();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let sink = log.clone();
+    stack.can_undo_changed.add(
+        "test",
+        Responder::new(move |can: &bool| sink.borrow_mut().push(("undo", *can))),
+    );
+    let sink = log.clone();
+    stack.can_redo_changed.add(
+        "test",
+        Responder::new(move |can: &bool| sink.borrow_mut().push(("redo", *can))),
+    );
+
+    append(&mut stack, &mut list, 'a');
+    append(&mut stack, &mut list, 'b');
+    stack.undo(&mut list);
+    stack.redo(&mut list);
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("undo", true),
+            ("undo", false),
+            ("redo", true),
+            ("undo", true),
+            ("redo", false)
+        ]
+    );
+
+    stack.set_limit(Some(2));
+    for ch in ['c', 'd', 'e'] {
+        stack.seal();
+        append(&mut stack, &mut list, ch);
+    }
+    while stack.undo(&mut list).is_some() {}
+    assert_eq!(list, ['a', 'b', 'c']);
+}